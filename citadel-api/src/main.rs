@@ -9,28 +9,38 @@
 //!   CITADEL_API_KEY           - Bootstrap admin key, plaintext (dev only)
 //!   CITADEL_API_KEY_HASH      - Bootstrap admin key, SHA-256 hex (production)
 //!   CITADEL_SEED_DEMO         - Set to "true" to seed demo keys on first run
+//!   CITADEL_SEED_DEMO_FORCE   - "true" to seed even when the deployment looks like production
+//!   CITADEL_ENV               - "production" refuses demo seeding (see CITADEL_SEED_DEMO_FORCE)
 //!   CITADEL_LOG_FORMAT        - "json" for structured logging, "pretty" for dev
 //!   CITADEL_RATE_LIMIT_RPS    - Requests per second per IP (default: 20)
 //!   CITADEL_RATE_LIMIT_BURST  - Burst capacity per IP (default: 50)
+//!   CITADEL_RATE_LIMIT_<SCOPE>_RPS   - Per-scope override (READ, ENCRYPT, MANAGE, ADMIN)
+//!   CITADEL_RATE_LIMIT_<SCOPE>_BURST - Per-scope override (READ, ENCRYPT, MANAGE, ADMIN)
 //!
 //! API Key Scopes:
-//!   read    - GET endpoints (status, metrics, keys list, threat, policies)
+//!   read    - GET endpoints (status, metrics, keys list, threat, policies, audit)
 //!   encrypt - encrypt/decrypt operations
-//!   manage  - key lifecycle (generate, activate, rotate, revoke, destroy)
+//!   manage  - key lifecycle (generate, activate, rotate, revoke, destroy), policies
 //!   admin   - all of the above + API key management
 //!
 //! Bootstrap:
 //!   On first run, CITADEL_API_KEY or CITADEL_API_KEY_HASH creates the initial
 //!   admin key. After that, manage keys via POST /api/auth/keys.
+//!
+//! Shutdown:
+//!   SIGTERM or Ctrl+C stops accepting connections, drains in-flight requests,
+//!   writes threat assessor state to <data_dir>/threat-state.json (restored on
+//!   next start) and flushes the audit sink.
 
 use axum::{
-    extract::{ConnectInfo, Path, Request, State},
-    http::{header, StatusCode},
+    extract::{ConnectInfo, DefaultBodyLimit, Extension, MatchedPath, Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
     middleware::{self, Next},
     response::{Html, IntoResponse},
     routing::{delete, get, post},
     Json, Router,
 };
+use base64ct::{Base64, Encoding};
 use citadel_keystore::*;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -83,20 +93,24 @@ fn has_scope(granted: &[Scope], required: &Scope) -> bool {
     granted.contains(required)
 }
 
+fn is_health_path(path: &str) -> bool {
+    path == "/health" || path == "/health/live" || path == "/health/ready"
+}
+
 fn required_scope(path: &str, method: &str) -> Option<Scope> {
-    if path == "/" || path == "/health" {
+    if path == "/" || is_health_path(path) {
         return None;
     }
     if path == "/api/auth/whoami" {
         return Some(Scope::Read);
     }
-    if path.starts_with("/api/auth/") {
+    if path.starts_with("/api/auth/") || path == "/api/threat/config" {
         return Some(Scope::Admin);
     }
-    if path.ends_with("/encrypt") || path == "/api/decrypt" {
+    if path.ends_with("/encrypt") || path == "/api/decrypt" || path == "/api/decrypt/bulk" {
         return Some(Scope::Encrypt);
     }
-    if method == "POST" || method == "DELETE" {
+    if method == "POST" || method == "PUT" || method == "DELETE" {
         return Some(Scope::Manage);
     }
     Some(Scope::Read)
@@ -206,6 +220,15 @@ struct AppState {
     api_keys: RwLock<ApiKeyStore>,
     api_keys_path: String,
     rate_limiter: RateLimiter,
+    /// Underlying JSONL log behind the integrity chain, read back by `/api/audit/verify`.
+    audit_log: Arc<FileAuditSink>,
+    /// Most recent chained events, served by `/api/audit`.
+    audit_recent: Arc<RingAuditSink>,
+    /// `Idempotency-Key` (scoped by caller) -> request it was used with.
+    idempotency_keys: std::sync::Mutex<HashMap<String, IdempotencyEntry>>,
+    /// Requests refused by the rate limiter since startup, by
+    /// `"{method} {route}"` (e.g. `"POST /api/keys/:id/encrypt"`).
+    rate_limit_rejections: Mutex<HashMap<String, u64>>,
 }
 
 type Shared = Arc<AppState>;
@@ -214,36 +237,50 @@ type Shared = Arc<AppState>;
 // Rate limiter
 // ---------------------------------------------------------------------------
 
-struct RateLimiter {
-    buckets: Mutex<HashMap<IpAddr, TokenBucket>>,
+/// Token-bucket limit for one scope.
+#[derive(Debug, Clone, Copy)]
+struct RateLimit {
     rps: f64,
     burst: u32,
 }
 
+/// Per-IP, per-scope token buckets. Each scope has its own limit so
+/// encrypt traffic cannot starve reads (and vice versa).
+struct RateLimiter {
+    buckets: Mutex<HashMap<(IpAddr, Scope), TokenBucket>>,
+    limits: HashMap<Scope, RateLimit>,
+    default_limit: RateLimit,
+}
+
 struct TokenBucket {
     tokens: f64,
     last_refill: Instant,
 }
 
 impl RateLimiter {
-    fn new(rps: f64, burst: u32) -> Self {
+    fn new(default_limit: RateLimit, limits: HashMap<Scope, RateLimit>) -> Self {
         Self {
             buckets: Mutex::new(HashMap::new()),
-            rps,
-            burst,
+            limits,
+            default_limit,
         }
     }
 
-    async fn check(&self, ip: IpAddr) -> bool {
+    fn limit_for(&self, scope: &Scope) -> RateLimit {
+        self.limits.get(scope).copied().unwrap_or(self.default_limit)
+    }
+
+    async fn check(&self, ip: IpAddr, scope: Scope) -> bool {
+        let limit = self.limit_for(&scope);
         let mut buckets = self.buckets.lock().await;
         let now = Instant::now();
-        let bucket = buckets.entry(ip).or_insert(TokenBucket {
-            tokens: self.burst as f64,
+        let bucket = buckets.entry((ip, scope)).or_insert(TokenBucket {
+            tokens: limit.burst as f64,
             last_refill: now,
         });
 
         let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
-        bucket.tokens = (bucket.tokens + elapsed * self.rps).min(self.burst as f64);
+        bucket.tokens = (bucket.tokens + elapsed * limit.rps).min(limit.burst as f64);
         bucket.last_refill = now;
 
         if bucket.tokens >= 1.0 {
@@ -263,6 +300,84 @@ async fn cleanup_rate_limiter(limiter: &RateLimiter) {
     });
 }
 
+// ---------------------------------------------------------------------------
+// Idempotency keys
+// ---------------------------------------------------------------------------
+
+/// How long a replayed `Idempotency-Key` returns the original key.
+const IDEMPOTENCY_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+const IDEMPOTENCY_KEY_MAX_LEN: usize = 255;
+
+struct IdempotencyEntry {
+    /// SHA-256 of the request the key was first used with.
+    request_hash: [u8; 32],
+    /// `None` while the first request is still generating.
+    key_id: Option<KeyId>,
+    created: Instant,
+}
+
+type IdempotencyKeys = std::sync::Mutex<HashMap<String, IdempotencyEntry>>;
+
+enum IdempotencyClaim<'a> {
+    Reserved(IdempotencyReservation<'a>),
+    Replay(KeyId),
+    InProgress,
+    Mismatch,
+}
+
+/// A pending `Idempotency-Key` entry owned by the request that created it.
+/// Dropping it before `complete` (generation failed, or the client went away
+/// and the handler future was dropped) removes the entry so a retry can
+/// claim the key again instead of getting 409 until the TTL runs out.
+struct IdempotencyReservation<'a> {
+    keys: &'a IdempotencyKeys,
+    key: String,
+}
+
+impl IdempotencyReservation<'_> {
+    /// Records the generated key; later requests with the same key replay it.
+    fn complete(self, id: &KeyId) {
+        if let Some(entry) = self.keys.lock().unwrap().get_mut(&self.key) {
+            entry.key_id = Some(id.clone());
+            entry.created = Instant::now();
+        }
+    }
+}
+
+impl Drop for IdempotencyReservation<'_> {
+    fn drop(&mut self) {
+        let mut keys = self.keys.lock().unwrap_or_else(|e| e.into_inner());
+        if keys.get(&self.key).is_some_and(|e| e.key_id.is_none()) {
+            keys.remove(&self.key);
+        }
+    }
+}
+
+fn idempotency_request_hash(req: &GenerateKeyReq) -> [u8; 32] {
+    let body = serde_json::json!({"name": req.name, "key_type": req.key_type, "policy_id": req.policy_id});
+    Sha256::digest(body.to_string().as_bytes()).into()
+}
+
+/// Looks `key` up and, when it is unused or expired, reserves it with a
+/// pending entry. The map lock is only held for the lookup.
+fn claim_idempotency_key<'a>(keys: &'a IdempotencyKeys, key: &str, request_hash: [u8; 32]) -> IdempotencyClaim<'a> {
+    let mut map = keys.lock().unwrap();
+    if let Some(entry) = map.get(key).filter(|e| e.created.elapsed() < IDEMPOTENCY_TTL) {
+        return match &entry.key_id {
+            _ if entry.request_hash != request_hash => IdempotencyClaim::Mismatch,
+            Some(id) => IdempotencyClaim::Replay(id.clone()),
+            None => IdempotencyClaim::InProgress,
+        };
+    }
+    map.insert(key.to_string(), IdempotencyEntry { request_hash, key_id: None, created: Instant::now() });
+    IdempotencyClaim::Reserved(IdempotencyReservation { keys, key: key.to_string() })
+}
+
+fn cleanup_idempotency_keys(keys: &IdempotencyKeys) {
+    keys.lock().unwrap().retain(|_, entry| entry.created.elapsed() < IDEMPOTENCY_TTL);
+}
+
 // ---------------------------------------------------------------------------
 // Crypto utilities
 // ---------------------------------------------------------------------------
@@ -296,6 +411,13 @@ struct AuthContext {
     scopes: Vec<Scope>,
 }
 
+/// Keystore handle that attributes audit events to the calling API key
+/// (or `"anonymous"` in dev mode, when no keys are configured).
+fn keystore_for<'a>(state: &'a AppState, auth: &Option<Extension<AuthContext>>) -> ActingKeystore<'a> {
+    let actor = auth.as_ref().map(|Extension(ctx)| ctx.key_id.clone()).unwrap_or_else(|| "anonymous".into());
+    state.keystore.with_actor(actor)
+}
+
 // ---------------------------------------------------------------------------
 // Rate limiting middleware
 // ---------------------------------------------------------------------------
@@ -306,20 +428,31 @@ async fn rate_limit_middleware(
     req: Request,
     next: Next,
 ) -> impl IntoResponse {
-    if req.uri().path() == "/health" {
+    if is_health_path(req.uri().path()) {
         return next.run(req).await.into_response();
     }
 
-    if !state.rate_limiter.check(addr.ip()).await {
-        state.keystore.record_threat_event(
+    // Unauthenticated paths (the dashboard) share the read bucket.
+    let scope = required_scope(req.uri().path(), req.method().as_str()).unwrap_or(Scope::Read);
+
+    if !state.rate_limiter.check(addr.ip(), scope.clone()).await {
+        // Count by route template rather than raw path, so key IDs don't
+        // make every request its own endpoint.
+        let route = req.extensions().get::<MatchedPath>().map_or("(unmatched)", |p| p.as_str());
+        let endpoint = format!("{} {}", req.method(), route);
+        *state.rate_limit_rejections.lock().await.entry(endpoint.clone()).or_insert(0) += 1;
+        state.keystore.record_observed_threat_event(
             ThreatEvent::new(ThreatEventKind::RapidAccessPattern, 0.3)
-                .with_detail(format!("rate limit exceeded: {}", addr.ip())),
+                .with_detail(format!("rate limit exceeded: {} on {} ({})", addr.ip(), endpoint, scope.as_str())),
+        );
+        tracing::warn!(
+            ip = %addr.ip(), method = %req.method(), path = %req.uri().path(), route = %route,
+            scope = %scope.as_str(), "rate limit exceeded"
         );
-        tracing::warn!(ip = %addr.ip(), path = %req.uri().path(), "rate limit exceeded");
         return (
             StatusCode::TOO_MANY_REQUESTS,
             [(header::RETRY_AFTER, "1")],
-            Json(ApiError { error: "rate limit exceeded".into() }),
+            Json(ApiError::new("RATE_LIMITED", "rate limit exceeded")),
         ).into_response();
     }
 
@@ -370,12 +503,10 @@ async fn auth_middleware(
                         );
                         return (
                             StatusCode::FORBIDDEN,
-                            Json(ApiError {
-                                error: format!(
-                                    "insufficient scope: requires '{}' permission",
-                                    required.as_str()
-                                ),
-                            }),
+                            Json(ApiError::new(
+                                "INSUFFICIENT_SCOPE",
+                                format!("insufficient scope: requires '{}' permission", required.as_str()),
+                            )),
                         ).into_response();
                     }
 
@@ -400,14 +531,14 @@ async fn auth_middleware(
                 }
                 None => {
                     drop(store);
-                    state.keystore.record_threat_event(
+                    state.keystore.record_observed_threat_event(
                         ThreatEvent::new(ThreatEventKind::AuthFailure, 0.5)
                             .with_detail(format!("invalid API key from {}", addr.ip())),
                     );
                     tracing::warn!(ip = %addr.ip(), path = %path, "invalid API key");
                     (
                         StatusCode::UNAUTHORIZED,
-                        Json(ApiError { error: "authentication failed".into() }),
+                        Json(ApiError::new("INVALID_API_KEY", "authentication failed")),
                     ).into_response()
                 }
             }
@@ -416,7 +547,7 @@ async fn auth_middleware(
             drop(store);
             (
                 StatusCode::UNAUTHORIZED,
-                Json(ApiError { error: "missing Authorization header (use: Bearer <api-key>)".into() }),
+                Json(ApiError::new("MISSING_API_KEY", "missing Authorization header (use: Bearer <api-key>)")),
             ).into_response()
         }
     }
@@ -438,6 +569,10 @@ struct EncryptReq {
     plaintext: String,
     aad: String,
     context: String,
+    #[serde(default)]
+    aad_encoding: BindingEncoding,
+    #[serde(default)]
+    context_encoding: BindingEncoding,
 }
 
 #[derive(Deserialize)]
@@ -445,8 +580,56 @@ struct DecryptReq {
     blob: EncryptedBlob,
     aad: String,
     context: String,
+    #[serde(default)]
+    aad_encoding: BindingEncoding,
+    #[serde(default)]
+    context_encoding: BindingEncoding,
+}
+
+/// How a request's `aad` / `context` string maps to bytes. Decrypt must
+/// use the encodings that were used to encrypt.
+#[derive(Deserialize, Default, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum BindingEncoding {
+    #[default]
+    Utf8,
+    Base64,
+    Hex,
+}
+
+impl BindingEncoding {
+    fn decode(self, value: &str) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Utf8 => Ok(value.as_bytes().to_vec()),
+            Self::Base64 => Base64::decode_vec(value).map_err(|e| format!("invalid base64: {}", e)),
+            Self::Hex => hex::decode(value).map_err(|e| format!("invalid hex: {}", e)),
+        }
+    }
+}
+
+fn decode_bindings(
+    aad: &str, aad_encoding: BindingEncoding, context: &str, context_encoding: BindingEncoding,
+) -> Result<(citadel_envelope::Aad, citadel_envelope::Context), String> {
+    let aad = aad_encoding.decode(aad).map_err(|e| format!("aad: {}", e))?;
+    let context = context_encoding.decode(context).map_err(|e| format!("context: {}", e))?;
+    Ok((citadel_envelope::Aad::raw(&aad), citadel_envelope::Context::raw(&context)))
 }
 
+#[derive(Deserialize)]
+struct BulkDecryptReq {
+    items: Vec<DecryptReq>,
+}
+
+/// Upper bound on items per bulk decrypt request.
+const BULK_DECRYPT_MAX_ITEMS: usize = 1000;
+const BULK_ACTIVATE_MAX_ITEMS: usize = 1000;
+
+#[derive(Deserialize)]
+struct BulkActivateReq {
+    ids: Vec<String>,
+}
+const THREAT_EVENTS_MAX_ITEMS: usize = 1000;
+
 #[derive(Deserialize)]
 struct ThreatEventReq {
     kind: String,
@@ -454,11 +637,21 @@ struct ThreatEventReq {
     detail: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct SetTagReq {
+    value: String,
+}
+
 #[derive(Deserialize)]
 struct RevokeReq {
     reason: String,
 }
 
+#[derive(Deserialize)]
+struct CompromiseReq {
+    detail: String,
+}
+
 #[derive(Deserialize)]
 struct CreateApiKeyReq {
     name: String,
@@ -475,8 +668,21 @@ struct StatusResponse {
     active_keys: usize,
 }
 
+/// Body of every JSON error response. `code` is stable and meant for
+/// programmatic branching; the message (serialized as `error`, which
+/// existing clients read) is for humans and may change.
 #[derive(Serialize, Clone)]
-struct ApiError { error: String }
+struct ApiError {
+    code: &'static str,
+    #[serde(rename = "error")]
+    message: String,
+}
+
+impl ApiError {
+    fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
 
 #[derive(Serialize)]
 struct KeyResponse {
@@ -484,12 +690,15 @@ struct KeyResponse {
     name: String,
     key_type: String,
     state: String,
+    can_encrypt: bool,
+    can_decrypt: bool,
     version: u32,
     usage_count: u64,
     created_at: String,
     updated_at: String,
     policy_id: Option<String>,
     parent_id: Option<String>,
+    tags: HashMap<String, String>,
 }
 
 #[derive(Serialize)]
@@ -500,6 +709,14 @@ struct ThreatHistoryEntry {
     reason: String,
 }
 
+#[derive(Serialize)]
+struct ThreatEventEntry {
+    timestamp: String,
+    kind: ThreatEventKind,
+    severity: f64,
+    detail: Option<String>,
+}
+
 #[derive(Serialize)]
 struct PolicyAdaptationResponse {
     policy_name: String,
@@ -515,11 +732,78 @@ struct PolicyAdaptationResponse {
     auto_rotate_forced: bool,
 }
 
-fn err(msg: impl Into<String>) -> (StatusCode, Json<ApiError>) {
-    (StatusCode::BAD_REQUEST, Json(ApiError { error: msg.into() }))
+fn err(code: &'static str, msg: impl Into<String>) -> (StatusCode, Json<ApiError>) {
+    (StatusCode::BAD_REQUEST, Json(ApiError::new(code, msg)))
+}
+fn err500(code: &'static str, msg: impl Into<String>) -> (StatusCode, Json<ApiError>) {
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new(code, msg)))
+}
+
+/// Stable error code for a keystore error.
+fn keystore_error_code(e: &KeystoreError) -> &'static str {
+    match e {
+        KeystoreError::KeyNotFound(_) => "KEY_NOT_FOUND",
+        KeystoreError::VersionNotFound { .. } => "VERSION_NOT_FOUND",
+        KeystoreError::InvalidTransition { .. } => "INVALID_TRANSITION",
+        KeystoreError::PolicyViolation(_) => "POLICY_VIOLATION",
+        KeystoreError::StorageError(_) => "STORAGE_ERROR",
+        KeystoreError::EnvelopeError(_) => "ENVELOPE_ERROR",
+        KeystoreError::DuplicateKey(_) => "DUPLICATE_KEY",
+        KeystoreError::KeyDestroyed(_) => "KEY_DESTROYED",
+        KeystoreError::NotActive(_) => "KEY_NOT_ACTIVE",
+        KeystoreError::NotDecryptable(_) => "KEY_NOT_DECRYPTABLE",
+        KeystoreError::PolicyNotFound(_) => "POLICY_NOT_FOUND",
+        KeystoreError::InvalidInput(_) => "INVALID_INPUT",
+        KeystoreError::ContextNotAllowed(_) => "CONTEXT_NOT_ALLOWED",
+    }
 }
-fn err500(msg: impl Into<String>) -> (StatusCode, Json<ApiError>) {
-    (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError { error: msg.into() }))
+
+/// HTTP status for a keystore error: 404 for a missing key, version or policy,
+/// 409 when the key's state forbids the operation, 403 when a policy
+/// does, 500 for storage and envelope failures, 400 otherwise.
+fn keystore_error_status(e: &KeystoreError) -> StatusCode {
+    match e {
+        KeystoreError::KeyNotFound(_)
+        | KeystoreError::VersionNotFound { .. }
+        | KeystoreError::PolicyNotFound(_) => StatusCode::NOT_FOUND,
+        KeystoreError::InvalidTransition { .. }
+        | KeystoreError::DuplicateKey(_)
+        | KeystoreError::KeyDestroyed(_)
+        | KeystoreError::NotActive(_)
+        | KeystoreError::NotDecryptable(_) => StatusCode::CONFLICT,
+        KeystoreError::PolicyViolation(_) | KeystoreError::ContextNotAllowed(_) => StatusCode::FORBIDDEN,
+        KeystoreError::StorageError(_) | KeystoreError::EnvelopeError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        KeystoreError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+    }
+}
+
+/// Validate a key ID from the path or body. `KeyId::new` accepts anything,
+/// including IDs that would name files outside the key directory.
+fn key_id(id: &str) -> Result<KeyId, (StatusCode, Json<ApiError>)> {
+    KeyId::parse(id).map_err(|e| err("INVALID_KEY_ID", e.to_string()))
+}
+
+fn keystore_err(e: &KeystoreError) -> (StatusCode, Json<ApiError>) {
+    (keystore_error_status(e), Json(ApiError::new(keystore_error_code(e), e.to_string())))
+}
+
+/// A refused encrypt gets the keystore error's status and code; a seal
+/// that fails inside the envelope is a server fault.
+fn encrypt_err(e: &EncryptError) -> (StatusCode, Json<ApiError>) {
+    match e {
+        EncryptError::Keystore(k) => (keystore_error_status(k), Json(ApiError::new(keystore_error_code(k), e.to_string()))),
+        EncryptError::Seal(_) => err500("ENCRYPT_FAILED", e.to_string()),
+    }
+}
+
+/// A refused decrypt gets the keystore error's status and code; a
+/// ciphertext that does not open (wrong key, AAD, context, or tampered)
+/// is the caller's input, so 400.
+fn decrypt_err(e: &DecryptError) -> (StatusCode, Json<ApiError>) {
+    match e {
+        DecryptError::Keystore(k) => (keystore_error_status(k), Json(ApiError::new(keystore_error_code(k), e.to_string()))),
+        DecryptError::Open(_) => err("DECRYPT_FAILED", e.to_string()),
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -544,8 +828,11 @@ fn parse_threat_kind(s: &str) -> Option<ThreatEventKind> {
         "ExternalAdvisory" => Some(ThreatEventKind::ExternalAdvisory),
         "AuthFailure" => Some(ThreatEventKind::AuthFailure),
         "KeyEnumeration" => Some(ThreatEventKind::KeyEnumeration),
+        "KeyCompromise" => Some(ThreatEventKind::KeyCompromise),
+        "PolicyViolation" => Some(ThreatEventKind::PolicyViolation),
         "ManualEscalation" => Some(ThreatEventKind::ManualEscalation),
         "ManualDeescalation" => Some(ThreatEventKind::ManualDeescalation),
+        "Heartbeat" => Some(ThreatEventKind::Heartbeat),
         _ => None,
     }
 }
@@ -555,10 +842,12 @@ fn key_to_response(meta: &KeyMetadata) -> KeyResponse {
     KeyResponse {
         id: meta.id.to_string(), name: meta.name.clone(),
         key_type: format!("{:?}", meta.key_type), state: format!("{}", meta.state),
+        can_encrypt: meta.state.can_encrypt(), can_decrypt: meta.state.can_decrypt(),
         version: ver, usage_count: meta.usage_count,
         created_at: meta.created_at.to_rfc3339(), updated_at: meta.updated_at.to_rfc3339(),
         policy_id: meta.policy_id.as_ref().map(|p| p.as_str().to_string()),
         parent_id: meta.parent_id.as_ref().map(|p| p.to_string()),
+        tags: meta.tags.clone(),
     }
 }
 
@@ -574,107 +863,330 @@ fn lname(level: ThreatLevel) -> &'static str {
 // Routes — crypto key management
 // ---------------------------------------------------------------------------
 
+/// Liveness: the process is up and serving. Also served at `/health`.
 async fn health() -> impl IntoResponse {
     Json(serde_json::json!({"status": "ok", "version": "0.2.0"}))
 }
 
+/// Readiness: storage can list keys and the audit log is writable.
+/// Returns 503 with the failing check otherwise.
+async fn health_ready(State(state): State<Shared>) -> impl IntoResponse {
+    let storage = state.keystore.list_keys().await.map(|_| ()).map_err(|e| e.to_string());
+    let audit = state.audit_log.check_writable().map_err(|e| e.to_string());
+    let check = |r: &Result<(), String>| match r {
+        Ok(()) => serde_json::json!("ok"),
+        Err(e) => serde_json::json!(e),
+    };
+    let ready = storage.is_ok() && audit.is_ok();
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(serde_json::json!({
+        "status": if ready { "ready" } else { "not_ready" },
+        "checks": { "storage": check(&storage), "audit": check(&audit) },
+    })))
+}
+
 async fn get_status(State(state): State<Shared>) -> Json<StatusResponse> {
     let ks = &state.keystore;
     let level = ks.threat_level();
-    let all = ks.list_keys().await.unwrap_or_default();
-    let active = all.iter().filter(|k| k.state == KeyState::Active).count();
+    let counts = ks.key_counts().await.unwrap_or_default();
+    let active = counts.get(&KeyState::Active).copied().unwrap_or(0);
     Json(StatusResponse {
         threat_level: level.value(), threat_name: lname(level), threat_color: level.color(),
-        threat_score: ks.threat_score(), total_keys: all.len(), active_keys: active,
+        threat_score: ks.threat_score(), total_keys: counts.values().sum(), active_keys: active,
     })
 }
 
 async fn get_metrics(State(state): State<Shared>) -> impl IntoResponse {
     match state.keystore.security_metrics().await {
-        Ok(m) => (StatusCode::OK, Json(serde_json::to_value(m).unwrap())).into_response(),
-        Err(e) => err500(e.to_string()).into_response(),
+        Ok(m) => {
+            let mut body = serde_json::to_value(m).unwrap();
+            body["rate_limit_rejections"] = serde_json::json!(*state.rate_limit_rejections.lock().await);
+            (StatusCode::OK, Json(body)).into_response()
+        }
+        Err(e) => err500(keystore_error_code(&e), e.to_string()).into_response(),
     }
 }
 
 async fn list_keys_handler(State(state): State<Shared>) -> impl IntoResponse {
     match state.keystore.list_keys().await {
         Ok(keys) => Json(keys.iter().map(key_to_response).collect::<Vec<_>>()).into_response(),
-        Err(e) => err500(e.to_string()).into_response(),
+        Err(e) => err500(keystore_error_code(&e), e.to_string()).into_response(),
     }
 }
 
 async fn get_key(State(state): State<Shared>, Path(id): Path<String>) -> impl IntoResponse {
-    match state.keystore.get(&KeyId::new(&id)).await {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    match state.keystore.get(&id).await {
         Ok(m) => Json(key_to_response(&m)).into_response(),
-        Err(e) => err(e.to_string()).into_response(),
+        Err(e) => keystore_err(&e).into_response(),
     }
 }
 
-async fn generate_key(State(state): State<Shared>, Json(req): Json<GenerateKeyReq>) -> impl IntoResponse {
+/// `POST /api/keys`. With an `Idempotency-Key` header, a retry within
+/// `IDEMPOTENCY_TTL` returns the key the first request created instead of
+/// generating a duplicate. Keys are scoped to the calling API key; reusing
+/// one with a different body is a 422, and a retry that arrives while the
+/// first request is still generating is a 409.
+async fn generate_key(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, headers: HeaderMap, Json(req): Json<GenerateKeyReq>) -> impl IntoResponse {
     let kt = match parse_key_type(&req.key_type) {
         Some(kt) => kt,
-        None => return err(format!("invalid key_type: {}", req.key_type)).into_response(),
+        None => return err("INVALID_KEY_TYPE", format!("invalid key_type: {}", req.key_type)).into_response(),
     };
+    let idempotency_key = match headers.get("idempotency-key").map(|v| v.to_str()) {
+        None => None,
+        Some(Ok(v)) if !v.is_empty() && v.len() <= IDEMPOTENCY_KEY_MAX_LEN => {
+            let caller = auth.as_ref().map(|Extension(ctx)| ctx.key_id.as_str()).unwrap_or("anonymous");
+            Some(format!("{}:{}", caller, v))
+        }
+        Some(_) => return err("INVALID_IDEMPOTENCY_KEY", format!("Idempotency-Key must be 1-{} visible ASCII characters", IDEMPOTENCY_KEY_MAX_LEN)).into_response(),
+    };
+
+    let reservation = match &idempotency_key {
+        None => None,
+        Some(k) => match claim_idempotency_key(&state.idempotency_keys, k, idempotency_request_hash(&req)) {
+            IdempotencyClaim::Reserved(reservation) => Some(reservation),
+            IdempotencyClaim::Replay(id) => {
+                return (StatusCode::CREATED, Json(serde_json::json!({"key_id": id.to_string()}))).into_response();
+            }
+            IdempotencyClaim::InProgress => {
+                return (
+                    StatusCode::CONFLICT,
+                    Json(ApiError::new("IDEMPOTENCY_KEY_IN_PROGRESS", "a request with this Idempotency-Key is still being processed")),
+                ).into_response();
+            }
+            IdempotencyClaim::Mismatch => {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(ApiError::new("IDEMPOTENCY_KEY_REUSED", "Idempotency-Key was already used with a different request body")),
+                ).into_response();
+            }
+        },
+    };
+
     let policy = req.policy_id.map(|p| PolicyId::new(&p));
-    match state.keystore.generate(&req.name, kt, policy, None).await {
-        Ok(id) => (StatusCode::CREATED, Json(serde_json::json!({"key_id": id.to_string()}))).into_response(),
-        Err(e) => err(e.to_string()).into_response(),
+    match keystore_for(&state, &auth).generate(&req.name, kt, policy, None).await {
+        Ok(id) => {
+            if let Some(reservation) = reservation {
+                reservation.complete(&id);
+            }
+            (StatusCode::CREATED, Json(serde_json::json!({"key_id": id.to_string()}))).into_response()
+        }
+        Err(e) => keystore_err(&e.0).into_response(),
     }
 }
 
-async fn activate_key(State(state): State<Shared>, Path(id): Path<String>) -> impl IntoResponse {
-    match state.keystore.activate(&KeyId::new(&id)).await {
+async fn activate_key(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Path(id): Path<String>) -> impl IntoResponse {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    match keystore_for(&state, &auth).activate(&id).await {
         Ok(()) => Json(serde_json::json!({"status": "activated"})).into_response(),
-        Err(e) => err(e.to_string()).into_response(),
+        Err(e) => keystore_err(&e.0).into_response(),
     }
 }
 
-async fn rotate_key(State(state): State<Shared>, Path(id): Path<String>) -> impl IntoResponse {
-    match state.keystore.rotate(&KeyId::new(&id)).await {
+/// `POST /api/keys/activate`: activate each listed key. Always 200; each
+/// result carries the key's own outcome.
+async fn activate_keys(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Json(req): Json<BulkActivateReq>) -> impl IntoResponse {
+    if req.ids.len() > BULK_ACTIVATE_MAX_ITEMS {
+        return err("TOO_MANY_ITEMS", format!("too many ids: {} (max {})", req.ids.len(), BULK_ACTIVATE_MAX_ITEMS)).into_response();
+    }
+    let ids = match req.ids.iter().map(|id| key_id(id)).collect::<Result<Vec<_>, _>>() {
+        Ok(ids) => ids,
+        Err(e) => return e.into_response(),
+    };
+    let results: Vec<serde_json::Value> = keystore_for(&state, &auth).activate_many(&ids).await
+        .into_iter()
+        .map(|(id, r)| match r {
+            Ok(()) => serde_json::json!({"key_id": id.to_string(), "status": "activated"}),
+            Err(e) => serde_json::json!({"key_id": id.to_string(), "code": keystore_error_code(&e.0), "error": e.0.to_string()}),
+        })
+        .collect();
+    Json(serde_json::json!({"results": results})).into_response()
+}
+
+async fn rotate_key(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Path(id): Path<String>) -> impl IntoResponse {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    match keystore_for(&state, &auth).rotate(&id).await {
         Ok(new_id) => Json(serde_json::json!({"status": "rotated", "new_key_id": new_id.to_string()})).into_response(),
-        Err(e) => err(e.to_string()).into_response(),
+        Err(e) => keystore_err(&e.0).into_response(),
     }
 }
 
-async fn revoke_key(State(state): State<Shared>, Path(id): Path<String>, Json(req): Json<RevokeReq>) -> impl IntoResponse {
-    match state.keystore.revoke(&KeyId::new(&id), &req.reason).await {
+async fn revoke_key(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Path(id): Path<String>, Json(req): Json<RevokeReq>) -> impl IntoResponse {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    match keystore_for(&state, &auth).revoke(&id, &req.reason).await {
         Ok(()) => Json(serde_json::json!({"status": "revoked"})).into_response(),
-        Err(e) => err(e.to_string()).into_response(),
+        Err(e) => keystore_err(&e.0).into_response(),
+    }
+}
+
+async fn compromise_key(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Path(id): Path<String>, Json(req): Json<CompromiseReq>) -> impl IntoResponse {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    match keystore_for(&state, &auth).compromise(&id, &req.detail).await {
+        Ok(()) => Json(serde_json::json!({"status": "compromised"})).into_response(),
+        Err(e) => keystore_err(&e.0).into_response(),
     }
 }
 
-async fn destroy_key(State(state): State<Shared>, Path(id): Path<String>) -> impl IntoResponse {
-    match state.keystore.destroy(&KeyId::new(&id)).await {
+async fn destroy_key(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Path(id): Path<String>) -> impl IntoResponse {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    match keystore_for(&state, &auth).destroy(&id).await {
         Ok(()) => Json(serde_json::json!({"status": "destroyed"})).into_response(),
-        Err(e) => err(e.to_string()).into_response(),
+        Err(e) => keystore_err(&e.0).into_response(),
+    }
+}
+
+async fn get_tag(State(state): State<Shared>, Path((id, key)): Path<(String, String)>) -> impl IntoResponse {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    match state.keystore.get(&id).await {
+        Ok(meta) => match meta.tags.get(&key) {
+            Some(value) => Json(serde_json::json!({"key": key, "value": value})).into_response(),
+            None => (StatusCode::NOT_FOUND, Json(ApiError::new("TAG_NOT_FOUND", format!("tag not found: {}", key)))).into_response(),
+        },
+        Err(e) => keystore_err(&e).into_response(),
     }
 }
 
-async fn encrypt_data(State(state): State<Shared>, Path(id): Path<String>, Json(req): Json<EncryptReq>) -> impl IntoResponse {
-    let aad = citadel_envelope::Aad::raw(req.aad.as_bytes());
-    let ctx = citadel_envelope::Context::raw(req.context.as_bytes());
-    match state.keystore.encrypt(&KeyId::new(&id), req.plaintext.as_bytes(), &aad, &ctx).await {
+async fn put_tag(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Path((id, key)): Path<(String, String)>, Json(req): Json<SetTagReq>) -> impl IntoResponse {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    match keystore_for(&state, &auth).set_tag(&id, &key, req.value.clone()).await {
+        Ok(()) => Json(serde_json::json!({"status": "tagged", "key": key, "value": req.value})).into_response(),
+        Err(e) => keystore_err(&e).into_response(),
+    }
+}
+
+async fn delete_tag(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Path((id, key)): Path<(String, String)>) -> impl IntoResponse {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    match keystore_for(&state, &auth).remove_tag(&id, &key).await {
+        Ok(Some(_)) => Json(serde_json::json!({"status": "removed", "key": key})).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiError::new("TAG_NOT_FOUND", format!("tag not found: {}", key)))).into_response(),
+        Err(e) => keystore_err(&e).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct AuditQuery {
+    key_id: Option<String>,
+    limit: Option<usize>,
+}
+
+const AUDIT_DEFAULT_LIMIT: usize = 100;
+const AUDIT_MAX_LIMIT: usize = 1000;
+
+/// Strip operator-only detail from an audit event served to a non-admin
+/// caller: a failed decryption's detail names the envelope stage that
+/// failed, which a client able to submit ciphertexts must not learn.
+fn redact_audit_event(mut event: AuditEvent) -> AuditEvent {
+    if matches!(event.action, audit::AuditAction::DecryptionFailed { .. }) {
+        event.detail = None;
+    }
+    event
+}
+
+async fn get_audit(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Query(q): Query<AuditQuery>) -> impl IntoResponse {
+    // Dev mode (no API keys configured) has no scopes and sees everything.
+    let admin = auth.as_ref().is_none_or(|Extension(ctx)| has_scope(&ctx.scopes, &Scope::Admin));
+    let events = state.audit_recent.events().await;
+    let limit = q.limit.unwrap_or(AUDIT_DEFAULT_LIMIT).min(AUDIT_MAX_LIMIT);
+    let key_id = q.key_id.map(|k| KeyId::new(&k));
+    let mut recent: Vec<AuditEvent> = events
+        .into_iter()
+        .rev()
+        .filter(|e| key_id.is_none() || e.key_id == key_id)
+        .take(limit)
+        .map(|e| if admin { e } else { redact_audit_event(e) })
+        .collect();
+    recent.reverse();
+    Json(recent).into_response()
+}
+
+async fn verify_audit(State(state): State<Shared>) -> impl IntoResponse {
+    let events = match state.audit_log.read_events() {
+        Ok(events) => events,
+        Err(e) => return Json(serde_json::json!({"valid": false, "verified": 0, "broken": {"reason": e.to_string()}})).into_response(),
+    };
+    match verify_chain(&events) {
+        Ok(n) => Json(serde_json::json!({"valid": true, "verified": n, "broken": null})).into_response(),
+        Err(brk) => Json(serde_json::json!({"valid": false, "verified": brk.index, "broken": brk})).into_response(),
+    }
+}
+
+async fn encrypt_data(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Path(id): Path<String>, Json(req): Json<EncryptReq>) -> impl IntoResponse {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    let (aad, ctx) = match decode_bindings(&req.aad, req.aad_encoding, &req.context, req.context_encoding) {
+        Ok(bindings) => bindings,
+        Err(msg) => return err("INVALID_ENCODING", msg).into_response(),
+    };
+    match keystore_for(&state, &auth).encrypt(&id, req.plaintext.as_bytes(), &aad, &ctx).await {
         Ok(blob) => (StatusCode::OK, Json(blob)).into_response(),
-        Err(e) => {
-            let msg = e.to_string();
-            if msg.contains("policy") || msg.contains("compliance") {
-                (StatusCode::FORBIDDEN, Json(ApiError { error: msg })).into_response()
-            } else {
-                err(msg).into_response()
-            }
-        }
+        Err(e) => encrypt_err(&e).into_response(),
     }
 }
 
-async fn decrypt_data(State(state): State<Shared>, Json(req): Json<DecryptReq>) -> impl IntoResponse {
-    let aad = citadel_envelope::Aad::raw(req.aad.as_bytes());
-    let ctx = citadel_envelope::Context::raw(req.context.as_bytes());
-    match state.keystore.decrypt(&req.blob, &aad, &ctx).await {
+async fn decrypt_data(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Json(req): Json<DecryptReq>) -> impl IntoResponse {
+    let (aad, ctx) = match decode_bindings(&req.aad, req.aad_encoding, &req.context, req.context_encoding) {
+        Ok(bindings) => bindings,
+        Err(msg) => return err("INVALID_ENCODING", msg).into_response(),
+    };
+    match keystore_for(&state, &auth).decrypt(&req.blob, &aad, &ctx).await {
         Ok(pt) => Json(serde_json::json!({"plaintext": String::from_utf8_lossy(&pt)})).into_response(),
-        Err(e) => err(e.to_string()).into_response(),
+        Err(e) => decrypt_err(&e).into_response(),
     }
 }
 
+async fn decrypt_bulk(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Json(req): Json<BulkDecryptReq>) -> impl IntoResponse {
+    if req.items.len() > BULK_DECRYPT_MAX_ITEMS {
+        return err("TOO_MANY_ITEMS", format!("too many items: {} (max {})", req.items.len(), BULK_DECRYPT_MAX_ITEMS)).into_response();
+    }
+    let mut batch = Vec::with_capacity(req.items.len());
+    for (i, item) in req.items.into_iter().enumerate() {
+        match decode_bindings(&item.aad, item.aad_encoding, &item.context, item.context_encoding) {
+            Ok((aad, ctx)) => batch.push((item.blob, aad, ctx)),
+            Err(msg) => return err("INVALID_ENCODING", format!("item {}: {}", i, msg)).into_response(),
+        }
+    }
+    let results: Vec<serde_json::Value> = keystore_for(&state, &auth).decrypt_batch(&batch).await
+        .into_iter()
+        .map(|r| match r {
+            Ok(pt) => serde_json::json!({"plaintext": String::from_utf8_lossy(&pt)}),
+            Err(e) => {
+                let (_, Json(body)) = decrypt_err(&e);
+                serde_json::json!(body)
+            }
+        })
+        .collect();
+    Json(serde_json::json!({"results": results})).into_response()
+}
+
 async fn get_threat(State(state): State<Shared>) -> impl IntoResponse {
     let ks = &state.keystore;
     let level = ks.threat_level();
@@ -689,14 +1201,36 @@ async fn get_threat(State(state): State<Shared>) -> impl IntoResponse {
     }))
 }
 
+#[derive(Deserialize)]
+struct ThreatEventsQuery {
+    limit: Option<usize>,
+}
+
+const THREAT_EVENTS_DEFAULT_LIMIT: usize = 50;
+
+/// The events behind the current score, oldest first.
+async fn get_threat_events(State(state): State<Shared>, Query(q): Query<ThreatEventsQuery>) -> impl IntoResponse {
+    let limit = q.limit.unwrap_or(THREAT_EVENTS_DEFAULT_LIMIT).min(THREAT_EVENTS_MAX_ITEMS);
+    let mut events: Vec<ThreatEventEntry> = state.keystore.recent_threat_events(limit).into_iter().map(|e| ThreatEventEntry {
+        timestamp: e.timestamp.to_rfc3339(), kind: e.kind, severity: e.severity, detail: e.detail,
+    }).collect();
+    events.reverse();
+    Json(serde_json::json!({"events": events}))
+}
+
 async fn post_threat_event(State(state): State<Shared>, Json(req): Json<ThreatEventReq>) -> impl IntoResponse {
     let kind = match parse_threat_kind(&req.kind) {
         Some(k) => k,
-        None => return err(format!("unknown threat kind: {}", req.kind)).into_response(),
+        None => return err("UNKNOWN_THREAT_KIND", format!("unknown threat kind: {}", req.kind)).into_response(),
     };
     let mut event = ThreatEvent::new(kind, req.severity);
     if let Some(d) = req.detail { event = event.with_detail(d); }
-    state.keystore.record_threat_event(event);
+    if !state.keystore.record_threat_event(event) {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(ApiError::new("THREAT_INGEST_LIMITED", "threat event ingestion limit exceeded")),
+        ).into_response();
+    }
     let level = state.keystore.threat_level();
     Json(serde_json::json!({
         "status": "recorded", "score": state.keystore.threat_score(),
@@ -704,6 +1238,49 @@ async fn post_threat_event(State(state): State<Shared>, Json(req): Json<ThreatEv
     })).into_response()
 }
 
+/// Record a batch of events (e.g. from a log shipper). Every kind is
+/// checked before anything is recorded; the level is reported once.
+async fn post_threat_events(State(state): State<Shared>, Json(reqs): Json<Vec<ThreatEventReq>>) -> impl IntoResponse {
+    if reqs.len() > THREAT_EVENTS_MAX_ITEMS {
+        return err("TOO_MANY_ITEMS", format!("too many items: {} (max {})", reqs.len(), THREAT_EVENTS_MAX_ITEMS)).into_response();
+    }
+    let mut events = Vec::with_capacity(reqs.len());
+    for (i, req) in reqs.into_iter().enumerate() {
+        let Some(kind) = parse_threat_kind(&req.kind) else {
+            return err("UNKNOWN_THREAT_KIND", format!("item {}: unknown threat kind: {}", i, req.kind)).into_response();
+        };
+        let mut event = ThreatEvent::new(kind, req.severity);
+        if let Some(d) = req.detail { event = event.with_detail(d); }
+        events.push(event);
+    }
+    let received = events.len();
+    let refused = state.keystore.record_threat_events(events);
+    let level = state.keystore.threat_level();
+    Json(serde_json::json!({
+        "status": "recorded", "received": received, "refused": refused, "score": state.keystore.threat_score(),
+        "level": level.value(), "name": lname(level),
+    })).into_response()
+}
+
+async fn get_threat_config(State(state): State<Shared>) -> impl IntoResponse {
+    Json(state.keystore.threat_config())
+}
+
+/// Replace the assessor's tuning at runtime. Not persisted across restarts.
+async fn put_threat_config(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Json(config): Json<ThreatConfig>) -> impl IntoResponse {
+    match keystore_for(&state, &auth).set_threat_config(config) {
+        Ok(()) => {
+            let level = state.keystore.threat_level();
+            tracing::info!(level = %lname(level), "updated threat config");
+            Json(serde_json::json!({
+                "status": "updated", "score": state.keystore.threat_score(),
+                "level": level.value(), "name": lname(level),
+            })).into_response()
+        }
+        Err(e) => keystore_err(&e).into_response(),
+    }
+}
+
 async fn reset_threat(State(state): State<Shared>) -> impl IntoResponse {
     state.keystore.record_threat_event(ThreatEvent::new(ThreatEventKind::ManualDeescalation, 0.0));
     let level = state.keystore.threat_level();
@@ -713,36 +1290,139 @@ async fn reset_threat(State(state): State<Shared>) -> impl IntoResponse {
     }))
 }
 
+async fn threat_heartbeat(State(state): State<Shared>) -> impl IntoResponse {
+    state.keystore.record_threat_event(ThreatEvent::new(ThreatEventKind::Heartbeat, 0.0));
+    let level = state.keystore.threat_level();
+    Json(serde_json::json!({
+        "status": "recomputed", "score": state.keystore.threat_score(),
+        "level": level.value(), "name": lname(level),
+    }))
+}
+
 async fn get_policies(State(state): State<Shared>) -> impl IntoResponse {
     let ks = &state.keystore;
     let mut out = Vec::new();
-    for id in &["default-dek", "default-kek"] {
-        let pid = PolicyId::new(*id);
+    for pid in ks.policy_ids() {
         if let Some(s) = ks.policy_adaptation_summary(&pid) {
-            out.push(PolicyAdaptationResponse {
-                policy_name: s.policy_name, threat_level: s.threat_level.value(),
-                base_rotation_age_days: s.base_rotation_age.map(|d| d.as_secs() as f64 / 86400.0),
-                effective_rotation_age_days: s.effective_rotation_age.map(|d| d.as_secs() as f64 / 86400.0),
-                base_grace_period_days: s.base_grace_period.as_secs() as f64 / 86400.0,
-                effective_grace_period_days: s.effective_grace_period.as_secs() as f64 / 86400.0,
-                base_max_lifetime_days: s.base_max_lifetime.map(|d| d.as_secs() as f64 / 86400.0),
-                effective_max_lifetime_days: s.effective_max_lifetime.map(|d| d.as_secs() as f64 / 86400.0),
-                base_usage_limit: s.base_usage_limit, effective_usage_limit: s.effective_usage_limit,
-                auto_rotate_forced: s.auto_rotate_forced,
-            });
+            out.push(adaptation_response(s));
         }
     }
     Json(out)
 }
 
-async fn expire_due(State(state): State<Shared>) -> impl IntoResponse {
-    match state.keystore.expire_due_keys().await {
+fn adaptation_response(s: AdaptationSummary) -> PolicyAdaptationResponse {
+    PolicyAdaptationResponse {
+        policy_name: s.policy_name, threat_level: s.threat_level.value(),
+        base_rotation_age_days: s.base_rotation_age.map(|d| d.as_secs() as f64 / 86400.0),
+        effective_rotation_age_days: s.effective_rotation_age.map(|d| d.as_secs() as f64 / 86400.0),
+        base_grace_period_days: s.base_grace_period.as_secs() as f64 / 86400.0,
+        effective_grace_period_days: s.effective_grace_period.as_secs() as f64 / 86400.0,
+        base_max_lifetime_days: s.base_max_lifetime.map(|d| d.as_secs() as f64 / 86400.0),
+        effective_max_lifetime_days: s.effective_max_lifetime.map(|d| d.as_secs() as f64 / 86400.0),
+        base_usage_limit: s.base_usage_limit, effective_usage_limit: s.effective_usage_limit,
+        auto_rotate_forced: s.auto_rotate_forced,
+    }
+}
+
+/// `GET /api/keys/:id/policy`: the key's policy as adapted to the current
+/// threat level. 404 if the key has no (registered) policy.
+async fn get_key_policy(State(state): State<Shared>, Path(id): Path<String>) -> impl IntoResponse {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    match state.keystore.effective_policy_summary(&id).await {
+        Ok(Some(s)) => Json(adaptation_response(s)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiError::new("POLICY_NOT_FOUND", format!("key {} has no registered policy", id)))).into_response(),
+        Err(e) => keystore_err(&e).into_response(),
+    }
+}
+
+/// Version history across the key's rotation lineage, oldest first; each
+/// row names the key holding that version.
+async fn get_key_versions(State(state): State<Shared>, Path(id): Path<String>) -> impl IntoResponse {
+    let id = match key_id(&id) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+    match state.keystore.key_versions(&id).await {
+        Ok(versions) => Json(serde_json::json!({"key_id": id, "versions": versions})).into_response(),
+        Err(e) => keystore_err(&e).into_response(),
+    }
+}
+
+async fn get_policy(State(state): State<Shared>, Path(id): Path<String>) -> impl IntoResponse {
+    match state.keystore.get_policy(&PolicyId::new(&id)) {
+        Some(p) => Json(p).into_response(),
+        None => (StatusCode::NOT_FOUND, Json(ApiError::new("POLICY_NOT_FOUND", format!("policy not found: {}", id)))).into_response(),
+    }
+}
+
+async fn create_policy(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Json(policy): Json<KeyPolicy>) -> impl IntoResponse {
+    // A malformed policy document is a bad request, not a policy refusal.
+    if let Err(e) = policy.validate() {
+        return err(keystore_error_code(&e), e.to_string()).into_response();
+    }
+    if state.keystore.get_policy(&policy.id).is_some() {
+        return (
+            StatusCode::CONFLICT,
+            Json(ApiError::new("POLICY_EXISTS", format!("policy already exists: {} (use PUT to update)", policy.id))),
+        ).into_response();
+    }
+    let id = policy.id.to_string();
+    keystore_for(&state, &auth).register_policy(policy);
+    tracing::info!(policy_id = %id, "registered policy");
+    (StatusCode::CREATED, Json(serde_json::json!({"status": "registered", "policy_id": id}))).into_response()
+}
+
+async fn update_policy(State(state): State<Shared>, auth: Option<Extension<AuthContext>>, Path(id): Path<String>, Json(policy): Json<KeyPolicy>) -> impl IntoResponse {
+    if policy.id.as_str() != id {
+        return err("POLICY_ID_MISMATCH", format!("policy id in body ({}) does not match path ({})", policy.id, id)).into_response();
+    }
+    if let Err(e) = policy.validate() {
+        return err(keystore_error_code(&e), e.to_string()).into_response();
+    }
+    match keystore_for(&state, &auth).update_policy(policy) {
+        Ok(()) => {
+            tracing::info!(policy_id = %id, "updated policy");
+            Json(serde_json::json!({"status": "updated", "policy_id": id})).into_response()
+        }
+        Err(KeystoreError::PolicyNotFound(_)) => {
+            (StatusCode::NOT_FOUND, Json(ApiError::new("POLICY_NOT_FOUND", format!("policy not found: {}", id)))).into_response()
+        }
+        Err(e) => keystore_err(&e).into_response(),
+    }
+}
+
+async fn expire_due(State(state): State<Shared>, auth: Option<Extension<AuthContext>>) -> impl IntoResponse {
+    match keystore_for(&state, &auth).expire_due_keys().await {
         Ok(report) => Json(serde_json::json!({
             "expired": report.expired.len(),
             "warnings": report.warnings.len(),
             "skipped": report.skipped,
         })).into_response(),
-        Err(e) => err500(e.to_string()).into_response(),
+        Err(e) => err500(keystore_error_code(&e), e.to_string()).into_response(),
+    }
+}
+
+/// `GET /api/expire/preview`: what `POST /api/expire` would do now,
+/// without changing anything.
+async fn expire_preview(State(state): State<Shared>) -> impl IntoResponse {
+    let source_name = |s: &ExpirationSource| match s {
+        ExpirationSource::GracePeriodExpired => "grace_period_expired",
+        ExpirationSource::MaxLifetimeExceeded => "max_lifetime_exceeded",
+    };
+    match state.keystore.expiration_preview().await {
+        Ok(report) => Json(serde_json::json!({
+            "would_expire": report.expired.iter().map(|(id, src)| serde_json::json!({
+                "key_id": id.to_string(), "source": source_name(src),
+            })).collect::<Vec<_>>(),
+            "warnings": report.warnings.iter().map(|(id, reason, remaining)| serde_json::json!({
+                "key_id": id.to_string(), "reason": reason, "remaining_secs": remaining.as_secs(),
+            })).collect::<Vec<_>>(),
+            "skipped": report.skipped,
+        })).into_response(),
+        Err(e) => err500(keystore_error_code(&e), e.to_string()).into_response(),
     }
 }
 
@@ -750,6 +1430,13 @@ async fn dashboard() -> Html<&'static str> {
     Html(include_str!("dashboard.html"))
 }
 
+/// Hand-maintained OpenAPI 3 description of every route below.
+const OPENAPI_JSON: &str = include_str!("openapi.json");
+
+async fn openapi() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], OPENAPI_JSON)
+}
+
 // ---------------------------------------------------------------------------
 // Routes — API key management (admin scope)
 // ---------------------------------------------------------------------------
@@ -761,18 +1448,18 @@ async fn list_api_keys(State(state): State<Shared>) -> impl IntoResponse {
 
 async fn create_api_key(State(state): State<Shared>, Json(req): Json<CreateApiKeyReq>) -> impl IntoResponse {
     if req.name.is_empty() || req.name.len() > 100 {
-        return err("name must be 1-100 characters").into_response();
+        return err("INVALID_NAME", "name must be 1-100 characters").into_response();
     }
 
     let mut scopes = Vec::new();
     for s in &req.scopes {
         match Scope::from_str(s) {
             Some(scope) => { if !scopes.contains(&scope) { scopes.push(scope); } }
-            None => return err(format!("invalid scope '{}' — valid: read, encrypt, manage, admin", s)).into_response(),
+            None => return err("INVALID_SCOPE", format!("invalid scope '{}' — valid: read, encrypt, manage, admin", s)).into_response(),
         }
     }
     if scopes.is_empty() {
-        return err("at least one scope required").into_response();
+        return err("INVALID_SCOPE", "at least one scope required").into_response();
     }
 
     let plaintext_key = generate_api_key();
@@ -792,7 +1479,7 @@ async fn create_api_key(State(state): State<Shared>, Json(req): Json<CreateApiKe
     let mut store = state.api_keys.write().await;
     store.add(entry);
     if let Err(e) = store.save(&state.api_keys_path) {
-        return err500(format!("failed to save: {}", e)).into_response();
+        return err500("STORAGE_ERROR", format!("failed to save: {}", e)).into_response();
     }
 
     tracing::info!(key_id = %key_id, name = %req.name, scopes = ?scopes, "created API key");
@@ -811,17 +1498,17 @@ async fn revoke_api_key(State(state): State<Shared>, Path(id): Path<String>) ->
 
     let target = store.keys.iter().find(|k| k.id == id);
     match target {
-        None => return err(format!("API key '{}' not found", id)).into_response(),
+        None => return err("API_KEY_NOT_FOUND", format!("API key '{}' not found", id)).into_response(),
         Some(entry) => {
             if !entry.active {
-                return err(format!("API key '{}' already revoked", id)).into_response();
+                return err("API_KEY_REVOKED", format!("API key '{}' already revoked", id)).into_response();
             }
             if entry.scopes.contains(&Scope::Admin) {
                 let other_admins = store.keys.iter()
                     .filter(|k| k.id != id && k.active && k.scopes.contains(&Scope::Admin))
                     .count();
                 if other_admins == 0 {
-                    return err("cannot revoke the last admin key").into_response();
+                    return err("LAST_ADMIN_KEY", "cannot revoke the last admin key").into_response();
                 }
             }
         }
@@ -829,7 +1516,7 @@ async fn revoke_api_key(State(state): State<Shared>, Path(id): Path<String>) ->
 
     store.deactivate(&id);
     if let Err(e) = store.save(&state.api_keys_path) {
-        return err500(format!("failed to save: {}", e)).into_response();
+        return err500("STORAGE_ERROR", format!("failed to save: {}", e)).into_response();
     }
 
     tracing::info!(key_id = %id, "revoked API key");
@@ -852,17 +1539,46 @@ async fn whoami(req: Request) -> impl IntoResponse {
 // Bootstrap
 // ---------------------------------------------------------------------------
 
-fn create_keystore(data_dir: &str) -> Keystore {
+/// Events kept in memory for `/api/audit`; the JSONL file keeps everything.
+const AUDIT_RING_CAPACITY: usize = 10_000;
+
+fn create_keystore(data_dir: &str, max_binding_bytes: usize) -> (Keystore, Arc<FileAuditSink>, Arc<RingAuditSink>) {
     let keys_dir = format!("{}/keys", data_dir);
     let audit_path = format!("{}/citadel-audit.jsonl", data_dir);
     std::fs::create_dir_all(&keys_dir).expect("failed to create data directory");
     let storage = Arc::new(FileBackend::new(&keys_dir).expect("failed to init file storage"));
-    let file_sink: Arc<dyn AuditSinkSync> = Arc::new(FileAuditSink::new(&audit_path));
-    let audit: Arc<dyn AuditSinkSync> = Arc::new(IntegrityChainSink::new(file_sink));
-    let mut ks = Keystore::new(storage, audit);
-    ks.register_policy(KeyPolicy::default_dek());
-    ks.register_policy(KeyPolicy::default_kek());
-    ks
+    let file_sink = Arc::new(FileAuditSink::new(&audit_path));
+
+    // Start the ring from the tail of the existing log so a restart keeps the recent view.
+    let ring = Arc::new(RingAuditSink::new(AUDIT_RING_CAPACITY));
+    let mut last = None;
+    match file_sink.read_events() {
+        Ok(events) => {
+            last = events.last().cloned();
+            let skip = events.len().saturating_sub(AUDIT_RING_CAPACITY);
+            events.into_iter().skip(skip).for_each(|e| ring.record(e));
+        }
+        Err(e) => tracing::warn!(error = %e, "cannot read audit log; /api/audit starts empty"),
+    }
+
+    // Continue the existing chain; a fresh genesis would look like tampering.
+    let fan_out = CompositeAuditSink::new(vec![file_sink.clone(), ring.clone()]);
+    let chain = IntegrityChainSink::new(Arc::new(fan_out));
+    let chain = match &last {
+        Some(last) => chain.resume_after(last),
+        None => chain,
+    };
+    let audit: Arc<dyn AuditSinkSync> = Arc::new(chain);
+    let ks = Keystore::new(storage, audit).with_max_binding_bytes(max_binding_bytes);
+    for policy in [KeyPolicy::default_dek(), KeyPolicy::default_kek()] {
+        let (id, types) = (policy.id.clone(), policy.applies_to.clone());
+        ks.register_policy(policy);
+        // Keys generated without a policy_id still fall under their type's default.
+        for key_type in types {
+            ks.set_default_policy(key_type, id.clone()).expect("default policy just registered");
+        }
+    }
+    (ks, file_sink, ring)
 }
 
 async fn seed_demo_keys(ks: &Keystore) {
@@ -883,7 +1599,23 @@ async fn seed_demo_keys(ks: &Keystore) {
     ks.activate(&old).await.unwrap();
     let _ = ks.rotate(&old).await;
     let _ = ks.generate("prod-dek-staged", KeyType::DataEncrypting, Some(PolicyId::new("default-dek")), Some(kek.clone())).await.unwrap();
-    tracing::info!("Seeded 9 demo keys across 4-level hierarchy");
+    tracing::info!("Seeded 10 demo keys across 4-level hierarchy");
+}
+
+/// Why demo seeding must not run in this deployment, if it looks like
+/// production: `CITADEL_ENV=production`, or a hashed bootstrap admin key
+/// (the production form) is configured. `force` overrides both.
+fn demo_seed_refusal(env: Option<&str>, api_key_hash_set: bool, force: bool) -> Option<&'static str> {
+    if force {
+        return None;
+    }
+    if env.is_some_and(|e| e.trim().eq_ignore_ascii_case("production")) {
+        Some("CITADEL_ENV=production")
+    } else if api_key_hash_set {
+        Some("CITADEL_API_KEY_HASH is configured")
+    } else {
+        None
+    }
 }
 
 fn resolve_bootstrap_hash() -> Option<[u8; 32]> {
@@ -942,6 +1674,136 @@ fn bootstrap_api_keys(data_dir: &str) -> (ApiKeyStore, String) {
     (store, path)
 }
 
+/// Default request body cap (16 MiB). Ciphertext travels hex-encoded, so a
+/// decrypt request is a bit over twice its plaintext: the default admits
+/// roughly 8 MiB of plaintext per decrypt.
+const DEFAULT_MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+fn rate_limits_from_env(default_limit: RateLimit) -> HashMap<Scope, RateLimit> {
+    let mut limits = HashMap::new();
+    for scope in [Scope::Read, Scope::Encrypt, Scope::Manage, Scope::Admin] {
+        let prefix = format!("CITADEL_RATE_LIMIT_{}", scope.as_str().to_uppercase());
+        let rps = std::env::var(format!("{}_RPS", prefix)).ok().and_then(|v| v.parse().ok());
+        let burst = std::env::var(format!("{}_BURST", prefix)).ok().and_then(|v| v.parse().ok());
+        if rps.is_some() || burst.is_some() {
+            limits.insert(scope, RateLimit {
+                rps: rps.unwrap_or(default_limit.rps),
+                burst: burst.unwrap_or(default_limit.burst),
+            });
+        }
+    }
+    limits
+}
+
+fn threat_state_path(data_dir: &str) -> String {
+    format!("{}/threat-state.json", data_dir)
+}
+
+fn load_threat_state(ks: &Keystore, data_dir: &str) {
+    let path = threat_state_path(data_dir);
+    let data = match std::fs::read_to_string(&path) {
+        Ok(data) => data,
+        Err(_) => return,
+    };
+    match serde_json::from_str::<ThreatSnapshot>(&data) {
+        Ok(snapshot) => {
+            ks.restore_threat_snapshot(snapshot);
+            let level = ks.threat_level();
+            tracing::info!(level = %lname(level), score = ks.threat_score(), "restored threat state");
+        }
+        Err(e) => tracing::error!("failed to parse {}: {}", path, e),
+    }
+}
+
+fn persist_state(state: &AppState, data_dir: &str) {
+    let path = threat_state_path(data_dir);
+    match serde_json::to_string_pretty(&state.keystore.threat_snapshot()) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(&path, json) {
+                tracing::error!("failed to write {}: {}", path, e);
+            }
+        }
+        Err(e) => tracing::error!("failed to serialize threat state: {}", e),
+    }
+    state.keystore.flush_audit();
+    tracing::info!(path = %path, "persisted threat state and flushed audit log");
+}
+
+/// Build a rustls server config from PEM files: a certificate chain
+/// (leaf first) and its private key.
+fn load_tls_config(cert_path: &str, key_path: &str) -> Result<rustls::ServerConfig, String> {
+    use std::io::BufReader;
+
+    let open = |path: &str| {
+        std::fs::File::open(path).map(BufReader::new).map_err(|e| format!("{}: {}", path, e))
+    };
+    let certs = rustls_pemfile::certs(&mut open(cert_path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{}: {}", cert_path, e))?;
+    if certs.is_empty() {
+        return Err(format!("{}: no certificates found", cert_path));
+    }
+    let key = rustls_pemfile::private_key(&mut open(key_path)?)
+        .map_err(|e| format!("{}: {}", key_path, e))?
+        .ok_or_else(|| format!("{}: no private key found", key_path))?;
+
+    let mut config = rustls::ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("certificate/key rejected: {}", e))?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// TLS config from `CITADEL_TLS_CERT` / `CITADEL_TLS_KEY`, or `None` for
+/// plain HTTP. Exits if only one is set or the files are unusable.
+fn resolve_tls_config() -> Option<rustls::ServerConfig> {
+    let cert = std::env::var("CITADEL_TLS_CERT").ok().filter(|v| !v.trim().is_empty());
+    let key = std::env::var("CITADEL_TLS_KEY").ok().filter(|v| !v.trim().is_empty());
+    match (cert, key) {
+        (Some(cert), Some(key)) => match load_tls_config(&cert, &key) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                tracing::error!("TLS configuration invalid: {}", e);
+                std::process::exit(1);
+            }
+        },
+        (None, None) => {
+            tracing::warn!("CITADEL_TLS_CERT/CITADEL_TLS_KEY not set — serving plain HTTP; terminate TLS in front of this server");
+            None
+        }
+        _ => {
+            tracing::error!("CITADEL_TLS_CERT and CITADEL_TLS_KEY must be set together");
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    tracing::info!("shutdown signal received — draining in-flight requests");
+}
+
 #[tokio::main]
 async fn main() {
     let log_format = std::env::var("CITADEL_LOG_FORMAT").unwrap_or_else(|_| "pretty".into());
@@ -958,17 +1820,41 @@ async fn main() {
     let seed_demo = std::env::var("CITADEL_SEED_DEMO").map(|v| v == "true").unwrap_or(false);
     let rate_rps: f64 = std::env::var("CITADEL_RATE_LIMIT_RPS").ok().and_then(|v| v.parse().ok()).unwrap_or(20.0);
     let rate_burst: u32 = std::env::var("CITADEL_RATE_LIMIT_BURST").ok().and_then(|v| v.parse().ok()).unwrap_or(50);
+    let max_body_bytes: usize = std::env::var("CITADEL_MAX_BODY_BYTES").ok().and_then(|v| v.parse().ok()).unwrap_or(DEFAULT_MAX_BODY_BYTES);
+    let max_binding_bytes: usize = std::env::var("CITADEL_MAX_BINDING_BYTES").ok().and_then(|v| v.parse().ok()).unwrap_or(DEFAULT_MAX_BINDING_BYTES);
+    let tls_config = resolve_tls_config();
+
+    let default_limit = RateLimit { rps: rate_rps, burst: rate_burst };
+    let scope_limits = rate_limits_from_env(default_limit);
+    for (scope, limit) in &scope_limits {
+        tracing::info!(scope = %scope.as_str(), rps = limit.rps, burst = limit.burst, "per-scope rate limit");
+    }
 
     let (api_key_store, api_keys_path) = bootstrap_api_keys(&data_dir);
 
     let keys_dir = format!("{}/keys", data_dir);
     let is_fresh = !std::path::Path::new(&keys_dir).exists()
         || std::fs::read_dir(&keys_dir).map(|mut d| d.next().is_none()).unwrap_or(true);
-    let ks = create_keystore(&data_dir);
+    let (ks, audit_log, audit_recent) = create_keystore(&data_dir, max_binding_bytes);
+    load_threat_state(&ks, &data_dir);
 
     if seed_demo && is_fresh {
-        tracing::info!("Fresh data directory — seeding demo keys");
-        seed_demo_keys(&ks).await;
+        let force = std::env::var("CITADEL_SEED_DEMO_FORCE").map(|v| v == "true").unwrap_or(false);
+        let hash_set = std::env::var("CITADEL_API_KEY_HASH").is_ok_and(|h| !h.trim().is_empty());
+        let env = std::env::var("CITADEL_ENV").ok();
+        if let Some(reason) = demo_seed_refusal(env.as_deref(), hash_set, force) {
+            tracing::error!(
+                reason,
+                "REFUSING to seed demo keys: this looks like a production deployment. \
+                 Unset CITADEL_SEED_DEMO, or set CITADEL_SEED_DEMO_FORCE=true if you really mean it"
+            );
+        } else {
+            if force {
+                tracing::warn!("CITADEL_SEED_DEMO_FORCE=true — seeding demo keys regardless of environment");
+            }
+            tracing::info!("Fresh data directory — seeding demo keys");
+            seed_demo_keys(&ks).await;
+        }
     } else if !is_fresh {
         let count = ks.list_keys().await.map(|k| k.len()).unwrap_or(0);
         tracing::info!(keys = count, dir = %keys_dir, "loaded crypto keys");
@@ -978,13 +1864,31 @@ async fn main() {
         keystore: ks,
         api_keys: RwLock::new(api_key_store),
         api_keys_path,
-        rate_limiter: RateLimiter::new(rate_rps, rate_burst),
+        rate_limiter: RateLimiter::new(default_limit, scope_limits),
+        audit_log,
+        audit_recent,
+        idempotency_keys: std::sync::Mutex::new(HashMap::new()),
+        rate_limit_rejections: Mutex::new(HashMap::new()),
     });
 
     let cleanup_state = state.clone();
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
-        loop { interval.tick().await; cleanup_rate_limiter(&cleanup_state.rate_limiter).await; }
+        loop {
+            interval.tick().await;
+            cleanup_rate_limiter(&cleanup_state.rate_limiter).await;
+            cleanup_idempotency_keys(&cleanup_state.idempotency_keys);
+        }
+    });
+
+    // Heartbeats let decay lower the threat level even when no new events arrive
+    let heartbeat_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            heartbeat_state.keystore.record_threat_event(ThreatEvent::new(ThreatEventKind::Heartbeat, 0.0));
+        }
     });
 
     let cors = CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any);
@@ -992,35 +1896,341 @@ async fn main() {
     let app = Router::new()
         .route("/", get(dashboard))
         .route("/health", get(health))
+        .route("/health/live", get(health))
+        .route("/health/ready", get(health_ready))
+        .route("/api/openapi.json", get(openapi))
         .route("/api/status", get(get_status))
         .route("/api/metrics", get(get_metrics))
         .route("/api/keys", get(list_keys_handler).post(generate_key))
+        .route("/api/keys/activate", post(activate_keys))
         .route("/api/keys/:id", get(get_key))
+        .route("/api/keys/:id/policy", get(get_key_policy))
+        .route("/api/keys/:id/versions", get(get_key_versions))
         .route("/api/keys/:id/activate", post(activate_key))
         .route("/api/keys/:id/rotate", post(rotate_key))
         .route("/api/keys/:id/revoke", post(revoke_key))
+        .route("/api/keys/:id/compromise", post(compromise_key))
         .route("/api/keys/:id/destroy", post(destroy_key))
         .route("/api/keys/:id/encrypt", post(encrypt_data))
+        .route("/api/keys/:id/tags/:key", get(get_tag).put(put_tag).delete(delete_tag))
         .route("/api/decrypt", post(decrypt_data))
+        .route("/api/decrypt/bulk", post(decrypt_bulk))
         .route("/api/threat", get(get_threat))
         .route("/api/threat/event", post(post_threat_event))
+        .route("/api/threat/events", get(get_threat_events).post(post_threat_events))
         .route("/api/threat/reset", post(reset_threat))
-        .route("/api/policies", get(get_policies))
+        .route("/api/threat/heartbeat", post(threat_heartbeat))
+        .route("/api/threat/config", get(get_threat_config).put(put_threat_config))
+        .route("/api/policies", get(get_policies).post(create_policy))
+        .route("/api/policies/:id", get(get_policy).put(update_policy))
         .route("/api/expire", post(expire_due))
+        .route("/api/expire/preview", get(expire_preview))
+        .route("/api/audit", get(get_audit))
+        .route("/api/audit/verify", get(verify_audit))
         .route("/api/auth/keys", get(list_api_keys).post(create_api_key))
         .route("/api/auth/keys/:id", delete(revoke_api_key))
         .route("/api/auth/whoami", get(whoami))
+        .layer(DefaultBodyLimit::max(max_body_bytes))
         .layer(middleware::from_fn_with_state(state.clone(), auth_middleware))
         .layer(middleware::from_fn_with_state(state.clone(), rate_limit_middleware))
         .layer(cors)
-        .with_state(state);
+        .with_state(state.clone());
 
-    tracing::info!(port, rate_rps, rate_burst, "starting Citadel API Server v0.2.0");
+    let scheme = if tls_config.is_some() { "https" } else { "http" };
+    tracing::info!(port, rate_rps, rate_burst, max_body_bytes, "starting Citadel API Server v0.2.0");
     tracing::info!(data_dir = %data_dir, "data directory");
-    tracing::info!("  Dashboard: http://0.0.0.0:{}", port);
-    tracing::info!("  API:       http://0.0.0.0:{}/api/", port);
+    tracing::info!("  Dashboard: {}://0.0.0.0:{}", scheme, port);
+    tracing::info!("  API:       {}://0.0.0.0:{}/api/", scheme, port);
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    if let Some(config) = tls_config {
+        let handle = axum_server::Handle::new();
+        let shutdown_handle = handle.clone();
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            shutdown_handle.graceful_shutdown(Some(std::time::Duration::from_secs(30)));
+        });
+        axum_server::bind_rustls(addr, axum_server::tls_rustls::RustlsConfig::from_config(Arc::new(config)))
+            .handle(handle)
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+            .await
+            .unwrap();
+    } else {
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown(shutdown_signal())
+            .await
+            .unwrap();
+    }
+
+    persist_state(&state, &data_dir);
+    tracing::info!("Citadel API Server stopped");
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter() -> RateLimiter {
+        let mut limits = HashMap::new();
+        limits.insert(Scope::Encrypt, RateLimit { rps: 0.0, burst: 2 });
+        RateLimiter::new(RateLimit { rps: 0.0, burst: 5 }, limits)
+    }
+
+    #[test]
+    fn test_audit_redacts_decryption_failure_stage() {
+        let id = KeyId::new("k");
+        let failed = AuditEvent::key_event(
+            &id, KeyType::DataEncrypting, KeyState::Active,
+            audit::AuditAction::DecryptionFailed { key_version: 1 },
+        ).with_detail("stage=decode");
+        assert_eq!(redact_audit_event(failed).detail, None);
+
+        let tagged = AuditEvent::key_event(
+            &id, KeyType::DataEncrypting, KeyState::Active, audit::AuditAction::KeyActivated,
+        ).with_detail("note");
+        assert_eq!(redact_audit_event(tagged).detail.as_deref(), Some("note"));
+    }
+
+    #[test]
+    fn test_binding_encodings_decode_to_same_bytes() {
+        let msg_id: [u8; 16] = *b"\x00\x01binary\xffmsg-id\x80";
+        let hex_id = hex::encode(msg_id);
+        let b64_id = Base64::encode_string(&msg_id);
+
+        assert_eq!(BindingEncoding::Hex.decode(&hex_id).unwrap(), msg_id);
+        assert_eq!(BindingEncoding::Base64.decode(&b64_id).unwrap(), msg_id);
+        assert_eq!(BindingEncoding::Utf8.decode("ctx").unwrap(), b"ctx");
+
+        // Omitted encodings stay UTF-8, as before.
+        let req: DecryptReq = serde_json::from_value(serde_json::json!({
+            "blob": {"key_id": "k", "key_version": 1, "ciphertext_hex": "", "encrypted_at": "2026-01-01T00:00:00Z"},
+            "aad": "a", "context": "c", "context_encoding": "hex",
+        })).unwrap();
+        assert_eq!(req.aad_encoding, BindingEncoding::Utf8);
+        assert_eq!(req.context_encoding, BindingEncoding::Hex);
+
+        let bad = decode_bindings("a", BindingEncoding::Utf8, "not hex", BindingEncoding::Hex).unwrap_err();
+        assert!(bad.starts_with("context: invalid hex"), "{}", bad);
+        assert!(decode_bindings("%%%", BindingEncoding::Base64, "", BindingEncoding::Utf8).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_limit_does_not_throttle_reads() {
+        let rl = limiter();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
 
-    let addr = format!("0.0.0.0:{}", port);
-    let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+        assert!(rl.check(ip, Scope::Encrypt).await);
+        assert!(rl.check(ip, Scope::Encrypt).await);
+        assert!(!rl.check(ip, Scope::Encrypt).await);
+
+        for _ in 0..5 {
+            assert!(rl.check(ip, Scope::Read).await);
+        }
+        assert!(!rl.check(ip, Scope::Read).await);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_buckets_are_per_ip() {
+        let rl = limiter();
+        let a: IpAddr = "10.0.0.1".parse().unwrap();
+        let b: IpAddr = "10.0.0.2".parse().unwrap();
+
+        assert!(rl.check(a, Scope::Encrypt).await);
+        assert!(rl.check(a, Scope::Encrypt).await);
+        assert!(!rl.check(a, Scope::Encrypt).await);
+        assert!(rl.check(b, Scope::Encrypt).await);
+    }
+
+    #[test]
+    fn test_cleanup_drops_expired_idempotency_keys() {
+        let keys = std::sync::Mutex::new(HashMap::new());
+        let expired = Instant::now().checked_sub(IDEMPOTENCY_TTL).unwrap();
+        keys.lock().unwrap().insert("k1:old".to_string(), IdempotencyEntry { request_hash: [0; 32], key_id: Some(KeyId::new("a")), created: expired });
+        keys.lock().unwrap().insert("k1:new".to_string(), IdempotencyEntry { request_hash: [0; 32], key_id: Some(KeyId::new("b")), created: Instant::now() });
+
+        cleanup_idempotency_keys(&keys);
+        let keys = keys.into_inner().unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys["k1:new"].key_id, Some(KeyId::new("b")));
+    }
+
+    #[test]
+    fn test_idempotency_key_reservation() {
+        let keys = std::sync::Mutex::new(HashMap::new());
+        let req = |name: &str| GenerateKeyReq { name: name.to_string(), key_type: "aes256".to_string(), policy_id: None };
+        let hash = idempotency_request_hash(&req("a"));
+
+        let IdempotencyClaim::Reserved(reservation) = claim_idempotency_key(&keys, "k1:x", hash) else { panic!("expected a reservation") };
+        // The map lock is free while the first request generates.
+        assert!(matches!(claim_idempotency_key(&keys, "k1:x", hash), IdempotencyClaim::InProgress));
+        assert!(matches!(claim_idempotency_key(&keys, "k1:x", idempotency_request_hash(&req("b"))), IdempotencyClaim::Mismatch));
+
+        reservation.complete(&KeyId::new("key-1"));
+        assert!(matches!(claim_idempotency_key(&keys, "k1:x", hash), IdempotencyClaim::Replay(id) if id == KeyId::new("key-1")));
+        assert!(matches!(claim_idempotency_key(&keys, "k1:x", idempotency_request_hash(&req("b"))), IdempotencyClaim::Mismatch));
+
+        // A failed generation drops the reservation and frees the key for a retry.
+        assert!(matches!(claim_idempotency_key(&keys, "k1:y", hash), IdempotencyClaim::Reserved(_)));
+        assert!(matches!(claim_idempotency_key(&keys, "k1:y", hash), IdempotencyClaim::Reserved(_)));
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_freed_when_request_is_dropped() {
+        let keys = std::sync::Mutex::new(HashMap::new());
+        let hash = [7; 32];
+
+        // A handler that reserves the key, then is dropped mid-generation
+        // (the client timed out and disconnected).
+        let in_flight = async {
+            let _claim = claim_idempotency_key(&keys, "k1:retry", hash);
+            std::future::pending::<()>().await;
+        };
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(10), in_flight).await.is_err());
+
+        // The client's retry is not stuck on IDEMPOTENCY_KEY_IN_PROGRESS.
+        let IdempotencyClaim::Reserved(retry) = claim_idempotency_key(&keys, "k1:retry", hash) else { panic!("retry should reserve the key") };
+        retry.complete(&KeyId::new("key-2"));
+        assert!(matches!(claim_idempotency_key(&keys, "k1:retry", hash), IdempotencyClaim::Replay(id) if id == KeyId::new("key-2")));
+    }
+
+    #[test]
+    fn test_required_scope_for_encrypt_paths() {
+        assert_eq!(required_scope("/api/keys/abc/encrypt", "POST"), Some(Scope::Encrypt));
+        assert_eq!(required_scope("/api/decrypt", "POST"), Some(Scope::Encrypt));
+        assert_eq!(required_scope("/api/decrypt/bulk", "POST"), Some(Scope::Encrypt));
+        assert_eq!(required_scope("/api/keys", "GET"), Some(Scope::Read));
+        assert_eq!(required_scope("/health", "GET"), None);
+        assert_eq!(required_scope("/health/live", "GET"), None);
+        assert_eq!(required_scope("/health/ready", "GET"), None);
+    }
+
+    #[test]
+    fn test_load_tls_config_rejects_missing_or_empty_pem() {
+        let dir = std::env::temp_dir().join(format!("citadel-tls-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let empty = dir.join("empty.pem");
+        std::fs::write(&empty, "").unwrap();
+        let empty = empty.to_str().unwrap();
+
+        let err = load_tls_config("/nonexistent/cert.pem", empty).unwrap_err();
+        assert!(err.contains("/nonexistent/cert.pem"));
+        let err = load_tls_config(empty, empty).unwrap_err();
+        assert!(err.contains("no certificates found"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_openapi_scopes_match_required_scope() {
+        let doc: serde_json::Value = serde_json::from_str(OPENAPI_JSON).unwrap();
+        let paths = doc["paths"].as_object().unwrap();
+        assert!(paths.contains_key("/api/decrypt/bulk"));
+        for (path, ops) in paths {
+            let concrete = path.replace("{id}", "x").replace("{key}", "k");
+            for (method, op) in ops.as_object().unwrap() {
+                let documented = op["x-required-scope"].as_str().and_then(Scope::from_str);
+                assert_eq!(
+                    documented,
+                    required_scope(&concrete, &method.to_uppercase()),
+                    "{} {}", method, path
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_error_codes_are_documented() {
+        let doc: serde_json::Value = serde_json::from_str(OPENAPI_JSON).unwrap();
+        let documented: Vec<&str> = doc["components"]["schemas"]["ErrorCode"]["enum"]
+            .as_array().unwrap().iter().map(|c| c.as_str().unwrap()).collect();
+
+        let id = KeyId::new("k");
+        let errors = [
+            KeystoreError::KeyNotFound(id.clone()),
+            KeystoreError::VersionNotFound { id: id.clone(), version: 2 },
+            KeystoreError::InvalidTransition { id: id.clone(), from: KeyState::Pending, to: KeyState::Rotated },
+            KeystoreError::PolicyViolation("x".into()),
+            KeystoreError::StorageError("x".into()),
+            KeystoreError::EnvelopeError("x".into()),
+            KeystoreError::DuplicateKey(id.clone()),
+            KeystoreError::KeyDestroyed(id.clone()),
+            KeystoreError::NotActive(id.clone()),
+            KeystoreError::NotDecryptable(id.clone()),
+            KeystoreError::PolicyNotFound("x".into()),
+            KeystoreError::InvalidInput("x".into()),
+            KeystoreError::ContextNotAllowed(id.clone()),
+        ];
+        for e in &errors {
+            assert!(documented.contains(&keystore_error_code(e)), "{} undocumented", keystore_error_code(e));
+        }
+
+        let (status, Json(body)) = encrypt_err(&EncryptError::from(KeystoreError::ContextNotAllowed(id.clone())));
+        assert_eq!((status, body.code), (StatusCode::FORBIDDEN, "CONTEXT_NOT_ALLOWED"));
+        let (status, Json(body)) = encrypt_err(&EncryptError::from(citadel_envelope::EncodingError));
+        assert_eq!((status, body.code), (StatusCode::INTERNAL_SERVER_ERROR, "ENCRYPT_FAILED"));
+        let (status, Json(body)) = decrypt_err(&DecryptError::from(KeystoreError::VersionNotFound { id: id.clone(), version: 2 }));
+        assert_eq!((status, body.code), (StatusCode::NOT_FOUND, "VERSION_NOT_FOUND"));
+        let (status, Json(body)) = decrypt_err(&DecryptError::from(citadel_envelope::DecryptionError::new()));
+        assert_eq!((status, body.code, body.message.as_str()), (StatusCode::BAD_REQUEST, "DECRYPT_FAILED", "decrypt: decryption failed"));
+
+        let body = serde_json::to_value(ApiError::new("KEY_NOT_FOUND", "key not found: k")).unwrap();
+        assert_eq!(body, serde_json::json!({"code": "KEY_NOT_FOUND", "error": "key not found: k"}));
+    }
+
+    #[test]
+    fn test_keystore_error_statuses() {
+        let id = KeyId::new("k");
+        let cases = [
+            (KeystoreError::KeyNotFound(id.clone()), StatusCode::NOT_FOUND),
+            (KeystoreError::PolicyNotFound("x".into()), StatusCode::NOT_FOUND),
+            (KeystoreError::InvalidTransition { id: id.clone(), from: KeyState::Revoked, to: KeyState::Active }, StatusCode::CONFLICT),
+            (KeystoreError::KeyDestroyed(id.clone()), StatusCode::CONFLICT),
+            (KeystoreError::NotActive(id.clone()), StatusCode::CONFLICT),
+            (KeystoreError::PolicyViolation("x".into()), StatusCode::FORBIDDEN),
+            (KeystoreError::ContextNotAllowed(id), StatusCode::FORBIDDEN),
+            (KeystoreError::StorageError("x".into()), StatusCode::INTERNAL_SERVER_ERROR),
+            (KeystoreError::InvalidInput("x".into()), StatusCode::BAD_REQUEST),
+        ];
+        for (e, status) in &cases {
+            let (got, body) = keystore_err(e);
+            assert_eq!(got, *status, "{}", e);
+            assert_eq!(body.0.code, keystore_error_code(e));
+        }
+    }
+
+    #[test]
+    fn test_key_id_rejects_traversal() {
+        assert!(matches!(key_id("0123abcd"), Ok(id) if id == KeyId::new("0123abcd")));
+        let Err((status, body)) = key_id("../../etc/passwd") else { panic!("traversal id accepted") };
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.0.code, "INVALID_KEY_ID");
+    }
+
+    #[test]
+    fn test_required_scope_for_policy_writes() {
+        assert_eq!(required_scope("/api/policies", "POST"), Some(Scope::Manage));
+        assert_eq!(required_scope("/api/policies/default-dek", "PUT"), Some(Scope::Manage));
+        assert_eq!(required_scope("/api/policies/default-dek", "GET"), Some(Scope::Read));
+    }
+
+    #[test]
+    fn test_demo_seed_refused_in_production_unless_forced() {
+        assert_eq!(demo_seed_refusal(None, false, false), None);
+        assert_eq!(demo_seed_refusal(Some("development"), false, false), None);
+        assert!(demo_seed_refusal(Some("Production"), false, false).is_some());
+        assert!(demo_seed_refusal(None, true, false).is_some());
+        assert_eq!(demo_seed_refusal(Some("production"), true, true), None);
+    }
+
+    #[test]
+    fn test_required_scope_for_threat_config() {
+        assert_eq!(required_scope("/api/threat/config", "GET"), Some(Scope::Admin));
+        assert_eq!(required_scope("/api/threat/config", "PUT"), Some(Scope::Admin));
+        assert_eq!(required_scope("/api/threat/events", "POST"), Some(Scope::Manage));
+    }
 }
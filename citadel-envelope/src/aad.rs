@@ -16,6 +16,12 @@
 //!   T: u8
 //!   L: u16 big-endian
 //!   V: bytes
+//!
+//! Field maps (`Aad::from_fields`, `Context::from_fields`):
+//!   prefix || count: u16 BE || (LV(key) || LV(value)) per field, keys sorted bytewise
+//!
+//!   prefix is `citadel|aad-fields|v1` or `citadel|ctx-fields|v1`; LV is a u16
+//!   big-endian length then the bytes. Duplicate keys are rejected.
 
 extern crate alloc;
 
@@ -31,6 +37,8 @@ pub type MsgId16 = [u8; 16];
 
 pub const CONTEXT_PREFIX: &[u8] = b"citadel|ctx|v1|";
 pub const AAD_PREFIX: &[u8] = b"citadel|aad|v1";
+pub const AAD_FIELDS_PREFIX: &[u8] = b"citadel|aad-fields|v1";
+pub const CONTEXT_FIELDS_PREFIX: &[u8] = b"citadel|ctx-fields|v1";
 
 #[repr(u8)]
 #[derive(Clone, Copy)]
@@ -106,6 +114,28 @@ pub fn build_aad(
     Ok(out)
 }
 
+/// Encode a key-value map canonically: the same fields in any order give
+/// the same bytes. Fails on a duplicate key, more than 65535 fields, or a
+/// key or value longer than 65535 bytes.
+pub fn encode_fields(prefix: &[u8], fields: &[(&str, &str)]) -> Result<Vec<u8>, EncodingError> {
+    let count = u16::try_from(fields.len()).map_err(|_| EncodingError)?;
+    let mut sorted: Vec<&(&str, &str)> = fields.iter().collect();
+    sorted.sort_unstable_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+    if sorted.windows(2).any(|w| w[0].0 == w[1].0) {
+        return Err(EncodingError);
+    }
+
+    let body: usize = sorted.iter().map(|(k, v)| 2 + k.len() + 2 + v.len()).sum();
+    let mut out = Vec::with_capacity(prefix.len() + 2 + body);
+    out.extend_from_slice(prefix);
+    out.extend_from_slice(&count.to_be_bytes());
+    for (key, value) in sorted {
+        push_lv(&mut out, key.as_bytes())?;
+        push_lv(&mut out, value.as_bytes())?;
+    }
+    Ok(out)
+}
+
 /// Generate a random 16-byte message id.
 ///
 /// This is for internal convenience; you can also supply your own msg_id.
@@ -126,6 +156,14 @@ fn tlv_size(v_len: usize) -> usize {
     1 + 2 + v_len
 }
 
+#[inline]
+fn push_lv(out: &mut Vec<u8>, v: &[u8]) -> Result<(), EncodingError> {
+    let len = u16::try_from(v.len()).map_err(|_| EncodingError)?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(v);
+    Ok(())
+}
+
 #[inline]
 fn push_tlv(out: &mut Vec<u8>, t: AadTlvType, v: &[u8]) -> Result<(), EncodingError> {
     // Length must fit u16
@@ -8,6 +8,7 @@ use aes_gcm::{
     Aes256Gcm, Nonce,
 };
 use getrandom::getrandom;
+use rand_core::{CryptoRng, RngCore};
 
 use crate::error::{DecryptionError, EncodingError};
 
@@ -18,6 +19,37 @@ pub fn nonce() -> Result<[u8; 12], EncodingError> {
     Ok(n)
 }
 
+/// Where `seal` gets its AES-GCM nonces
+/// (see [`Citadel::with_nonce_source`](crate::Citadel::with_nonce_source)).
+///
+/// A source MUST NOT return the same nonce twice. Every seal derives a
+/// fresh AES key from a fresh KEM encapsulation, so a repeat does not by
+/// itself reuse a (key, nonce) pair, but that is the only thing standing
+/// between a repeat and a catastrophic GCM failure: the engine does not
+/// check, and counters must be persisted across restarts.
+pub trait NonceSource: Send + Sync {
+    /// The next 12-byte nonce, or `SealError` if none can be produced
+    /// (sealing then fails).
+    fn next_nonce(&self) -> Result<[u8; 12], EncodingError>;
+}
+
+/// The default [`NonceSource`]: 12 bytes from the OS RNG per seal.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsNonceSource;
+
+impl NonceSource for OsNonceSource {
+    fn next_nonce(&self) -> Result<[u8; 12], EncodingError> {
+        nonce()
+    }
+}
+
+/// Draw a 12-byte nonce from a caller-supplied RNG (deterministic testing).
+pub fn nonce_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> [u8; 12] {
+    let mut n = [0u8; 12];
+    rng.fill_bytes(&mut n);
+    n
+}
+
 /// AEAD seal (encrypt path). Returns EncodingError on failure.
 pub fn aead_seal(
     key: &[u8; 32],
@@ -38,8 +70,8 @@ pub fn aead_open(
     ciphertext: &[u8],
     aad: &[u8],
 ) -> Result<Vec<u8>, DecryptionError> {
-    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| DecryptionError)?;
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| DecryptionError::new())?;
     let n = Nonce::from_slice(nonce);
     let payload = Payload { msg: ciphertext, aad };
-    cipher.decrypt(n, payload).map_err(|_| DecryptionError)
+    cipher.decrypt(n, payload).map_err(|_| DecryptionError::new())
 }
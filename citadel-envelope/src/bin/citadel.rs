@@ -2,14 +2,30 @@
 //!
 //! Usage:
 //!   citadel keygen --name <n>
-//!   citadel seal   --key <PUBKEY_FILE> --in <FILE> [--aad <AAD>] [--ctx <CTX>]
-//!   citadel open   --key <SECKEY_FILE> --in <FILE> [--aad <AAD>] [--ctx <CTX>]
+//!   citadel seal   --key <PUBKEY_FILE> --in <FILE> [--aad <AAD>] [--ctx <CTX>] [--no-magic]
+//!   citadel open   --key <SECKEY_FILE> --in <FILE|-> [--out <FILE|->] [--aad <AAD>] [--ctx <CTX>]
+//!   citadel verify --key <SECKEY_FILE> --in <FILE|-> [--aad <AAD>] [--ctx <CTX>]
+//!   citadel completions <bash|zsh|fish>
+//!
+//! `open --in -` reads ciphertext from stdin and, unless `--out` is given,
+//! writes plaintext to stdout. `verify` decrypts the same way but writes
+//! nothing: the exit status alone says whether the ciphertext opens.
+//!
+//! `seal` frames its output with the `CTD1` magic (see
+//! `citadel_envelope::container`) unless `--no-magic` asks for raw wire
+//! bytes; `open` and `verify` accept either.
+//!
+//! Inputs are read whole into memory: the envelope is a single AEAD message
+//! and the SDK has no chunked `seal_stream`/`open_stream` yet. Split very
+//! large files before sealing.
 
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Read, Write};
+use std::path::Path;
 use std::process;
 
-use citadel_envelope::{Citadel, Aad, Context, PublicKey, SecretKey};
+use citadel_envelope::{container, Citadel, Aad, Context, PublicKey, SecretKey};
+use zeroize::Zeroizing;
 
 fn usage() -> ! {
     eprintln!(
@@ -24,13 +40,28 @@ fn usage() -> ! {
          \n\
          Encrypt a file:\n\
          \n\
-         citadel seal --key <PUBKEY>.pub --in <FILE> [--aad <AAD>] [--ctx <CTX>]\n\
-         Writes <FILE>.ctd\n\
+         citadel seal --key <PUBKEY>.pub --in <FILE> [--aad <AAD>] [--ctx <CTX>] [--no-magic]\n\
+         Writes <FILE>.ctd, starting with the CTD1 magic (--no-magic: raw ciphertext)\n\
          \n\
          Decrypt a file:\n\
          \n\
-         citadel open --key <SECKEY>.sec --in <FILE>.ctd [--aad <AAD>] [--ctx <CTX>]\n\
-         Writes <FILE> (strips .ctd extension, or appends .dec)\n"
+         citadel open --key <SECKEY>.sec --in <FILE>.ctd [--out <FILE>] [--aad <AAD>] [--ctx <CTX>]\n\
+         Writes <FILE> (strips .ctd extension, or appends .dec)\n\
+         \n\
+         Decrypt from a pipe:\n\
+         \n\
+         cat <FILE>.ctd | citadel open --key <SECKEY>.sec --in -\n\
+         Writes plaintext to stdout (or to --out)\n\
+         \n\
+         Check a file decrypts (for monitoring jobs):\n\
+         \n\
+         citadel verify --key <SECKEY>.sec --in <FILE>.ctd [--aad <AAD>] [--ctx <CTX>]\n\
+         Writes nothing; exits 0 if it decrypts, 1 otherwise\n\
+         \n\
+         Shell completion:\n\
+         \n\
+         citadel completions <bash|zsh|fish>\n\
+         Prints a completion script to stdout\n"
     );
     process::exit(1);
 }
@@ -51,7 +82,11 @@ fn parse_args() -> (String, Vec<(String, String)>) {
 
     let mut i = 2;
     while i < args.len() {
-        if args[i].starts_with("--") && i + 1 < args.len() {
+        if args[i] == "--no-magic" {
+            // The one switch that takes no value
+            flags.push((args[i].clone(), String::new()));
+            i += 1;
+        } else if args[i].starts_with("--") && i + 1 < args.len() {
             flags.push((args[i].clone(), args[i + 1].clone()));
             i += 2;
         } else {
@@ -66,6 +101,10 @@ fn get_flag(flags: &[(String, String)], name: &str) -> Option<String> {
     flags.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone())
 }
 
+fn has_flag(flags: &[(String, String)], name: &str) -> bool {
+    flags.iter().any(|(k, _)| k == name)
+}
+
 fn require_flag(flags: &[(String, String)], name: &str) -> String {
     get_flag(flags, name).unwrap_or_else(|| die(&format!("missing required flag: {}", name)))
 }
@@ -109,9 +148,12 @@ fn cmd_seal(flags: &[(String, String)]) {
     let citadel = Citadel::new();
     let aad = Aad::raw(aad_str.as_bytes());
     let ctx = Context::raw(ctx_str.as_bytes());
-    let ciphertext = citadel
+    let mut ciphertext = citadel
         .seal(&pk, &plaintext, &aad, &ctx)
         .unwrap_or_else(|_| die("encryption failed"));
+    if !has_flag(flags, "--no-magic") {
+        ciphertext = container::frame(&ciphertext);
+    }
 
     // Write ciphertext
     fs::write(&out_file, &ciphertext).unwrap_or_else(|e| die(&format!("write {}: {}", out_file, e)));
@@ -130,55 +172,206 @@ fn cmd_open(flags: &[(String, String)]) {
     let in_file = require_flag(flags, "--in");
     let aad_str = get_flag(flags, "--aad").unwrap_or_default();
     let ctx_str = get_flag(flags, "--ctx").unwrap_or_else(|| "citadel-cli-v1".to_string());
-
-    // Determine output filename
-    let out_file = if in_file.ends_with(".ctd") {
-        in_file.trim_end_matches(".ctd").to_string()
-    } else {
-        format!("{}.dec", in_file)
+    let from_stdin = in_file == "-";
+
+    // Determine output: explicit --out, derived from the input name, or stdout for piped input
+    let out_file = match get_flag(flags, "--out") {
+        Some(out) if out == "-" => None,
+        Some(out) => Some(out),
+        None if from_stdin => None,
+        None if in_file.ends_with(".ctd") => Some(in_file.trim_end_matches(".ctd").to_string()),
+        None => Some(format!("{}.dec", in_file)),
     };
 
     // Don't overwrite the input
-    let out_path = PathBuf::from(&out_file);
-    let in_path = PathBuf::from(&in_file);
-    if out_path == in_path {
-        die("output path would overwrite input — rename the input file");
+    if let Some(out_file) = &out_file {
+        if !from_stdin && Path::new(out_file) == Path::new(&in_file) {
+            die("output path would overwrite input — rename the input file");
+        }
+    }
+
+    let (ciphertext, plaintext) = decrypt_input(&key_file, &in_file, &aad_str, &ctx_str);
+
+    // Write plaintext
+    match out_file {
+        Some(out_file) => {
+            fs::write(&out_file, &plaintext).unwrap_or_else(|e| die(&format!("write {}: {}", out_file, e)));
+            eprintln!(
+                "opened {} -> {} ({} bytes ciphertext -> {} bytes plaintext)",
+                if from_stdin { "<stdin>" } else { &in_file },
+                out_file,
+                ciphertext.len(),
+                plaintext.len()
+            );
+        }
+        None => {
+            let mut stdout = io::stdout().lock();
+            stdout
+                .write_all(&plaintext)
+                .and_then(|_| stdout.flush())
+                .unwrap_or_else(|e| die(&format!("write stdout: {}", e)));
+        }
+    }
+}
+
+fn cmd_verify(flags: &[(String, String)]) {
+    let key_file = require_flag(flags, "--key");
+    let in_file = require_flag(flags, "--in");
+    let aad_str = get_flag(flags, "--aad").unwrap_or_default();
+    let ctx_str = get_flag(flags, "--ctx").unwrap_or_else(|| "citadel-cli-v1".to_string());
+    if get_flag(flags, "--out").is_some() {
+        die("verify writes no output — use open to decrypt to a file");
     }
 
+    // The plaintext is zeroized on drop and never leaves this process.
+    decrypt_input(&key_file, &in_file, &aad_str, &ctx_str);
+}
+
+/// Shared by `open` and `verify`: load the secret key, read the ciphertext
+/// (`-` for stdin) and decrypt it, exiting on any failure.
+fn decrypt_input(key_file: &str, in_file: &str, aad_str: &str, ctx_str: &str) -> (Vec<u8>, Zeroizing<Vec<u8>>) {
     // Load secret key
-    let sk_bytes = fs::read(&key_file).unwrap_or_else(|e| die(&format!("read {}: {}", key_file, e)));
+    let sk_bytes = fs::read(key_file).unwrap_or_else(|e| die(&format!("read {}: {}", key_file, e)));
     let sk = SecretKey::from_bytes(&sk_bytes).unwrap_or_else(|_| die("invalid secret key file"));
 
     // Load ciphertext
-    let ciphertext = fs::read(&in_file).unwrap_or_else(|e| die(&format!("read {}: {}", in_file, e)));
+    let ciphertext = if in_file == "-" {
+        let mut buf = Vec::new();
+        io::stdin().read_to_end(&mut buf).unwrap_or_else(|e| die(&format!("read stdin: {}", e)));
+        buf
+    } else {
+        fs::read(in_file).unwrap_or_else(|e| die(&format!("read {}: {}", in_file, e)))
+    };
 
     // Decrypt
     let citadel = Citadel::new();
     let aad = Aad::raw(aad_str.as_bytes());
     let ctx = Context::raw(ctx_str.as_bytes());
     let plaintext = citadel
-        .open(&sk, &ciphertext, &aad, &ctx)
+        .open(&sk, container::unframe(&ciphertext), &aad, &ctx)
         .unwrap_or_else(|_| die("decryption failed (wrong key, corrupted, or mismatched aad/context)"));
+    (ciphertext, Zeroizing::new(plaintext))
+}
 
-    // Write plaintext
-    fs::write(&out_file, &plaintext).unwrap_or_else(|e| die(&format!("write {}: {}", out_file, e)));
+const BASH_COMPLETION: &str = r#"_citadel() {
+    local cur prev cmd
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+    cmd="${COMP_WORDS[1]}"
+
+    if [ "$COMP_CWORD" -eq 1 ]; then
+        COMPREPLY=($(compgen -W "keygen seal open verify completions" -- "$cur"))
+        return
+    fi
+
+    case "$prev" in
+        --key|--in|--out) COMPREPLY=($(compgen -f -- "$cur")); return ;;
+        --name|--aad|--ctx) return ;;
+    esac
+
+    case "$cmd" in
+        keygen) COMPREPLY=($(compgen -W "--name" -- "$cur")) ;;
+        seal) COMPREPLY=($(compgen -W "--key --in --aad --ctx --no-magic" -- "$cur")) ;;
+        open) COMPREPLY=($(compgen -W "--key --in --out --aad --ctx" -- "$cur")) ;;
+        verify) COMPREPLY=($(compgen -W "--key --in --aad --ctx" -- "$cur")) ;;
+        completions) COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur")) ;;
+    esac
+}
+complete -F _citadel citadel
+"#;
+
+const ZSH_COMPLETION: &str = r#"#compdef citadel
+
+_citadel() {
+    local -a commands
+    commands=(
+        'keygen:generate a keypair'
+        'seal:encrypt a file'
+        'open:decrypt a file'
+        'verify:check a file decrypts, writing nothing'
+        'completions:print a shell completion script'
+    )
+
+    if (( CURRENT == 2 )); then
+        _describe 'command' commands
+        return
+    fi
+
+    case "$words[2]" in
+        keygen)
+            _arguments '--name[key file prefix]:name:' ;;
+        seal)
+            _arguments \
+                '--key[public key file]:file:_files' \
+                '--in[plaintext file]:file:_files' \
+                '--aad[additional authenticated data]:aad:' \
+                '--ctx[domain separation context]:ctx:' \
+                '--no-magic[write raw ciphertext without the CTD1 magic]' ;;
+        open)
+            _arguments \
+                '--key[secret key file]:file:_files' \
+                '--in[ciphertext file, or - for stdin]:file:_files' \
+                '--out[plaintext file, or - for stdout]:file:_files' \
+                '--aad[additional authenticated data]:aad:' \
+                '--ctx[domain separation context]:ctx:' ;;
+        verify)
+            _arguments \
+                '--key[secret key file]:file:_files' \
+                '--in[ciphertext file, or - for stdin]:file:_files' \
+                '--aad[additional authenticated data]:aad:' \
+                '--ctx[domain separation context]:ctx:' ;;
+        completions)
+            _values 'shell' bash zsh fish ;;
+    esac
+}
 
-    eprintln!(
-        "opened {} -> {} ({} bytes ciphertext -> {} bytes plaintext)",
-        in_file,
-        out_file,
-        ciphertext.len(),
-        plaintext.len()
-    );
+_citadel "$@"
+"#;
+
+const FISH_COMPLETION: &str = r#"complete -c citadel -f
+complete -c citadel -n '__fish_use_subcommand' -a keygen -d 'Generate a keypair'
+complete -c citadel -n '__fish_use_subcommand' -a seal -d 'Encrypt a file'
+complete -c citadel -n '__fish_use_subcommand' -a open -d 'Decrypt a file'
+complete -c citadel -n '__fish_use_subcommand' -a verify -d 'Check a file decrypts, writing nothing'
+complete -c citadel -n '__fish_use_subcommand' -a completions -d 'Print a shell completion script'
+complete -c citadel -n '__fish_seen_subcommand_from keygen' -l name -x -d 'Key file prefix'
+complete -c citadel -n '__fish_seen_subcommand_from seal open verify' -l key -r -F -d 'Key file'
+complete -c citadel -n '__fish_seen_subcommand_from seal open verify' -l in -r -F -d 'Input file'
+complete -c citadel -n '__fish_seen_subcommand_from open' -l out -r -F -d 'Output file'
+complete -c citadel -n '__fish_seen_subcommand_from seal open verify' -l aad -x -d 'Additional authenticated data'
+complete -c citadel -n '__fish_seen_subcommand_from seal open verify' -l ctx -x -d 'Domain separation context'
+complete -c citadel -n '__fish_seen_subcommand_from seal' -l no-magic -d 'Write raw ciphertext without the CTD1 magic'
+complete -c citadel -n '__fish_seen_subcommand_from completions' -a 'bash zsh fish'
+"#;
+
+fn cmd_completions(shell: Option<&str>) {
+    let script = match shell {
+        Some("bash") => BASH_COMPLETION,
+        Some("zsh") => ZSH_COMPLETION,
+        Some("fish") => FISH_COMPLETION,
+        Some(other) => die(&format!("unsupported shell: {} (expected bash, zsh or fish)", other)),
+        None => die("missing shell: citadel completions <bash|zsh|fish>"),
+    };
+    io::stdout()
+        .write_all(script.as_bytes())
+        .unwrap_or_else(|e| die(&format!("write stdout: {}", e)));
 }
 
 fn main() {
+    // `completions` takes a positional argument, so handle it before flag parsing
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("completions") {
+        cmd_completions(args.get(2).map(String::as_str));
+        return;
+    }
+
     let (command, flags) = parse_args();
 
     match command.as_str() {
         "keygen" => cmd_keygen(&flags),
         "seal" => cmd_seal(&flags),
         "open" => cmd_open(&flags),
+        "verify" => cmd_verify(&flags),
         _ => {
             eprintln!("unknown command: {}", command);
             usage();
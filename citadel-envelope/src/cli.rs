@@ -2,16 +2,23 @@
 //!
 //! Usage:
 //!   citadel keygen --output <dir>
-//!   citadel seal --key <pk_file> --aad <aad> --context <ctx> --input <file> --output <file>
+//!   citadel seal --key <pk_file> --aad <aad> --context <ctx> --input <file> --output <file> [--no-magic]
 //!   citadel open --key <sk_file> --aad <aad> --context <ctx> --input <file> --output <file>
+//!   citadel verify --key <sk_file> --aad <aad> --context <ctx> --input <file>
 //!   citadel inspect <file>
+//!
+//! Inputs are read whole into memory (see `src/bin/citadel.rs`).
+//!
+//! `seal` writes the `CTD1` container unless `--no-magic` is given;
+//! `open`, `verify` and `inspect` accept framed or raw ciphertext.
 
 use std::fs;
 use std::io::{self, Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
-use citadel_envelope::{Citadel, Aad, Context, PublicKey, SecretKey};
+use citadel_envelope::{container, Citadel, Aad, Context, PublicKey, SecretKey};
+use zeroize::Zeroizing;
 
 fn main() -> ExitCode {
     let args: Vec<String> = std::env::args().collect();
@@ -25,6 +32,7 @@ fn main() -> ExitCode {
         "keygen" => cmd_keygen(&args[2..]),
         "seal" => cmd_seal(&args[2..]),
         "open" => cmd_open(&args[2..]),
+        "verify" => cmd_verify(&args[2..]),
         "inspect" => cmd_inspect(&args[2..]),
         "--help" | "-h" => {
             print_usage();
@@ -61,6 +69,7 @@ COMMANDS:
     keygen      Generate a new keypair
     seal        Encrypt a file
     open        Decrypt a file
+    verify      Check a file decrypts, writing nothing (exit 0 = ok)
     inspect     Show ciphertext metadata (no decryption)
 
 EXAMPLES:
@@ -73,7 +82,7 @@ EXAMPLES:
         --aad "backup|db|2026" \
         --context "myapp|prod" \
         --input secret.txt \
-        --output secret.enc
+        --output secret.enc           # add --no-magic for raw wire bytes
 
     # Decrypt
     citadel open \
@@ -83,6 +92,13 @@ EXAMPLES:
         --input secret.enc \
         --output secret.txt
 
+    # Verify (cron-friendly: exit status only)
+    citadel verify \
+        --key ./keys/secret.key \
+        --aad "backup|db|2026" \
+        --context "myapp|prod" \
+        --input secret.enc
+
     # Inspect
     citadel inspect secret.enc
 
@@ -144,6 +160,7 @@ fn cmd_seal(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     let mut ctx_str = String::new();
     let mut input_path: Option<PathBuf> = None;
     let mut output_path: Option<PathBuf> = None;
+    let mut magic = true;
 
     let mut i = 0;
     while i < args.len() {
@@ -168,6 +185,7 @@ fn cmd_seal(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
                 i += 1;
                 output_path = Some(PathBuf::from(args.get(i).ok_or("missing output path")?));
             }
+            "--no-magic" => magic = false,
             _ => return Err(format!("unknown option: {}", args[i]).into()),
         }
         i += 1;
@@ -193,9 +211,12 @@ fn cmd_seal(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     let citadel = Citadel::new();
     let aad = Aad::raw(aad_str.as_bytes());
     let ctx = Context::raw(ctx_str.as_bytes());
-    let ciphertext = citadel
+    let mut ciphertext = citadel
         .seal(&pk, &plaintext, &aad, &ctx)
         .map_err(|_| "encryption failed")?;
+    if magic {
+        ciphertext = container::frame(&ciphertext);
+    }
 
     // Write ciphertext
     fs::write(&output_path, &ciphertext)?;
@@ -242,13 +263,70 @@ fn cmd_open(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let key_path = key_path.ok_or("missing --key")?;
+    let (ciphertext, plaintext) = decrypt_input(&key_path, input_path.as_deref(), &aad_str, &ctx_str)?;
+
+    // Write plaintext
+    if let Some(ref path) = output_path {
+        fs::write(path, &plaintext)?;
+        eprintln!("Decrypted {} bytes -> {} bytes", ciphertext.len(), plaintext.len());
+        eprintln!("Output: {}", path.display());
+    } else {
+        io::stdout().write_all(&plaintext)?;
+    }
 
+    Ok(())
+}
+
+fn cmd_verify(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut key_path: Option<PathBuf> = None;
+    let mut aad_str = String::new();
+    let mut ctx_str = String::new();
+    let mut input_path: Option<PathBuf> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--key" | "-k" => {
+                i += 1;
+                key_path = Some(PathBuf::from(args.get(i).ok_or("missing key path")?));
+            }
+            "--aad" | "-a" => {
+                i += 1;
+                aad_str = args.get(i).ok_or("missing aad")?.clone();
+            }
+            "--context" | "-c" => {
+                i += 1;
+                ctx_str = args.get(i).ok_or("missing context")?.clone();
+            }
+            "--input" | "-i" => {
+                i += 1;
+                input_path = Some(PathBuf::from(args.get(i).ok_or("missing input path")?));
+            }
+            _ => return Err(format!("unknown option: {}", args[i]).into()),
+        }
+        i += 1;
+    }
+
+    let key_path = key_path.ok_or("missing --key")?;
+    // The plaintext is zeroized on drop and never leaves this process.
+    decrypt_input(&key_path, input_path.as_deref(), &aad_str, &ctx_str)?;
+    Ok(())
+}
+
+/// Shared by `open` and `verify`: load the secret key, read the ciphertext
+/// (stdin if no path) and decrypt it.
+fn decrypt_input(
+    key_path: &Path,
+    input_path: Option<&Path>,
+    aad_str: &str,
+    ctx_str: &str,
+) -> Result<(Vec<u8>, Zeroizing<Vec<u8>>), Box<dyn std::error::Error>> {
     // Load secret key
-    let sk_bytes = fs::read(&key_path)?;
+    let sk_bytes = fs::read(key_path)?;
     let sk = SecretKey::from_bytes(&sk_bytes).map_err(|_| "invalid secret key")?;
 
     // Read ciphertext
-    let ciphertext = if let Some(ref path) = input_path {
+    let ciphertext = if let Some(path) = input_path {
         fs::read(path)?
     } else {
         let mut buf = Vec::new();
@@ -261,34 +339,27 @@ fn cmd_open(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     let aad = Aad::raw(aad_str.as_bytes());
     let ctx = Context::raw(ctx_str.as_bytes());
     let plaintext = citadel
-        .open(&sk, &ciphertext, &aad, &ctx)
+        .open(&sk, container::unframe(&ciphertext), &aad, &ctx)
         .map_err(|_| "decryption failed")?;
-
-    // Write plaintext
-    if let Some(ref path) = output_path {
-        fs::write(path, &plaintext)?;
-        eprintln!("Decrypted {} bytes -> {} bytes", ciphertext.len(), plaintext.len());
-        eprintln!("Output: {}", path.display());
-    } else {
-        io::stdout().write_all(&plaintext)?;
-    }
-
-    Ok(())
+    Ok((ciphertext, Zeroizing::new(plaintext)))
 }
 
 fn cmd_inspect(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     let input_path = args.first().ok_or("missing file path")?;
     
-    let ciphertext = fs::read(input_path)?;
+    let data = fs::read(input_path)?;
+    let ciphertext = container::unframe(&data);
     
-    let info = citadel_envelope::inspect(&ciphertext)
-        .map_err(|_| "invalid ciphertext format")?;
+    let info = citadel_envelope::inspect_detailed(ciphertext)
+        .map_err(|e| format!("invalid ciphertext: {}", e))?;
 
     println!("Citadel Ciphertext");
     println!("==================");
+    println!("Container:       {}", if container::is_framed(&data) { "CTD1" } else { "raw" });
     println!("Version:         {}", info.version);
     println!("KEM Suite:       {}", info.kem_suite);
     println!("AEAD Suite:      {}", info.aead_suite);
+    println!("Labeled:         {}", if info.labeled { "yes" } else { "no" });
     println!();
     println!("Total Size:      {} bytes", info.total_bytes);
     println!("Plaintext Size:  ~{} bytes", info.plaintext_bytes);
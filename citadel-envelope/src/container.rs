@@ -0,0 +1,35 @@
+//! File container (CLI framing)
+//!
+//! Format:
+//!   magic[4] = "CTD1" || ciphertext
+//!
+//! The magic lets `file(1)` and friends recognize sealed files. It sits
+//! outside the wire format: nothing here is authenticated, and `open`
+//! never sees it. A raw ciphertext starts with `PROTOCOL_VERSION`, which
+//! is not `b'C'`, so framed and unframed input are told apart by the
+//! first byte.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+/// Leading bytes of a framed file.
+pub const MAGIC: [u8; 4] = *b"CTD1";
+
+/// Prepend [`MAGIC`] to a ciphertext.
+pub fn frame(ciphertext: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(MAGIC.len() + ciphertext.len());
+    out.extend_from_slice(&MAGIC);
+    out.extend_from_slice(ciphertext);
+    out
+}
+
+/// Whether `data` starts with [`MAGIC`].
+pub fn is_framed(data: &[u8]) -> bool {
+    data.starts_with(&MAGIC)
+}
+
+/// The ciphertext inside `data`: `data` without [`MAGIC`] if it is
+/// framed, `data` unchanged if not.
+pub fn unframe(data: &[u8]) -> &[u8] {
+    data.strip_prefix(&MAGIC[..]).unwrap_or(data)
+}
@@ -77,6 +77,7 @@ impl Envelope {
     }
 
     /// Convenience: build context + aad and then seal.
+    #[allow(clippy::too_many_arguments)]
     pub fn seal_internal(
         &self,
         pk: &PublicKey,
@@ -96,6 +97,7 @@ impl Envelope {
     }
 
     /// Convenience: build context + aad and then open.
+    #[allow(clippy::too_many_arguments)]
     pub fn open_internal(
         &self,
         sk: &SecretKey,
@@ -111,7 +113,7 @@ impl Envelope {
     ) -> Result<Vec<u8>, DecryptionError> {
         let ctx = aad::build_context(env, purpose);
         let aad_bytes = aad::build_aad(sender_id, recipient_id, route, ts_unix_ms, seq, msg_id)
-            .map_err(|_| DecryptionError)?;
+            .map_err(|_| DecryptionError::new())?;
         self.open(sk, ciphertext, &aad_bytes, &ctx)
     }
 
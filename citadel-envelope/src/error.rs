@@ -2,8 +2,50 @@
 
 use core::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct DecryptionError;
+/// Opening failed.
+///
+/// Opaque: build it with [`DecryptionError::new`]. The type has the same
+/// shape with or without `diagnostic-errors`. With the feature it also
+/// records which stage of `open` failed, shown in `Debug` and via
+/// [`category`] but never in `Display`. All values compare equal.
+///
+/// [`category`]: DecryptionError::category
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DecryptionError {
+    #[cfg_attr(not(feature = "diagnostic-errors"), allow(dead_code))]
+    category: Category,
+}
+
+#[cfg(not(feature = "diagnostic-errors"))]
+type Category = ();
+#[cfg(feature = "diagnostic-errors")]
+type Category = Option<OpenFailure>;
+
+impl DecryptionError {
+    /// An uncategorized decryption error.
+    pub const fn new() -> Self {
+        #[cfg(not(feature = "diagnostic-errors"))]
+        let category = ();
+        #[cfg(feature = "diagnostic-errors")]
+        let category = None;
+        Self { category }
+    }
+
+    /// Which stage of `open` failed, if known. Development aid only:
+    /// wrong key, AAD and context are all `AeadVerification`.
+    #[cfg(feature = "diagnostic-errors")]
+    pub fn category(&self) -> Option<OpenFailure> {
+        self.category
+    }
+}
+
+impl PartialEq for DecryptionError {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for DecryptionError {}
 
 impl fmt::Display for DecryptionError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -14,21 +56,70 @@ impl fmt::Display for DecryptionError {
 #[cfg(feature = "std")]
 impl std::error::Error for DecryptionError {}
 
+/// Sealing failed.
+///
+/// Opaque, like `DecryptionError`: `Display` is the fixed string
+/// `"encryption failed"` so messages that reach logs or clients never name
+/// the internal step. Encode-time detail lives in the internal
+/// `wire::EncodeError`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct EncodingError;
 
 impl fmt::Display for EncodingError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "encoding error")
+        write!(f, "encryption failed")
     }
 }
 
 #[cfg(feature = "std")]
 impl std::error::Error for EncodingError {}
 
+/// Which stage of `open` failed. For operator diagnostics only.
+///
+/// Never return this to the party that supplied the ciphertext: telling
+/// decode, decapsulation and AEAD failures apart is exactly the oracle the
+/// uniform `DecryptionError` exists to prevent. A wrong key, AAD or context
+/// all surface as `AeadVerification` (ML-KEM uses implicit rejection and the
+/// context only feeds the KDF).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenFailure {
+    /// The ciphertext could not be parsed (length, version, suite).
+    Decode,
+    /// KEM decapsulation rejected the encapsulated key.
+    Decapsulation,
+    /// Key derivation from the shared secret failed.
+    KeyDerivation,
+    /// The AEAD tag did not verify.
+    AeadVerification,
+}
+
+impl fmt::Display for OpenFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Decode => "decode",
+            Self::Decapsulation => "decapsulation",
+            Self::KeyDerivation => "key derivation",
+            Self::AeadVerification => "aead verification",
+        };
+        f.write_str(s)
+    }
+}
+
+impl From<OpenFailure> for DecryptionError {
+    #[cfg(not(feature = "diagnostic-errors"))]
+    fn from(_: OpenFailure) -> Self {
+        DecryptionError::new()
+    }
+
+    #[cfg(feature = "diagnostic-errors")]
+    fn from(failure: OpenFailure) -> Self {
+        DecryptionError { category: Some(failure) }
+    }
+}
+
 /// Normalize encode errors into decrypt errors (oracle discipline).
 impl From<EncodingError> for DecryptionError {
     fn from(_: EncodingError) -> Self {
-        DecryptionError
+        DecryptionError::new()
     }
 }
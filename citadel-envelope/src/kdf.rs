@@ -1,7 +1,14 @@
 //! KDF (v1 structured)
 //!
-//! info = PROTOCOL_ID || b"|aes|" || ct_hash || context
+//! info = prefix || b"|aes|" || ct_hash || context
 //! key  = HKDF-SHA256(shared_secret, salt=None, info=info, len=32)
+//!
+//! Labeled (`FLAG_LABELED`):
+//! info = prefix || b"|aes-label|" || ct_hash || label_len[2] || label || context
+//!
+//! `prefix` is [`KdfParams::info`], `PROTOCOL_ID` unless overridden with
+//! `Citadel::with_kdf_params`. Only [`KdfParams`] and [`SaltSource`] are
+//! public; the derivation itself stays internal.
 
 extern crate alloc;
 use alloc::vec::Vec;
@@ -13,18 +20,96 @@ use sha3::{Digest, Sha3_256};
 use crate::error::EncodingError;
 use crate::wire::PROTOCOL_ID;
 
-pub fn ct_hash(kem_ct: &[u8]) -> [u8; 32] {
+/// Where the HKDF salt comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SaltSource {
+    /// No salt (HKDF's zero-filled default). The SHA3-256 hash of the KEM
+    /// ciphertext is bound through `info` instead.
+    Unsalted,
+}
+
+/// HKDF parameters used to derive the AEAD key, for compliance mapping.
+///
+/// The default is the frozen v1 construction. Overriding `info` gives an
+/// application its own domain separation; ciphertexts only open under the
+/// same `info` they were sealed with, and nothing on the wire records it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KdfParams {
+    /// How the HKDF salt is chosen. Fixed to [`SaltSource::Unsalted`].
+    pub salt_source: SaltSource,
+    /// Prefix of the HKDF `info` input, ahead of the `|aes|` tag, the
+    /// ciphertext hash and the context.
+    pub info: Vec<u8>,
+}
+
+impl KdfParams {
+    /// Parameters with `info` as the HKDF info prefix.
+    pub fn with_info(info: impl Into<Vec<u8>>) -> Self {
+        Self {
+            salt_source: SaltSource::Unsalted,
+            info: info.into(),
+        }
+    }
+
+    /// Whether these are the frozen v1 defaults.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self::with_info(PROTOCOL_ID)
+    }
+}
+
+impl core::fmt::Display for KdfParams {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let salt = match self.salt_source {
+            SaltSource::Unsalted => "none",
+        };
+        write!(
+            f,
+            "HKDF-SHA256(salt={}, info=\"{}\" || tag || SHA3-256(kem_ct) || context, len=32)",
+            salt,
+            self.info.escape_ascii()
+        )
+    }
+}
+
+pub(crate) fn ct_hash(kem_ct: &[u8]) -> [u8; 32] {
     let h = Sha3_256::digest(kem_ct);
     let mut out = [0u8; 32];
     out.copy_from_slice(&h);
     out
 }
 
-pub fn derive_key(shared_secret: &[u8], ct_hash: &[u8; 32], context: &[u8]) -> Result<[u8; 32], EncodingError> {
-    let mut info = Vec::with_capacity(PROTOCOL_ID.len() + 5 + 32 + context.len());
-    info.extend_from_slice(PROTOCOL_ID);
-    info.extend_from_slice(b"|aes|");
-    info.extend_from_slice(ct_hash);
+/// The optional caller label is length-prefixed under a distinct tag, so
+/// no (context, label) pair collides with an unlabeled context.
+pub(crate) fn derive_key(
+    params: &KdfParams,
+    shared_secret: &[u8],
+    ct_hash: &[u8; 32],
+    context: &[u8],
+    label: Option<&[u8]>,
+) -> Result<[u8; 32], EncodingError> {
+    let label_bytes = label.map_or(0, |l| 2 + l.len());
+    let mut info = Vec::with_capacity(params.info.len() + 11 + 32 + label_bytes + context.len());
+    info.extend_from_slice(&params.info);
+    match label {
+        None => {
+            info.extend_from_slice(b"|aes|");
+            info.extend_from_slice(ct_hash);
+        }
+        Some(label) => {
+            let len = u16::try_from(label.len()).map_err(|_| EncodingError)?;
+            info.extend_from_slice(b"|aes-label|");
+            info.extend_from_slice(ct_hash);
+            info.extend_from_slice(&len.to_be_bytes());
+            info.extend_from_slice(label);
+        }
+    }
     info.extend_from_slice(context);
 
     let hk = Hkdf::<Sha256>::new(None, shared_secret);
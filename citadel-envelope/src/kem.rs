@@ -22,7 +22,7 @@ use ml_kem::{
     kem::{Decapsulate, Encapsulate},
     Ciphertext, EncodedSizeUser, KemCore, MlKem768, MlKem768Params,
 };
-use rand_core::OsRng;
+use rand_core::{CryptoRng, OsRng, RngCore};
 use x25519_dalek::{EphemeralSecret, PublicKey as X25519PublicKey, StaticSecret};
 
 use crate::error::{DecryptionError, EncodingError};
@@ -65,17 +65,17 @@ impl PublicKey {
 
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecryptionError> {
         if bytes.len() != KEM_PUBLIC_KEY_BYTES {
-            return Err(DecryptionError);
+            return Err(DecryptionError::new());
         }
 
         let x25519_bytes: [u8; X25519_KEY_BYTES] = bytes[..X25519_KEY_BYTES]
             .try_into()
-            .map_err(|_| DecryptionError)?;
+            .map_err(|_| DecryptionError::new())?;
         let x25519 = X25519PublicKey::from(x25519_bytes);
 
         let mlkem_bytes: [u8; MLKEM_PUBLIC_KEY_BYTES] = bytes[X25519_KEY_BYTES..]
             .try_into()
-            .map_err(|_| DecryptionError)?;
+            .map_err(|_| DecryptionError::new())?;
         let mlkem = Ek::from_bytes(&mlkem_bytes.into());
 
         Ok(Self { x25519, mlkem })
@@ -116,22 +116,27 @@ impl SecretKey {
 
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecryptionError> {
         if bytes.len() != KEM_SECRET_KEY_BYTES {
-            return Err(DecryptionError);
+            return Err(DecryptionError::new());
         }
 
         let x25519_bytes: [u8; X25519_KEY_BYTES] = bytes[..X25519_KEY_BYTES]
             .try_into()
-            .map_err(|_| DecryptionError)?;
+            .map_err(|_| DecryptionError::new())?;
         let x25519 = StaticSecret::from(x25519_bytes);
 
         let mlkem_bytes: [u8; MLKEM_SECRET_KEY_BYTES] = bytes[X25519_KEY_BYTES..]
             .try_into()
-            .map_err(|_| DecryptionError)?;
+            .map_err(|_| DecryptionError::new())?;
         let mlkem = Dk::from_bytes(&mlkem_bytes.into());
 
         Ok(Self { x25519, mlkem })
     }
 
+    /// The public key belonging to this secret key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from_parts(X25519PublicKey::from(&self.x25519), self.mlkem.encapsulation_key().clone())
+    }
+
     pub(crate) fn x25519(&self) -> &StaticSecret {
         &self.x25519
     }
@@ -145,10 +150,32 @@ impl SecretKey {
 // KEM provider trait + hybrid implementation
 // ---------------------------------------------------------------------------
 
+/// A KEM suite the engine can be instantiated with
+/// (see [`Citadel::with_provider`](crate::Citadel::with_provider)).
+///
+/// `SUITE_KEM`, the `_with_rng` methods and `decapsulate` are required;
+/// `keygen` and `encapsulate` default to the `_with_rng` versions with the
+/// OS RNG.
 pub trait KemProvider {
-    fn keygen() -> (PublicKey, SecretKey);
+    /// On-wire `suite_kem` byte written into every ciphertext sealed with
+    /// this provider. Opening rejects ciphertexts carrying any other suite,
+    /// so each provider needs a byte of its own.
+    const SUITE_KEM: u8;
+
+    fn keygen() -> (PublicKey, SecretKey) {
+        Self::keygen_with_rng(&mut OsRng)
+    }
+    /// Keygen drawing all randomness from `rng` (tests, KAT vectors, fuzzing).
+    fn keygen_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey);
     /// Returns (combined_shared_secret, kem_ciphertext_bytes).
-    fn encapsulate(pk: &PublicKey) -> Result<(Vec<u8>, Vec<u8>), EncodingError>;
+    fn encapsulate(pk: &PublicKey) -> Result<(Vec<u8>, Vec<u8>), EncodingError> {
+        Self::encapsulate_with_rng(pk, &mut OsRng)
+    }
+    /// Encapsulate drawing all randomness from `rng` (tests, KAT vectors, fuzzing).
+    fn encapsulate_with_rng<R: RngCore + CryptoRng>(
+        pk: &PublicKey,
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Vec<u8>), EncodingError>;
     /// Returns combined_shared_secret.
     fn decapsulate(sk: &SecretKey, ct: &[u8]) -> Result<Vec<u8>, DecryptionError>;
 }
@@ -160,13 +187,15 @@ pub trait KemProvider {
 pub struct HybridX25519MlKem768Provider;
 
 impl KemProvider for HybridX25519MlKem768Provider {
-    fn keygen() -> (PublicKey, SecretKey) {
+    const SUITE_KEM: u8 = crate::wire::KemSuite::HybridX25519MlKem768.to_byte();
+
+    fn keygen_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
         // X25519 long-term keypair
-        let x25519_sk = StaticSecret::random_from_rng(OsRng);
+        let x25519_sk = StaticSecret::random_from_rng(&mut *rng);
         let x25519_pk = X25519PublicKey::from(&x25519_sk);
 
         // ML-KEM-768 keypair (generate returns (dk, ek))
-        let (mlkem_dk, mlkem_ek) = MlKem768::generate(&mut OsRng);
+        let (mlkem_dk, mlkem_ek) = MlKem768::generate(rng);
 
         (
             PublicKey::from_parts(x25519_pk, mlkem_ek),
@@ -174,16 +203,19 @@ impl KemProvider for HybridX25519MlKem768Provider {
         )
     }
 
-    fn encapsulate(pk: &PublicKey) -> Result<(Vec<u8>, Vec<u8>), EncodingError> {
+    fn encapsulate_with_rng<R: RngCore + CryptoRng>(
+        pk: &PublicKey,
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Vec<u8>), EncodingError> {
         // X25519: generate ephemeral keypair, compute DH shared secret
-        let x25519_eph = EphemeralSecret::random_from_rng(OsRng);
+        let x25519_eph = EphemeralSecret::random_from_rng(&mut *rng);
         let x25519_eph_pk = X25519PublicKey::from(&x25519_eph);
         let x25519_ss = x25519_eph.diffie_hellman(pk.x25519());
 
         // ML-KEM-768: encapsulate
         let (mlkem_ct, mlkem_ss) = pk
             .mlkem()
-            .encapsulate(&mut OsRng)
+            .encapsulate(rng)
             .map_err(|_| EncodingError)?;
 
         // Combined shared secret: x25519_ss[32] || mlkem_ss[32]
@@ -201,17 +233,17 @@ impl KemProvider for HybridX25519MlKem768Provider {
 
     fn decapsulate(sk: &SecretKey, ct: &[u8]) -> Result<Vec<u8>, DecryptionError> {
         if ct.len() != KEM_CIPHERTEXT_BYTES {
-            return Err(DecryptionError);
+            return Err(DecryptionError::new());
         }
 
         // Parse: x25519_ephemeral_pk[32] || mlkem_ct[1088]
         let x25519_epk_bytes: [u8; X25519_KEY_BYTES] = ct[..X25519_KEY_BYTES]
             .try_into()
-            .map_err(|_| DecryptionError)?;
+            .map_err(|_| DecryptionError::new())?;
         let x25519_epk = X25519PublicKey::from(x25519_epk_bytes);
 
         let mlkem_ct_bytes = &ct[X25519_KEY_BYTES..];
-        let mlkem_ct = MlKemCt::try_from(mlkem_ct_bytes).map_err(|_| DecryptionError)?;
+        let mlkem_ct = MlKemCt::try_from(mlkem_ct_bytes).map_err(|_| DecryptionError::new())?;
 
         // X25519 DH
         let x25519_ss = sk.x25519().diffie_hellman(&x25519_epk);
@@ -220,7 +252,7 @@ impl KemProvider for HybridX25519MlKem768Provider {
         let mlkem_ss = sk
             .mlkem()
             .decapsulate(&mlkem_ct)
-            .map_err(|_| DecryptionError)?;
+            .map_err(|_| DecryptionError::new())?;
 
         // Combined shared secret: x25519_ss[32] || mlkem_ss[32]
         let mut combined_ss = Vec::with_capacity(SHARED_SECRET_BYTES * 2);
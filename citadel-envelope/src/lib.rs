@@ -22,7 +22,9 @@
 //! ## Security Properties
 //!
 //! - **Hybrid KEM**: X25519 + ML-KEM-768 Ã¢â‚¬â€ secure if either holds
-//! - **Uniform errors**: All failures produce identical error type
+//! - **Uniform errors**: All failures produce identical error type (the
+//!   `diagnostic-errors` feature adds a `Debug`-only failure category for
+//!   development builds)
 //! - **AAD/context binding**: Wrong metadata causes decryption failure
 //! - **Stable wire format**: Versioned, self-describing
 //!
@@ -31,7 +33,8 @@
 //! - Key management
 //! - Streaming encryption
 //! - FIPS certification
-//! - Constant-time guarantees
+//! - Constant-time guarantees (malformed input is padded out to a dummy
+//!   decapsulation + AEAD check, but timing still varies with length)
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![deny(unsafe_code)]
@@ -45,14 +48,19 @@ extern crate alloc;
 
 mod aead;
 mod error;
-mod kdf;
 mod kem;
 
+// KDF parameters are public for compliance mapping; the derivation is not
+pub mod kdf;
+
 // Wire module needs to be pub(crate) for CLI inspect command
 // but should not be considered stable API
 #[doc(hidden)]
 pub mod wire;
 
+// `CTD1` file framing written by the CLIs; not part of the wire format
+pub mod container;
+
 // Legacy internal modules (hidden from docs)
 #[doc(hidden)]
 pub mod aad;
@@ -70,7 +78,10 @@ pub use sdk::{
     // Main types
     Citadel,
     Aad,
+    AadRef,
+    AadBuilder,
     Context,
+    ContextRef,
     
     // Error types
     SealError,
@@ -82,14 +93,32 @@ pub use sdk::{
     
     // Inspection
     CiphertextInfo,
+    KemSuite,
+    AeadSuite,
+    WireError,
     inspect,
+    inspect_detailed,
     
+    // KEM suites
+    KemProvider,
+    HybridX25519MlKem768Provider,
+
+    // Raw KEM
+    SharedSecret,
+    KemCiphertext,
+
     // Constants
     VERSION,
     PROTOCOL_VERSION,
     MIN_CIPHERTEXT_BYTES,
+    DEFAULT_MAX_CIPHERTEXT_BYTES,
+    MAX_AAD_BYTES,
+    MAX_LABEL_BYTES,
 };
 
+// Nonce sources for `Citadel::with_nonce_source`
+pub use aead::{NonceSource, OsNonceSource};
+
 // ---------------------------------------------------------------------------
 // Internal engine alias (not public API)
 // ---------------------------------------------------------------------------
@@ -110,14 +139,21 @@ pub type CitadelHybrid = CitadelEngine;
 
 // Internal engine (not part of public API, but needed for legacy compat)
 mod kem_engine {
+    use alloc::boxed::Box;
     use alloc::vec::Vec;
+    use rand_core::{CryptoRng, RngCore};
     use zeroize::Zeroizing;
     
-    use crate::error::{DecryptionError, EncodingError};
+    use crate::aead::{NonceSource, OsNonceSource};
+    use crate::error::{DecryptionError, EncodingError, OpenFailure};
     use crate::kem::{KemProvider, PublicKey, SecretKey};
     use crate::{aead, kdf, wire};
 
     pub struct Citadel<K: KemProvider> {
+        kdf_params: kdf::KdfParams,
+        nonce_source: Box<dyn NonceSource>,
+        max_ciphertext_len: usize,
+        bind_header: bool,
         _marker: core::marker::PhantomData<K>,
     }
 
@@ -129,15 +165,53 @@ mod kem_engine {
 
     impl<K: KemProvider> Citadel<K> {
         pub fn new() -> Self {
+            Self::with_kdf_params(kdf::KdfParams::default())
+        }
+
+        pub fn with_kdf_params(kdf_params: kdf::KdfParams) -> Self {
             Self {
+                kdf_params,
+                nonce_source: Box::new(OsNonceSource),
+                max_ciphertext_len: crate::sdk::DEFAULT_MAX_CIPHERTEXT_BYTES,
+                bind_header: false,
                 _marker: core::marker::PhantomData,
             }
         }
 
+        pub fn kdf_params(&self) -> &kdf::KdfParams {
+            &self.kdf_params
+        }
+
+        pub fn set_kdf_params(&mut self, kdf_params: kdf::KdfParams) {
+            self.kdf_params = kdf_params;
+        }
+
+        /// Nonces for `encrypt` / `encrypt_labeled`; `encrypt_with_rng`
+        /// still draws its nonce from the caller's RNG.
+        pub fn set_nonce_source(&mut self, source: Box<dyn NonceSource>) {
+            self.nonce_source = source;
+        }
+
+        /// Longer ciphertexts take the malformed path in `decrypt`, before
+        /// anything proportional to their length is allocated.
+        pub fn set_max_ciphertext_len(&mut self, max: usize) {
+            self.max_ciphertext_len = max;
+        }
+
+        /// Set `FLAG_HEADER_BOUND` on new ciphertexts. `decrypt` accepts
+        /// both forms regardless.
+        pub fn set_header_binding(&mut self, enabled: bool) {
+            self.bind_header = enabled;
+        }
+
         pub fn keygen(&self) -> (PublicKey, SecretKey) {
             K::keygen()
         }
 
+        pub fn keygen_with_rng<R: RngCore + CryptoRng>(&self, rng: &mut R) -> (PublicKey, SecretKey) {
+            K::keygen_with_rng(rng)
+        }
+
         pub fn encrypt(
             &self,
             pk: &PublicKey,
@@ -146,14 +220,72 @@ mod kem_engine {
             context: &[u8],
         ) -> Result<Vec<u8>, EncodingError> {
             let (ss_raw, kem_ct) = K::encapsulate(pk)?;
+            let nonce = self.nonce_source.next_nonce()?;
+            self.finish_encrypt(ss_raw, &kem_ct, &nonce, plaintext, aad, context, None)
+        }
+
+        /// Encrypt with `label` mixed into the KDF and its length in the header.
+        pub fn encrypt_labeled(
+            &self,
+            pk: &PublicKey,
+            plaintext: &[u8],
+            aad: &[u8],
+            context: &[u8],
+            label: &[u8],
+        ) -> Result<Vec<u8>, EncodingError> {
+            let (ss_raw, kem_ct) = K::encapsulate(pk)?;
+            let nonce = self.nonce_source.next_nonce()?;
+            self.finish_encrypt(ss_raw, &kem_ct, &nonce, plaintext, aad, context, Some(label))
+        }
+
+        /// Encrypt with all randomness (KEM ephemerals + nonce) drawn from `rng`.
+        pub fn encrypt_with_rng<R: RngCore + CryptoRng>(
+            &self,
+            pk: &PublicKey,
+            plaintext: &[u8],
+            aad: &[u8],
+            context: &[u8],
+            rng: &mut R,
+        ) -> Result<Vec<u8>, EncodingError> {
+            let (ss_raw, kem_ct) = K::encapsulate_with_rng(pk, rng)?;
+            let nonce = aead::nonce_with_rng(rng);
+            self.finish_encrypt(ss_raw, &kem_ct, &nonce, plaintext, aad, context, None)
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        fn finish_encrypt(
+            &self,
+            ss_raw: Vec<u8>,
+            kem_ct: &[u8],
+            nonce: &[u8; 12],
+            plaintext: &[u8],
+            aad: &[u8],
+            context: &[u8],
+            label: Option<&[u8]>,
+        ) -> Result<Vec<u8>, EncodingError> {
+            let label_len = label
+                .map(|l| u16::try_from(l.len()).map_err(|_| EncodingError))
+                .transpose()?;
             let shared_secret = Zeroizing::new(ss_raw);
-            let ct_hash = kdf::ct_hash(&kem_ct);
-            let aes_key = Zeroizing::new(kdf::derive_key(&shared_secret, &ct_hash, context)?);
-            let nonce = aead::nonce()?;
-            let aead_ct = aead::aead_seal(&aes_key, &nonce, plaintext, aad)?;
-            wire::encode_wire(&kem_ct, &nonce, &aead_ct)
+            let ct_hash = kdf::ct_hash(kem_ct);
+            let aes_key = Zeroizing::new(kdf::derive_key(&self.kdf_params, &shared_secret, &ct_hash, context, label)?);
+            let header = wire::encode_header(K::SUITE_KEM, wire::AeadSuite::Aes256Gcm.to_byte(), label_len, self.bind_header, kem_ct)?;
+            let aead_ct = aead::aead_seal(&aes_key, nonce, plaintext, &wire::aead_aad(&header, aad))?;
+            wire::finish_wire(header, kem_ct, nonce, &aead_ct).map_err(EncodingError::from)
         }
 
+        /// Decrypt a v1 ciphertext.
+        ///
+        /// Malformed input does not short-circuit: when `decode_wire` rejects
+        /// the ciphertext, a fixed dummy KEM ciphertext, nonce and tag are run
+        /// through decapsulation, the KDF and AEAD verification before the
+        /// uniform error is returned. This keeps "malformed" and "well-formed
+        /// but wrong" on roughly the same timing profile.
+        ///
+        /// Residual leakage: the dummy AEAD input is tag-only, so the time
+        /// spent verifying a well-formed ciphertext still scales with its
+        /// length, and the underlying primitives are only as constant-time as
+        /// their implementations.
         pub fn decrypt(
             &self,
             sk: &SecretKey,
@@ -161,15 +293,74 @@ mod kem_engine {
             aad: &[u8],
             context: &[u8],
         ) -> Result<Vec<u8>, DecryptionError> {
-            let parts = wire::decode_wire(ciphertext)?;
-            let ss_raw = K::decapsulate(sk, parts.kem_ciphertext)?;
-            let shared_secret = Zeroizing::new(ss_raw);
-            let ct_hash = kdf::ct_hash(parts.kem_ciphertext);
+            self.decrypt_diagnostic(sk, ciphertext, aad, context)
+                .map_err(DecryptionError::from)
+        }
+
+        /// Same work as `decrypt`, but reports which stage failed.
+        /// Every stage still runs, so timing matches `decrypt`.
+        pub fn decrypt_diagnostic(
+            &self,
+            sk: &SecretKey,
+            ciphertext: &[u8],
+            aad: &[u8],
+            context: &[u8],
+        ) -> Result<Vec<u8>, OpenFailure> {
+            self.decrypt_diagnostic_labeled(sk, ciphertext, aad, context, None)
+        }
+
+        /// `decrypt_diagnostic` for a ciphertext sealed with `label` (`None`
+        /// for unlabeled). A label of the wrong length, or a labeled/unlabeled
+        /// mismatch, takes the malformed path.
+        pub fn decrypt_diagnostic_labeled(
+            &self,
+            sk: &SecretKey,
+            ciphertext: &[u8],
+            aad: &[u8],
+            context: &[u8],
+            label: Option<&[u8]>,
+        ) -> Result<Vec<u8>, OpenFailure> {
+            const DUMMY_KEM_CT: [u8; wire::KEM_CIPHERTEXT_BYTES] = [0u8; wire::KEM_CIPHERTEXT_BYTES];
+            const DUMMY_NONCE: [u8; wire::NONCE_BYTES] = [0u8; wire::NONCE_BYTES];
+            const DUMMY_AEAD_CT: [u8; wire::AEAD_TAG_BYTES] = [0u8; wire::AEAD_TAG_BYTES];
+
+            let parsed = if ciphertext.len() > self.max_ciphertext_len {
+                Err(DecryptionError::new())
+            } else {
+                wire::decode_wire_for_suite(ciphertext, K::SUITE_KEM)
+            };
+            let parsed = parsed.and_then(|parts| {
+                if parts.label_len.map(usize::from) == label.map(<[u8]>::len) {
+                    Ok(parts)
+                } else {
+                    Err(DecryptionError::new())
+                }
+            });
+            let malformed = parsed.is_err();
+            let (header, kem_ct, nonce, aead_ct): (&[u8], &[u8], &[u8; wire::NONCE_BYTES], &[u8]) = match &parsed {
+                Ok(parts) => (parts.header, parts.kem_ciphertext, parts.nonce, parts.aead_ciphertext),
+                Err(_) => (&[], &DUMMY_KEM_CT, &DUMMY_NONCE, &DUMMY_AEAD_CT),
+            };
+
+            let decap = K::decapsulate(sk, kem_ct);
+            let decap_failed = decap.is_err();
+            let shared_secret = Zeroizing::new(
+                decap.unwrap_or_else(|_| alloc::vec![0u8; 2 * wire::SHARED_SECRET_BYTES]),
+            );
+            let ct_hash = kdf::ct_hash(kem_ct);
             let aes_key = Zeroizing::new(
-                kdf::derive_key(&shared_secret, &ct_hash, context)
-                    .map_err(|_| DecryptionError)?,
+                kdf::derive_key(&self.kdf_params, &shared_secret, &ct_hash, context, label)
+                    .map_err(|_| OpenFailure::KeyDerivation)?,
             );
-            aead::aead_open(&aes_key, parts.nonce, parts.aead_ciphertext, aad)
+            let opened = aead::aead_open(&aes_key, nonce, aead_ct, &wire::aead_aad(header, aad));
+
+            if malformed {
+                return Err(OpenFailure::Decode);
+            }
+            if decap_failed {
+                return Err(OpenFailure::Decapsulation);
+            }
+            opened.map_err(|_| OpenFailure::AeadVerification)
         }
 
         #[inline]
@@ -202,6 +393,6 @@ pub use aad::MsgId16;
 #[doc(hidden)]
 pub use envelope::Envelope;
 #[doc(hidden)]
-pub use error::{DecryptionError, EncodingError};
+pub use error::{DecryptionError, EncodingError, OpenFailure};
 #[doc(hidden)]
-pub use kem::{HybridX25519MlKem768Provider, KemProvider, MlKem768Provider};
\ No newline at end of file
+pub use kem::MlKem768Provider;
\ No newline at end of file
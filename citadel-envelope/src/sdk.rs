@@ -10,6 +10,7 @@
 //! - `PublicKey`, `SecretKey` Ã¢â‚¬â€ key types with serialization
 //! - `Aad`, `Context` Ã¢â‚¬â€ typed metadata (prevents misuse)
 //! - `SealError`, `OpenError` Ã¢â‚¬â€ uniform error types
+//! - `KemProvider` — KEM suite selection via `Citadel::with_provider`
 //!
 //! Internal modules (`wire`, `kdf`, `aead`, `kem`) are NOT part of the
 //! public API and may change without notice.
@@ -17,13 +18,19 @@
 extern crate alloc;
 
 use alloc::format;
+use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt;
 
+use rand_core::{CryptoRng, RngCore};
+
 // Re-export only what customers need
 pub use crate::error::DecryptionError as OpenError;
 pub use crate::error::EncodingError as SealError;
-pub use crate::kem::{PublicKey, SecretKey};
+pub use crate::kem::{HybridX25519MlKem768Provider, KemProvider, PublicKey, SecretKey};
+use crate::aead::NonceSource;
+use crate::kdf::KdfParams;
+pub use crate::wire::{AeadSuite, KemSuite, WireError};
 
 // ---------------------------------------------------------------------------
 // Typed AAD and Context (prevents misuse)
@@ -33,7 +40,9 @@ pub use crate::kem::{PublicKey, SecretKey};
 ///
 /// Use the builder methods to construct AAD for common use cases.
 /// This prevents accidental misuse and standardizes behavior across deployments.
-#[derive(Clone, Debug)]
+///
+/// Constructors copy their input; see [`AadRef`] to pass borrowed bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Aad {
     inner: Vec<u8>,
 }
@@ -89,19 +98,146 @@ impl Aad {
         }
     }
 
+    /// AAD from named fields, encoded canonically: keys are sorted and
+    /// every key and value is length-prefixed, so call sites that list the
+    /// same fields in a different order still produce identical bytes.
+    ///
+    /// Fails on a duplicate key or a key or value over 65535 bytes.
+    ///
+    /// ```
+    /// use citadel_envelope::Aad;
+    ///
+    /// let a = Aad::from_fields(&[("table", "users"), ("column", "ssn")]).unwrap();
+    /// let b = Aad::from_fields(&[("column", "ssn"), ("table", "users")]).unwrap();
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn from_fields(fields: &[(&str, &str)]) -> Result<Self, SealError> {
+        let inner = crate::aad::encode_fields(crate::aad::AAD_FIELDS_PREFIX, fields)?;
+        Ok(Self { inner })
+    }
+
+    /// Start building a structured, anti-replay AAD.
+    ///
+    /// Produces the canonical TLV encoding (`citadel|aad|v1` prefix +
+    /// sender/recipient/route/timestamp/sequence/msg_id fields).
+    pub fn builder() -> AadBuilder {
+        AadBuilder::default()
+    }
+
+    /// Length in bytes.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Whether this is zero-length.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
     /// Access the raw bytes (for internal use).
     pub(crate) fn as_bytes(&self) -> &[u8] {
         &self.inner
     }
 }
 
+/// Builder for structured AAD — see [`Aad::builder`].
+///
+/// Unset string fields encode as empty, unset timestamp/sequence as 0.
+/// If no `msg_id` is supplied, a random one is generated at `build()` time.
+///
+/// ```
+/// use citadel_envelope::Aad;
+///
+/// let aad = Aad::builder()
+///     .sender("billing")
+///     .recipient("ledger")
+///     .route("invoices/v2")
+///     .timestamp_ms(1_700_000_000_000)
+///     .sequence(7)
+///     .msg_id([0x11; 16])
+///     .build()
+///     .unwrap();
+/// assert!(aad.len() > 0);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AadBuilder {
+    sender: String,
+    recipient: String,
+    route: String,
+    timestamp_ms: u64,
+    sequence: u64,
+    msg_id: Option<[u8; 16]>,
+}
+
+impl AadBuilder {
+    /// Stable identifier of the sending party.
+    pub fn sender(mut self, sender: impl Into<String>) -> Self {
+        self.sender = sender.into();
+        self
+    }
+
+    /// Stable identifier of the receiving party.
+    pub fn recipient(mut self, recipient: impl Into<String>) -> Self {
+        self.recipient = recipient.into();
+        self
+    }
+
+    /// Logical route / channel name.
+    pub fn route(mut self, route: impl Into<String>) -> Self {
+        self.route = route.into();
+        self
+    }
+
+    /// Message timestamp in Unix milliseconds.
+    pub fn timestamp_ms(mut self, ts_unix_ms: u64) -> Self {
+        self.timestamp_ms = ts_unix_ms;
+        self
+    }
+
+    /// Channel sequence number (0 if the channel has none).
+    pub fn sequence(mut self, seq: u64) -> Self {
+        self.sequence = seq;
+        self
+    }
+
+    /// 16-byte message id; must be unique per sender for replay detection.
+    pub fn msg_id(mut self, msg_id: [u8; 16]) -> Self {
+        self.msg_id = Some(msg_id);
+        self
+    }
+
+    /// Encode the AAD. Fails if a field exceeds 65535 bytes or the RNG
+    /// is unavailable for msg_id generation.
+    pub fn build(self) -> Result<Aad, SealError> {
+        let msg_id = match self.msg_id {
+            Some(id) => id,
+            None => crate::aad::generate_msg_id()?,
+        };
+        let inner = crate::aad::build_aad(
+            &self.sender,
+            &self.recipient,
+            &self.route,
+            self.timestamp_ms,
+            self.sequence,
+            msg_id,
+        )?;
+        Ok(Aad { inner })
+    }
+}
+
 /// Domain separation context Ã¢â‚¬â€ distinguishes encryption purposes.
 ///
 /// Context is bound into the key derivation, so ciphertext encrypted
 /// with one context cannot be decrypted with another.
 ///
 /// This is your primary defense against cross-protocol attacks.
-#[derive(Clone, Debug)]
+///
+/// By convention contexts are `|`-delimited paths, most general first
+/// (`app|billing|prod`). Build narrower ones with [`Context::child`]
+/// rather than `format!`, so every service spells them the same way.
+///
+/// As with [`Aad`], [`ContextRef`] passes borrowed bytes without a copy.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Context {
     inner: Vec<u8>,
 }
@@ -139,6 +275,31 @@ impl Context {
         }
     }
 
+    /// `base` followed by a binary epoch: a `0x00` separator byte, then
+    /// `epoch` as 8 bytes big-endian.
+    ///
+    /// Rotating by context means bumping the epoch: ciphertext sealed
+    /// under one epoch opens only with that exact epoch, so incrementing
+    /// it makes everything sealed earlier undecryptable under the new
+    /// context. Keep the old epoch around for as long as old data must
+    /// stay readable. The suffix has a fixed length and text contexts
+    /// never contain `0x00`, so it cannot run into the base.
+    ///
+    /// ```
+    /// use citadel_envelope::Context;
+    ///
+    /// let ctx = Context::with_epoch(b"app|sessions", 7);
+    /// assert_eq!(ctx.as_bytes(), b"app|sessions\x00\x00\x00\x00\x00\x00\x00\x00\x07");
+    /// assert_ne!(ctx, Context::with_epoch(b"app|sessions", 8));
+    /// ```
+    pub fn with_epoch(base: &[u8], epoch: u64) -> Self {
+        let mut inner = Vec::with_capacity(base.len() + 9);
+        inner.extend_from_slice(base);
+        inner.push(0x00);
+        inner.extend_from_slice(&epoch.to_be_bytes());
+        Self { inner }
+    }
+
     /// Context for inter-service communication.
     ///
     /// Format: `service|{from}|{to}|{protocol_version}`
@@ -157,12 +318,262 @@ impl Context {
         }
     }
 
-    /// Access the raw bytes (for internal use).
-    pub(crate) fn as_bytes(&self) -> &[u8] {
+    /// Context from named fields, with the same canonical encoding as
+    /// [`Aad::from_fields`] under a distinct prefix (so the same fields
+    /// never yield identical AAD and context bytes).
+    pub fn from_fields(fields: &[(&str, &str)]) -> Result<Self, SealError> {
+        let inner = crate::aad::encode_fields(crate::aad::CONTEXT_FIELDS_PREFIX, fields)?;
+        Ok(Self { inner })
+    }
+
+    /// Derive a child context by appending `|{segment}`.
+    ///
+    /// Contexts form a `|`-delimited hierarchy (`app|billing` is the parent
+    /// of `app|billing|invoices`). The child of an empty context is just
+    /// `segment`. A segment containing `|` adds several levels at once.
+    ///
+    /// The hierarchy is a naming convention only: ciphertext is bound to
+    /// the exact context bytes, so a parent context cannot open a child's
+    /// ciphertext. Applications that want a parent scope to cover its
+    /// children enforce that themselves with [`Context::is_ancestor_of`].
+    ///
+    /// ```
+    /// use citadel_envelope::Context;
+    ///
+    /// let billing = Context::raw(b"app|billing");
+    /// let invoices = billing.child("invoices");
+    /// assert_eq!(invoices, Context::raw(b"app|billing|invoices"));
+    /// assert!(billing.is_ancestor_of(&invoices));
+    /// assert!(!invoices.is_ancestor_of(&billing));
+    /// ```
+    pub fn child(&self, segment: &str) -> Self {
+        let mut inner = Vec::with_capacity(self.inner.len() + 1 + segment.len());
+        inner.extend_from_slice(&self.inner);
+        if !inner.is_empty() {
+            inner.push(b'|');
+        }
+        inner.extend_from_slice(segment.as_bytes());
+        Self { inner }
+    }
+
+    /// Whether `other` is a strict descendant of this context under the
+    /// [`Context::child`] convention (`app|billing` is an ancestor of
+    /// `app|billing|invoices` but not of `app|billingx`). The empty context
+    /// is an ancestor of every non-empty context.
+    pub fn is_ancestor_of(&self, other: &Context) -> bool {
+        if self.inner.is_empty() {
+            return !other.inner.is_empty();
+        }
+        other.inner.len() > self.inner.len()
+            && other.inner.starts_with(&self.inner)
+            && other.inner[self.inner.len()] == b'|'
+    }
+
+    /// Length in bytes.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Whether this is zero-length.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// The raw context bytes (e.g. for per-key allow-lists).
+    pub fn as_bytes(&self) -> &[u8] {
         &self.inner
     }
 }
 
+/// Render bytes as UTF-8 when valid, otherwise as lowercase hex.
+fn fmt_bytes(bytes: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match core::str::from_utf8(bytes) {
+        Ok(text) => f.write_str(text),
+        Err(_) => {
+            for b in bytes {
+                write!(f, "{:02x}", b)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for Aad {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_bytes(&self.inner, f)
+    }
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_bytes(&self.inner, f)
+    }
+}
+
+/// AAD borrowed from the caller instead of copied into an [`Aad`].
+///
+/// Every method that takes `&Aad` also takes an `AadRef`. It saves one
+/// copy per call, which is small next to the KEM work in `seal`, so reach
+/// for it to cut allocation churn in hot loops rather than for throughput.
+///
+/// ```
+/// use citadel_envelope::{Citadel, Aad, AadRef, Context};
+///
+/// let citadel = Citadel::new();
+/// let (pk, sk) = citadel.generate_keypair();
+/// let header = b"batch-2024-06";
+/// let ct = citadel.seal(&pk, b"row", AadRef::raw(header), &Context::empty()).unwrap();
+/// assert_eq!(citadel.open(&sk, &ct, &Aad::raw(header), &Context::empty()).unwrap(), b"row");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AadRef<'a> {
+    inner: &'a [u8],
+}
+
+impl<'a> AadRef<'a> {
+    /// Raw AAD borrowing `bytes`.
+    pub fn raw(bytes: &'a [u8]) -> Self {
+        Self { inner: bytes }
+    }
+
+    /// Length in bytes.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Whether this is zero-length.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Access the raw bytes (for internal use).
+    pub(crate) fn as_bytes(&self) -> &'a [u8] {
+        self.inner
+    }
+}
+
+impl<'a> From<&'a Aad> for AadRef<'a> {
+    fn from(aad: &'a Aad) -> Self {
+        Self { inner: aad.as_bytes() }
+    }
+}
+
+impl From<AadRef<'_>> for Aad {
+    fn from(aad: AadRef<'_>) -> Self {
+        Self::raw(aad.inner)
+    }
+}
+
+impl fmt::Display for AadRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_bytes(self.inner, f)
+    }
+}
+
+/// Context borrowed from the caller instead of copied into a [`Context`];
+/// see [`AadRef`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContextRef<'a> {
+    inner: &'a [u8],
+}
+
+impl<'a> ContextRef<'a> {
+    /// Raw context borrowing `bytes`.
+    pub fn raw(bytes: &'a [u8]) -> Self {
+        Self { inner: bytes }
+    }
+
+    /// Length in bytes.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Whether this is zero-length.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// The raw context bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.inner
+    }
+}
+
+impl<'a> From<&'a Context> for ContextRef<'a> {
+    fn from(context: &'a Context) -> Self {
+        Self { inner: context.as_bytes() }
+    }
+}
+
+impl From<ContextRef<'_>> for Context {
+    fn from(context: ContextRef<'_>) -> Self {
+        Self::raw(context.inner)
+    }
+}
+
+impl fmt::Display for ContextRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_bytes(self.inner, f)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Raw KEM output
+// ---------------------------------------------------------------------------
+
+/// Shared secret from [`Citadel::encapsulate`] / [`Citadel::decapsulate`].
+///
+/// This is the provider's raw combined secret (for the hybrid suite,
+/// `x25519_dh[32] || mlkem_ss[32]`), not a key: run it through a KDF that
+/// also binds the KEM ciphertext before using it. Zeroized on drop;
+/// `Debug` never shows the bytes.
+pub struct SharedSecret(zeroize::Zeroizing<Vec<u8>>);
+
+impl SharedSecret {
+    /// The secret bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Length in bytes.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether this is zero-length (never, for a real provider).
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for SharedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SharedSecret([REDACTED; {}])", self.0.len())
+    }
+}
+
+/// KEM ciphertext from [`Citadel::encapsulate`]; send it to the holder of
+/// the secret key. Not secret.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KemCiphertext(Vec<u8>);
+
+impl KemCiphertext {
+    /// Wrap received bytes. Length is checked by `decapsulate`.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+
+    /// The ciphertext bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Take the ciphertext bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Main SDK interface
 // ---------------------------------------------------------------------------
@@ -188,8 +599,11 @@ impl Context {
 ///
 /// assert_eq!(plaintext, b"secret data");
 /// ```
-pub struct Citadel {
-    inner: crate::CitadelEngine,
+///
+/// The KEM suite is a type parameter defaulting to the hybrid suite; see
+/// [`Citadel::with_provider`] to pin one explicitly.
+pub struct Citadel<K: KemProvider = HybridX25519MlKem768Provider> {
+    inner: crate::kem_engine::Citadel<K>,
 }
 
 impl Default for Citadel {
@@ -199,13 +613,48 @@ impl Default for Citadel {
 }
 
 impl Citadel {
-    /// Create a new Citadel instance.
+    /// Create a new Citadel instance using the default hybrid suite.
     pub fn new() -> Self {
         Self {
             inner: crate::CitadelEngine::new(),
         }
     }
 
+    /// Create an engine pinned to KEM suite `P`.
+    ///
+    /// Ciphertexts carry the suite in their header, so opening one sealed
+    /// under a different suite fails like any other invalid ciphertext.
+    ///
+    /// ```
+    /// use citadel_envelope::{Citadel, HybridX25519MlKem768Provider, Aad, Context};
+    ///
+    /// let citadel = Citadel::with_provider::<HybridX25519MlKem768Provider>();
+    /// let (pk, sk) = citadel.generate_keypair();
+    /// let ct = citadel.seal(&pk, b"data", &Aad::empty(), &Context::empty()).unwrap();
+    /// assert_eq!(citadel.open(&sk, &ct, &Aad::empty(), &Context::empty()).unwrap(), b"data");
+    /// ```
+    pub fn with_provider<P: KemProvider>() -> Citadel<P> {
+        Citadel {
+            inner: crate::kem_engine::Citadel::new(),
+        }
+    }
+
+    /// Exact ciphertext size `seal` will produce for `plaintext_len` bytes.
+    ///
+    /// The fixed overhead (header, KEM ciphertext, nonce, tag) is
+    /// [`MIN_CIPHERTEXT_BYTES`]; AES-GCM adds nothing beyond the tag.
+    pub fn ciphertext_len(plaintext_len: usize) -> usize {
+        MIN_CIPHERTEXT_BYTES.saturating_add(plaintext_len)
+    }
+
+    /// Plaintext size carried by a ciphertext of `ciphertext_len` bytes,
+    /// or `None` if it is shorter than [`MIN_CIPHERTEXT_BYTES`].
+    pub fn max_plaintext_len(ciphertext_len: usize) -> Option<usize> {
+        ciphertext_len.checked_sub(MIN_CIPHERTEXT_BYTES)
+    }
+}
+
+impl<K: KemProvider> Citadel<K> {
     /// Generate a new keypair.
     ///
     /// The public key can be shared freely.
@@ -214,6 +663,122 @@ impl Citadel {
         self.inner.keygen()
     }
 
+    /// Generate a keypair drawing all randomness from `rng`.
+    ///
+    /// Intended for reproducible tests and KAT vectors. Production code
+    /// should use [`Citadel::generate_keypair`], which uses the OS RNG.
+    pub fn generate_keypair_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> (PublicKey, SecretKey) {
+        self.inner.keygen_with_rng(rng)
+    }
+
+    /// Replace the HKDF `info` prefix used to derive the AEAD key.
+    ///
+    /// The default ([`KdfParams::default`]) is the frozen v1 construction;
+    /// the salt source and the rest of the `info` layout are fixed. The
+    /// prefix is not written on the wire, so a ciphertext only opens under
+    /// the params it was sealed with.
+    ///
+    /// ```
+    /// use citadel_envelope::{Citadel, Aad, Context};
+    /// use citadel_envelope::kdf::KdfParams;
+    ///
+    /// let citadel = Citadel::new().with_kdf_params(KdfParams::with_info(&b"acme-vault-v1"[..]));
+    /// let (pk, sk) = citadel.generate_keypair();
+    /// let ct = citadel.seal(&pk, b"data", &Aad::empty(), &Context::empty()).unwrap();
+    /// assert_eq!(citadel.open(&sk, &ct, &Aad::empty(), &Context::empty()).unwrap(), b"data");
+    /// assert!(Citadel::new().open(&sk, &ct, &Aad::empty(), &Context::empty()).is_err());
+    /// ```
+    pub fn with_kdf_params(mut self, params: KdfParams) -> Self {
+        self.inner.set_kdf_params(params);
+        self
+    }
+
+    /// Draw `seal` nonces from `source` instead of the OS RNG, e.g. an
+    /// HSM or a persisted counter. `seal_with_rng` keeps using its RNG.
+    ///
+    /// The source must never repeat a nonce; see [`NonceSource`] for why
+    /// the engine cannot catch it if it does.
+    ///
+    /// ```
+    /// use core::sync::atomic::{AtomicU64, Ordering};
+    /// use citadel_envelope::{Citadel, NonceSource, SealError, Aad, Context};
+    ///
+    /// struct Counter(AtomicU64);
+    ///
+    /// impl NonceSource for Counter {
+    ///     fn next_nonce(&self) -> Result<[u8; 12], SealError> {
+    ///         let mut n = [0u8; 12];
+    ///         n[4..].copy_from_slice(&self.0.fetch_add(1, Ordering::SeqCst).to_be_bytes());
+    ///         Ok(n)
+    ///     }
+    /// }
+    ///
+    /// let citadel = Citadel::new().with_nonce_source(Counter(AtomicU64::new(1)));
+    /// let (pk, sk) = citadel.generate_keypair();
+    /// let ct = citadel.seal(&pk, b"data", &Aad::empty(), &Context::empty()).unwrap();
+    /// assert_eq!(citadel.open(&sk, &ct, &Aad::empty(), &Context::empty()).unwrap(), b"data");
+    /// ```
+    pub fn with_nonce_source(mut self, source: impl NonceSource + 'static) -> Self {
+        self.inner.set_nonce_source(alloc::boxed::Box::new(source));
+        self
+    }
+
+    /// Refuse to open ciphertexts longer than `max` bytes (default
+    /// [`DEFAULT_MAX_CIPHERTEXT_BYTES`]).
+    ///
+    /// `open` buffers the whole plaintext, so without a cap a crafted
+    /// multi-gigabyte input costs that much memory before the tag is ever
+    /// checked. Oversized input fails with the usual opaque `OpenError`,
+    /// without allocating. The cap does not limit `seal`: payloads larger
+    /// than the receiver's cap must be split into separately sealed chunks,
+    /// as there is no streaming mode.
+    ///
+    /// ```
+    /// use citadel_envelope::{Citadel, Aad, Context};
+    ///
+    /// let citadel = Citadel::new();
+    /// let (pk, sk) = citadel.generate_keypair();
+    /// let ct = citadel.seal(&pk, &[0u8; 4096], &Aad::empty(), &Context::empty()).unwrap();
+    ///
+    /// let strict = Citadel::new().with_max_ciphertext_len(1024 + Citadel::ciphertext_len(0));
+    /// assert!(strict.open(&sk, &ct, &Aad::empty(), &Context::empty()).is_err());
+    /// ```
+    pub fn with_max_ciphertext_len(mut self, max: usize) -> Self {
+        self.inner.set_max_ciphertext_len(max);
+        self
+    }
+
+    /// Also authenticate the wire header: seal sets flag `0x02` and feeds
+    /// the header bytes to the AEAD ahead of `aad`, so changing any header
+    /// byte, even to another accepted value, fails `open`.
+    ///
+    /// Off by default. Readers from before the flag require `flags` to be
+    /// `0x00` and reject header-bound ciphertexts, so enable it only once
+    /// every recipient runs a version that opens them. Opening accepts
+    /// both forms whatever this is set to.
+    ///
+    /// ```
+    /// use citadel_envelope::{Citadel, Aad, Context};
+    ///
+    /// let bound = Citadel::new().with_header_binding(true);
+    /// let (pk, sk) = bound.generate_keypair();
+    /// let ct = bound.seal(&pk, b"data", &Aad::empty(), &Context::empty()).unwrap();
+    /// assert_eq!(ct[3], 0x02);
+    /// assert_eq!(Citadel::new().open(&sk, &ct, &Aad::empty(), &Context::empty()).unwrap(), b"data");
+    /// ```
+    pub fn with_header_binding(mut self, enabled: bool) -> Self {
+        self.inner.set_header_binding(enabled);
+        self
+    }
+
+    /// HKDF parameters this engine derives keys with.
+    pub fn kdf_params(&self) -> &KdfParams {
+        self.inner.kdf_params()
+    }
+
     /// Encrypt (seal) plaintext to a public key.
     ///
     /// Both `aad` and `context` are bound to the ciphertext and must match on decryption.
@@ -228,16 +793,44 @@ impl Citadel {
     /// # Returns
     ///
     /// Self-describing ciphertext bytes (minimum 1154 bytes).
-    pub fn seal(
+    ///
+    /// # Errors
+    ///
+    /// Returns `SealError` if `aad` exceeds [`MAX_AAD_BYTES`].
+    pub fn seal<'a>(
         &self,
         pk: &PublicKey,
         plaintext: &[u8],
-        aad: &Aad,
-        context: &Context,
+        aad: impl Into<AadRef<'a>>,
+        context: impl Into<ContextRef<'a>>,
     ) -> Result<Vec<u8>, SealError> {
+        let (aad, context) = (aad.into(), context.into());
+        check_aad_len(aad)?;
         self.inner.encrypt(pk, plaintext, aad.as_bytes(), context.as_bytes())
     }
 
+    /// Encrypt (seal) with a caller-supplied RNG.
+    ///
+    /// Identical to [`Citadel::seal`], except the KEM ephemerals and the AEAD
+    /// nonce are drawn from `rng`. A seeded RNG yields byte-identical
+    /// ciphertext, which makes test vectors and fuzzing reproducible.
+    ///
+    /// Never reuse a seeded RNG state in production: repeating it repeats the
+    /// nonce and the ephemeral keys.
+    pub fn seal_with_rng<'a, R: RngCore + CryptoRng>(
+        &self,
+        pk: &PublicKey,
+        plaintext: &[u8],
+        aad: impl Into<AadRef<'a>>,
+        context: impl Into<ContextRef<'a>>,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, SealError> {
+        let (aad, context) = (aad.into(), context.into());
+        check_aad_len(aad)?;
+        self.inner
+            .encrypt_with_rng(pk, plaintext, aad.as_bytes(), context.as_bytes(), rng)
+    }
+
     /// Decrypt (open) ciphertext using a secret key.
     ///
     /// Both `aad` and `context` must match exactly what was used during encryption.
@@ -249,18 +842,161 @@ impl Citadel {
     /// - Wrong AAD
     /// - Wrong context
     /// - Tampered ciphertext
-    /// - Malformed input
+    /// - Malformed input, or input longer than the cap set with
+    ///   [`Citadel::with_max_ciphertext_len`]
     ///
-    /// This uniform behavior prevents oracle attacks.
-    pub fn open(
+    /// This uniform behavior prevents oracle attacks. Malformed input also
+    /// runs a dummy decapsulation and AEAD check before failing, so it is not
+    /// rejected measurably faster than a well-formed ciphertext with the
+    /// wrong key. Timing still depends on ciphertext length.
+    pub fn open<'a>(
         &self,
         sk: &SecretKey,
         ciphertext: &[u8],
-        aad: &Aad,
-        context: &Context,
+        aad: impl Into<AadRef<'a>>,
+        context: impl Into<ContextRef<'a>>,
     ) -> Result<Vec<u8>, OpenError> {
+        let (aad, context) = (aad.into(), context.into());
         self.inner.decrypt(sk, ciphertext, aad.as_bytes(), context.as_bytes())
     }
+
+    /// Encrypt (seal) with an extra caller-chosen `label` mixed into key
+    /// derivation alongside `context`.
+    ///
+    /// Use it to separate epochs (or any other dimension) without
+    /// overloading context strings: a ciphertext sealed under label `b"2025"`
+    /// will not open under `b"2026"`, even with the same key and context.
+    /// The label is not stored; only its length is written to the header
+    /// (the `FLAG_LABELED` flag). Open with [`Citadel::open_labeled`] and
+    /// the same label; plain [`Citadel::open`] refuses labeled ciphertexts.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SealError` if `aad` exceeds [`MAX_AAD_BYTES`] or `label`
+    /// exceeds [`MAX_LABEL_BYTES`].
+    pub fn seal_labeled<'a>(
+        &self,
+        pk: &PublicKey,
+        plaintext: &[u8],
+        aad: impl Into<AadRef<'a>>,
+        context: impl Into<ContextRef<'a>>,
+        label: &[u8],
+    ) -> Result<Vec<u8>, SealError> {
+        let (aad, context) = (aad.into(), context.into());
+        check_aad_len(aad)?;
+        if label.len() > MAX_LABEL_BYTES {
+            return Err(SealError);
+        }
+        self.inner
+            .encrypt_labeled(pk, plaintext, aad.as_bytes(), context.as_bytes(), label)
+    }
+
+    /// Decrypt (open) a ciphertext produced by [`Citadel::seal_labeled`].
+    ///
+    /// `label` must match exactly. Fails with the same opaque `OpenError`
+    /// as [`Citadel::open`], including for unlabeled ciphertexts.
+    pub fn open_labeled<'a>(
+        &self,
+        sk: &SecretKey,
+        ciphertext: &[u8],
+        aad: impl Into<AadRef<'a>>,
+        context: impl Into<ContextRef<'a>>,
+        label: &[u8],
+    ) -> Result<Vec<u8>, OpenError> {
+        let (aad, context) = (aad.into(), context.into());
+        self.inner
+            .decrypt_diagnostic_labeled(sk, ciphertext, aad.as_bytes(), context.as_bytes(), Some(label))
+            .map_err(OpenError::from)
+    }
+
+    /// Move a ciphertext from recipient A to recipient B.
+    ///
+    /// Opens `ciphertext` with `sk_a`, then immediately seals the result to
+    /// `pk_b` under the same `aad` and `context`. The output is a fresh
+    /// ciphertext (new KEM ephemerals and nonce) that only B can open.
+    ///
+    /// # Trust assumption
+    ///
+    /// This is **not** proxy re-encryption: the plaintext exists in this
+    /// process's memory between the two steps, so whoever runs `rekey` must
+    /// already be trusted with it (it holds `sk_a`). The intermediate buffer
+    /// is `Zeroizing` and is wiped before returning, on success or failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns the opaque `OpenError` if A cannot open the ciphertext, or if
+    /// re-sealing fails.
+    pub fn rekey<'a>(
+        &self,
+        sk_a: &SecretKey,
+        pk_b: &PublicKey,
+        ciphertext: &[u8],
+        aad: impl Into<AadRef<'a>>,
+        context: impl Into<ContextRef<'a>>,
+    ) -> Result<Vec<u8>, OpenError> {
+        let (aad, context) = (aad.into(), context.into());
+        let plaintext = zeroize::Zeroizing::new(self.open(sk_a, ciphertext, aad, context)?);
+        self.seal(pk_b, &plaintext, aad, context)
+            .map_err(|_| OpenError::new())
+    }
+
+    /// Run the raw KEM: a fresh shared secret and the ciphertext that
+    /// carries it to the holder of `pk`'s secret key.
+    ///
+    /// For protocols that do their own key schedule. Nothing here binds
+    /// AAD or context; prefer [`Citadel::seal`] unless you need this.
+    ///
+    /// ```
+    /// use citadel_envelope::Citadel;
+    ///
+    /// let citadel = Citadel::new();
+    /// let (pk, sk) = citadel.generate_keypair();
+    /// let (ss, ct) = citadel.encapsulate(&pk).unwrap();
+    /// let ss2 = citadel.decapsulate(&sk, &ct).unwrap();
+    /// assert_eq!(ss.as_bytes(), ss2.as_bytes());
+    /// ```
+    pub fn encapsulate(&self, pk: &PublicKey) -> Result<(SharedSecret, KemCiphertext), SealError> {
+        let (ss, ct) = K::encapsulate(pk)?;
+        Ok((SharedSecret(zeroize::Zeroizing::new(ss)), KemCiphertext(ct)))
+    }
+
+    /// [`Citadel::encapsulate`] drawing the KEM ephemerals from `rng`.
+    pub fn encapsulate_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        pk: &PublicKey,
+        rng: &mut R,
+    ) -> Result<(SharedSecret, KemCiphertext), SealError> {
+        let (ss, ct) = K::encapsulate_with_rng(pk, rng)?;
+        Ok((SharedSecret(zeroize::Zeroizing::new(ss)), KemCiphertext(ct)))
+    }
+
+    /// Recover the shared secret from a KEM ciphertext.
+    ///
+    /// Fails with the opaque `OpenError` only for a malformed ciphertext.
+    /// ML-KEM uses implicit rejection, so a tampered but well-formed
+    /// ciphertext yields a different secret rather than an error: confirm
+    /// the secret (e.g. with an AEAD tag) before trusting it.
+    pub fn decapsulate(&self, sk: &SecretKey, ct: &KemCiphertext) -> Result<SharedSecret, OpenError> {
+        K::decapsulate(sk, &ct.0).map(|ss| SharedSecret(zeroize::Zeroizing::new(ss)))
+    }
+
+    /// Like [`Citadel::open`], but reports which stage failed.
+    ///
+    /// For server-side logging only (the keystore uses it to enrich audit
+    /// and threat events). Always hand callers the opaque `OpenError`;
+    /// `OpenFailure` converts into it with `?` / `From`.
+    #[doc(hidden)]
+    pub fn open_diagnostic<'a>(
+        &self,
+        sk: &SecretKey,
+        ciphertext: &[u8],
+        aad: impl Into<AadRef<'a>>,
+        context: impl Into<ContextRef<'a>>,
+    ) -> Result<Vec<u8>, crate::OpenFailure> {
+        let (aad, context) = (aad.into(), context.into());
+        self.inner
+            .decrypt_diagnostic(sk, ciphertext, aad.as_bytes(), context.as_bytes())
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -276,19 +1012,43 @@ pub struct CiphertextInfo {
     pub kem_suite: &'static str,
     /// AEAD suite identifier
     pub aead_suite: &'static str,
+    /// KEM suite; `None` when `supported` is false.
+    pub kem_suite_id: Option<KemSuite>,
+    /// AEAD suite; `None` when `supported` is false.
+    pub aead_suite_id: Option<AeadSuite>,
     /// Total ciphertext length
     pub total_bytes: usize,
-    /// Plaintext length (total - overhead)
+    /// Plaintext length (total - overhead); 0 when `supported` is false
     pub plaintext_bytes: usize,
+    /// Whether this SDK can open the ciphertext's format version.
+    pub supported: bool,
+    /// Whether it was sealed with a label (`Citadel::seal_labeled`).
+    pub labeled: bool,
+    /// Default KDF parameters for this format (`kdf-debug` feature). The
+    /// wire does not record an `info` override from
+    /// [`Citadel::with_kdf_params`], so this is what a default engine uses.
+    #[cfg(feature = "kdf-debug")]
+    pub kdf: KdfParams,
 }
 
 impl fmt::Display for CiphertextInfo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.supported {
+            return write!(f, "Citadel v{} (unsupported format) | {} bytes", self.version, self.total_bytes);
+        }
         write!(
             f,
-            "Citadel v{} | {} + {} | {} bytes ({} plaintext)",
-            self.version, self.kem_suite, self.aead_suite, self.total_bytes, self.plaintext_bytes
-        )
+            "Citadel v{} | {} + {} | {} bytes ({} plaintext){}",
+            self.version,
+            self.kem_suite,
+            self.aead_suite,
+            self.total_bytes,
+            self.plaintext_bytes,
+            if self.labeled { " | labeled" } else { "" }
+        )?;
+        #[cfg(feature = "kdf-debug")]
+        write!(f, " | {}", self.kdf)?;
+        Ok(())
     }
 }
 
@@ -296,34 +1056,56 @@ impl fmt::Display for CiphertextInfo {
 ///
 /// Useful for logging, debugging, and operational tooling.
 /// Does NOT reveal any secret information.
+///
+/// A ciphertext whose version byte is not [`PROTOCOL_VERSION`] is reported
+/// with `supported: false` and no suites rather than as an error, so
+/// a newer format can be told apart from corruption. A malformed v1
+/// ciphertext is the opaque `OpenError`; [`inspect_detailed`] says what is
+/// wrong and where.
 pub fn inspect(ciphertext: &[u8]) -> Result<CiphertextInfo, OpenError> {
-    use crate::wire::{decode_wire, MIN_CIPHERTEXT_BYTES, SUITE_KEM_HYBRID_X25519_MLKEM768, SUITE_AEAD_AES256GCM};
-
-    let parts = decode_wire(ciphertext)?;
-
-    let kem_suite = if parts.suite_kem == SUITE_KEM_HYBRID_X25519_MLKEM768 {
-        "X25519+ML-KEM-768"
-    } else {
-        "unknown"
-    };
-
-    let aead_suite = if parts.suite_aead == SUITE_AEAD_AES256GCM {
-        "AES-256-GCM"
-    } else {
-        "unknown"
-    };
-
-    // Plaintext bytes = total - (header + kem_ct + nonce + tag)
-    let overhead = MIN_CIPHERTEXT_BYTES;
-    let plaintext_bytes = ciphertext.len().saturating_sub(overhead);
-
-    Ok(CiphertextInfo {
-        version: parts.version,
-        kem_suite,
-        aead_suite,
-        total_bytes: ciphertext.len(),
-        plaintext_bytes,
-    })
+    inspect_detailed(ciphertext).map_err(|_| OpenError::new())
+}
+
+/// [`inspect`], reporting a malformed v1 ciphertext as a [`WireError`]
+/// (see [`wire::validate`](crate::wire::validate)). For operator tooling
+/// such as `citadel inspect`.
+pub fn inspect_detailed(ciphertext: &[u8]) -> Result<CiphertextInfo, WireError> {
+    match crate::wire::validate(ciphertext) {
+        Ok(()) => {
+            let kem_suite_id = KemSuite::from_byte(ciphertext[1]);
+            let aead_suite_id = AeadSuite::from_byte(ciphertext[2]);
+            Ok(CiphertextInfo {
+                version: PROTOCOL_VERSION,
+                kem_suite: kem_suite_id.map_or("unknown", KemSuite::name),
+                aead_suite: aead_suite_id.map_or("unknown", AeadSuite::name),
+                kem_suite_id,
+                aead_suite_id,
+                total_bytes: ciphertext.len(),
+                // Plaintext bytes = total - (header + label_len + kem_ct + nonce + tag)
+                plaintext_bytes: ciphertext.len()
+                    - MIN_CIPHERTEXT_BYTES
+                    - crate::wire::header_extension_len(ciphertext[3]),
+                supported: true,
+                labeled: ciphertext[3] & crate::wire::FLAG_LABELED != 0,
+                #[cfg(feature = "kdf-debug")]
+                kdf: KdfParams::default(),
+            })
+        }
+        Err(WireError::BadVersion(version)) => Ok(CiphertextInfo {
+            version,
+            kem_suite: "unknown",
+            aead_suite: "unknown",
+            kem_suite_id: None,
+            aead_suite_id: None,
+            total_bytes: ciphertext.len(),
+            plaintext_bytes: 0,
+            supported: false,
+            labeled: false,
+            #[cfg(feature = "kdf-debug")]
+            kdf: KdfParams::default(),
+        }),
+        Err(e) => Err(e),
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -338,3 +1120,21 @@ pub const PROTOCOL_VERSION: u8 = 0x01;
 
 /// Minimum ciphertext size in bytes.
 pub const MIN_CIPHERTEXT_BYTES: usize = crate::wire::MIN_CIPHERTEXT_BYTES;
+
+/// Largest AAD accepted by `seal` (64 KiB). AAD is authenticated on every
+/// open, so an unbounded value makes each operation arbitrarily expensive.
+pub const MAX_AAD_BYTES: usize = 64 * 1024;
+
+/// Default cap on ciphertexts accepted by `open` (1 GiB); see
+/// [`Citadel::with_max_ciphertext_len`].
+pub const DEFAULT_MAX_CIPHERTEXT_BYTES: usize = 1 << 30;
+
+/// Largest label accepted by `seal_labeled`; its length is a u16 on the wire.
+pub const MAX_LABEL_BYTES: usize = u16::MAX as usize;
+
+fn check_aad_len(aad: AadRef<'_>) -> Result<(), SealError> {
+    if aad.len() > MAX_AAD_BYTES {
+        return Err(SealError);
+    }
+    Ok(())
+}
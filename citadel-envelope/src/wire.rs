@@ -5,9 +5,18 @@
 //!   || kem_ct[1120] || nonce[12] || aead_ct[16+]
 //!
 //! kem_ct = x25519_ephemeral_pk[32] || mlkem768_ciphertext[1088]
+//!
+//! With `FLAG_LABELED` set, `label_len[2]` (u16 big-endian) follows
+//! `kem_ct_len`. The label itself is never on the wire.
+//!
+//! With `FLAG_HEADER_BOUND` set, the header bytes (through `label_len`)
+//! are prepended to the caller's AAD for AEAD, so any header mutation
+//! fails authentication. Seal sets it only when asked to
+//! (`Citadel::with_header_binding`).
 
 extern crate alloc;
 use alloc::vec::Vec;
+use core::fmt;
 
 use crate::error::{DecryptionError, EncodingError};
 
@@ -21,9 +30,110 @@ pub const PROTOCOL_VERSION: u8 = 0x01;
 pub const SUITE_KEM_HYBRID_X25519_MLKEM768: u8 = 0xA3;
 pub const SUITE_AEAD_AES256GCM: u8 = 0xB1;
 
+/// KEM suite named by a `suite_kem` header byte.
+///
+/// The single place a new suite is registered; the `SUITE_KEM_*` byte
+/// constants stay for wire compatibility. Provider-defined suites
+/// (`KemProvider::SUITE_KEM`) have no variant and map to `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KemSuite {
+    /// X25519 + ML-KEM-768 hybrid.
+    HybridX25519MlKem768,
+}
+
+impl KemSuite {
+    /// Suite for a header byte, or `None` if this SDK doesn't know it.
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            SUITE_KEM_HYBRID_X25519_MLKEM768 => Some(Self::HybridX25519MlKem768),
+            _ => None,
+        }
+    }
+
+    /// On-wire byte.
+    pub const fn to_byte(self) -> u8 {
+        match self {
+            Self::HybridX25519MlKem768 => SUITE_KEM_HYBRID_X25519_MLKEM768,
+        }
+    }
+
+    /// Human-readable name, e.g. for `inspect`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::HybridX25519MlKem768 => "X25519+ML-KEM-768",
+        }
+    }
+
+    /// KEM ciphertext size this suite writes after the header.
+    pub const fn ciphertext_len(self) -> usize {
+        match self {
+            Self::HybridX25519MlKem768 => KEM_CIPHERTEXT_BYTES,
+        }
+    }
+}
+
+impl fmt::Display for KemSuite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// AEAD suite named by a `suite_aead` header byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AeadSuite {
+    /// AES-256-GCM.
+    Aes256Gcm,
+}
+
+impl AeadSuite {
+    /// Suite for a header byte, or `None` if this SDK doesn't know it.
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            SUITE_AEAD_AES256GCM => Some(Self::Aes256Gcm),
+            _ => None,
+        }
+    }
+
+    /// On-wire byte.
+    pub const fn to_byte(self) -> u8 {
+        match self {
+            Self::Aes256Gcm => SUITE_AEAD_AES256GCM,
+        }
+    }
+
+    /// Human-readable name, e.g. for `inspect`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Aes256Gcm => "AES-256-GCM",
+        }
+    }
+}
+
+impl fmt::Display for AeadSuite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 /// Flags (reserved for future use)
 pub const FLAGS_V1: u8 = 0x00;
 
+/// Flag: a caller label was mixed into the KDF (`Citadel::seal_labeled`)
+/// and the header carries its length.
+pub const FLAG_LABELED: u8 = 0x01;
+
+/// Flag: the header is part of the AEAD associated data. Opt-in
+/// (`Citadel::with_header_binding`); ciphertexts without it still open.
+pub const FLAG_HEADER_BOUND: u8 = 0x02;
+
+/// Every flag bit this SDK understands.
+const KNOWN_FLAGS: u8 = FLAG_LABELED | FLAG_HEADER_BOUND;
+
+/// Size of the `label_len` header field present under `FLAG_LABELED`.
+pub const LABEL_LEN_BYTES: usize = 2;
+
 // ---------------------------------------------------------------------------
 // Component sizes
 // ---------------------------------------------------------------------------
@@ -79,36 +189,133 @@ pub struct WireComponents<'a> {
     pub suite_aead: u8,
     pub flags: u8,
     pub kem_ct_len: u16,
+    /// Label length, present iff `FLAG_LABELED` is set.
+    pub label_len: Option<u16>,
+    /// Raw header bytes, `label_len` included.
+    pub header: &'a [u8],
     pub kem_ciphertext: &'a [u8; KEM_CIPHERTEXT_BYTES],
     pub nonce: &'a [u8; NONCE_BYTES],
     pub aead_ciphertext: &'a [u8],
 }
 
-pub fn decode_wire(data: &[u8]) -> Result<WireComponents<'_>, DecryptionError> {
-    if data.len() < MIN_CIPHERTEXT_BYTES {
-        return Err(DecryptionError);
+/// Read the version byte without validating anything else.
+///
+/// Lets tooling tell "sealed by a newer format" apart from garbage.
+/// `decode_wire` still rejects every version but `PROTOCOL_VERSION`.
+pub fn peek_version(data: &[u8]) -> Result<u8, DecryptionError> {
+    data.first().copied().ok_or(DecryptionError::new())
+}
+
+/// Why a buffer is not a well-formed v1 ciphertext.
+///
+/// Diagnostic only (`inspect`, the CLI). `open` never exposes this and
+/// keeps returning the uniform `OpenError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireError {
+    /// Shorter than the fixed v1 overhead.
+    TooShort { got: usize, need: usize },
+    /// Version byte is not `PROTOCOL_VERSION`.
+    BadVersion(u8),
+    /// `suite_kem` byte is not the expected KEM suite.
+    BadKemSuite(u8),
+    /// `suite_aead` byte is not `SUITE_AEAD_AES256GCM`.
+    BadAeadSuite(u8),
+    /// Flags byte has a bit outside `FLAG_LABELED | FLAG_HEADER_BOUND`.
+    BadFlags(u8),
+    /// Header `kem_ct_len` disagrees with the suite's KEM ciphertext size.
+    KemLenMismatch { got: usize, expected: usize },
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { got, need } => write!(f, "truncated: {} bytes, need at least {}", got, need),
+            Self::BadVersion(v) => write!(f, "byte 0: unknown version 0x{:02x}", v),
+            Self::BadKemSuite(s) => write!(f, "byte 1: unknown KEM suite 0x{:02x}", s),
+            Self::BadAeadSuite(s) => write!(f, "byte 2: unknown AEAD suite 0x{:02x}", s),
+            Self::BadFlags(b) => write!(f, "byte 3: unsupported flags 0x{:02x}", b),
+            Self::KemLenMismatch { got, expected } => {
+                write!(f, "bytes 4-5: KEM ciphertext length {} (expected {})", got, expected)
+            }
+        }
     }
+}
 
-    let version = data[0];
-    let suite_kem = data[1];
-    let suite_aead = data[2];
-    let flags = data[3];
-    let kem_ct_len = u16::from_be_bytes([data[4], data[5]]);
+/// Check that `data` is a structurally valid v1 ciphertext under the
+/// default hybrid suite, reporting the first problem found.
+///
+/// Header fields are checked before overall length, so a damaged header
+/// is reported as such even when the buffer is also truncated.
+pub fn validate(data: &[u8]) -> Result<(), WireError> {
+    validate_for_suite(data, KemSuite::HybridX25519MlKem768.to_byte())
+}
+
+fn validate_for_suite(data: &[u8], suite_kem: u8) -> Result<(), WireError> {
+    let too_short = WireError::TooShort { got: data.len(), need: MIN_CIPHERTEXT_BYTES };
 
+    let version = *data.first().ok_or(too_short)?;
     if version != PROTOCOL_VERSION {
-        return Err(DecryptionError);
+        return Err(WireError::BadVersion(version));
+    }
+    if data.len() < HEADER_BYTES {
+        return Err(too_short);
     }
-    if suite_kem != SUITE_KEM_HYBRID_X25519_MLKEM768 || suite_aead != SUITE_AEAD_AES256GCM {
-        return Err(DecryptionError);
+    if data[1] != suite_kem {
+        return Err(WireError::BadKemSuite(data[1]));
     }
-    if flags != FLAGS_V1 {
-        return Err(DecryptionError);
+    if AeadSuite::from_byte(data[2]) != Some(AeadSuite::Aes256Gcm) {
+        return Err(WireError::BadAeadSuite(data[2]));
     }
-    if kem_ct_len as usize != KEM_CIPHERTEXT_BYTES {
-        return Err(DecryptionError);
+    if data[3] & !KNOWN_FLAGS != 0 {
+        return Err(WireError::BadFlags(data[3]));
     }
+    let extra = header_extension_len(data[3]);
+    let kem_ct_len = u16::from_be_bytes([data[4], data[5]]) as usize;
+    let expected = kem_ciphertext_len(suite_kem);
+    if kem_ct_len != expected {
+        return Err(WireError::KemLenMismatch { got: kem_ct_len, expected });
+    }
+    if data.len() < MIN_CIPHERTEXT_BYTES + extra {
+        return Err(WireError::TooShort { got: data.len(), need: MIN_CIPHERTEXT_BYTES + extra });
+    }
+    Ok(())
+}
 
-    let kem_start = HEADER_BYTES;
+/// Bytes between the fixed header and `kem_ct` for a given flags byte.
+pub(crate) fn header_extension_len(flags: u8) -> usize {
+    if flags & FLAG_LABELED != 0 { LABEL_LEN_BYTES } else { 0 }
+}
+
+/// AEAD associated data for a ciphertext with this header: `header || aad`
+/// under `FLAG_HEADER_BOUND`, `aad` alone otherwise.
+pub(crate) fn aead_aad(header: &[u8], aad: &[u8]) -> Vec<u8> {
+    let bound = header.get(3).is_some_and(|flags| flags & FLAG_HEADER_BOUND != 0);
+    let mut out = Vec::with_capacity(if bound { header.len() } else { 0 } + aad.len());
+    if bound {
+        out.extend_from_slice(header);
+    }
+    out.extend_from_slice(aad);
+    out
+}
+
+/// Parse a ciphertext sealed with the default hybrid suite.
+pub fn decode_wire(data: &[u8]) -> Result<WireComponents<'_>, DecryptionError> {
+    decode_wire_for_suite(data, KemSuite::HybridX25519MlKem768.to_byte())
+}
+
+/// Parse a ciphertext, requiring its `suite_kem` byte to be `suite_kem`.
+pub fn decode_wire_for_suite(data: &[u8], suite_kem: u8) -> Result<WireComponents<'_>, DecryptionError> {
+    validate_for_suite(data, suite_kem).map_err(|_| DecryptionError::new())?;
+
+    let version = data[0];
+    let suite_aead = data[2];
+    let flags = data[3];
+    let kem_ct_len = u16::from_be_bytes([data[4], data[5]]);
+    let label_len = (flags & FLAG_LABELED != 0)
+        .then(|| u16::from_be_bytes([data[HEADER_BYTES], data[HEADER_BYTES + 1]]));
+
+    let kem_start = HEADER_BYTES + header_extension_len(flags);
+    let header = &data[..kem_start];
     let kem_end = kem_start + KEM_CIPHERTEXT_BYTES;
 
     let nonce_start = kem_end;
@@ -116,15 +323,15 @@ pub fn decode_wire(data: &[u8]) -> Result<WireComponents<'_>, DecryptionError> {
 
     let kem_ciphertext: &[u8; KEM_CIPHERTEXT_BYTES] = data[kem_start..kem_end]
         .try_into()
-        .map_err(|_| DecryptionError)?;
+        .map_err(|_| DecryptionError::new())?;
 
     let nonce: &[u8; NONCE_BYTES] = data[nonce_start..nonce_end]
         .try_into()
-        .map_err(|_| DecryptionError)?;
+        .map_err(|_| DecryptionError::new())?;
 
     let aead_ciphertext = &data[nonce_end..];
     if aead_ciphertext.len() < AEAD_TAG_BYTES {
-        return Err(DecryptionError);
+        return Err(DecryptionError::new());
     }
 
     Ok(WireComponents {
@@ -133,35 +340,140 @@ pub fn decode_wire(data: &[u8]) -> Result<WireComponents<'_>, DecryptionError> {
         suite_aead,
         flags,
         kem_ct_len,
+        label_len,
+        header,
         kem_ciphertext,
         nonce,
         aead_ciphertext,
     })
 }
 
+/// KEM ciphertext size for `suite_kem`.
+///
+/// Registered suites report their own size. Provider-defined suites have
+/// no `KemSuite` variant and must use the v1 layout's `KEM_CIPHERTEXT_BYTES`.
+pub const fn kem_ciphertext_len(suite_kem: u8) -> usize {
+    match KemSuite::from_byte(suite_kem) {
+        Some(suite) => suite.ciphertext_len(),
+        None => KEM_CIPHERTEXT_BYTES,
+    }
+}
+
+/// Why `encode_wire` / `encode_header` refused their input.
+///
+/// Internal detail for provider development; the SDK collapses it into
+/// the opaque `SealError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeError {
+    /// `suite_aead` is not an AEAD this version can write.
+    UnsupportedAeadSuite(u8),
+    /// A KEM provider returned a ciphertext of the wrong size for its suite.
+    KemCiphertextLength { suite_kem: u8, got: usize, expected: usize },
+    /// The AEAD output is shorter than its tag.
+    AeadCiphertextTooShort { got: usize },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedAeadSuite(s) => write!(f, "unsupported AEAD suite 0x{:02x}", s),
+            Self::KemCiphertextLength { suite_kem, got, expected } => write!(
+                f,
+                "KEM suite 0x{:02x} produced a {}-byte ciphertext (expected {})",
+                suite_kem, got, expected
+            ),
+            Self::AeadCiphertextTooShort { got } => {
+                write!(f, "AEAD output is {} bytes, shorter than its tag", got)
+            }
+        }
+    }
+}
+
+impl From<EncodeError> for EncodingError {
+    fn from(_: EncodeError) -> Self {
+        EncodingError
+    }
+}
+
+/// Encode a ciphertext under the given suites, without `FLAG_HEADER_BOUND`.
+///
+/// Rejects an AEAD suite other than `SUITE_AEAD_AES256GCM` and a `kem_ct`
+/// whose length does not match `suite_kem`, naming the mismatch.
 pub fn encode_wire(
+    suite_kem: u8,
+    suite_aead: u8,
+    kem_ct: &[u8],
+    nonce: &[u8; NONCE_BYTES],
+    aead_ct: &[u8],
+) -> Result<Vec<u8>, EncodeError> {
+    encode_wire_labeled(suite_kem, suite_aead, None, kem_ct, nonce, aead_ct)
+}
+
+/// `encode_wire`; `Some(label_len)` sets `FLAG_LABELED` and writes the
+/// length after `kem_ct_len`.
+pub fn encode_wire_labeled(
+    suite_kem: u8,
+    suite_aead: u8,
+    label_len: Option<u16>,
     kem_ct: &[u8],
     nonce: &[u8; NONCE_BYTES],
     aead_ct: &[u8],
-) -> Result<Vec<u8>, EncodingError> {
-    if kem_ct.len() != KEM_CIPHERTEXT_BYTES {
-        return Err(EncodingError);
+) -> Result<Vec<u8>, EncodeError> {
+    let header = encode_header(suite_kem, suite_aead, label_len, false, kem_ct)?;
+    finish_wire(header, kem_ct, nonce, aead_ct)
+}
+
+/// Header bytes for a ciphertext, `label_len` included: what
+/// `FLAG_HEADER_BOUND` feeds to the AEAD ahead of the caller's AAD.
+///
+/// Validates the suites and `kem_ct` length as `encode_wire` does.
+pub fn encode_header(
+    suite_kem: u8,
+    suite_aead: u8,
+    label_len: Option<u16>,
+    header_bound: bool,
+    kem_ct: &[u8],
+) -> Result<Vec<u8>, EncodeError> {
+    if AeadSuite::from_byte(suite_aead) != Some(AeadSuite::Aes256Gcm) {
+        return Err(EncodeError::UnsupportedAeadSuite(suite_aead));
     }
-    if aead_ct.len() < AEAD_TAG_BYTES {
-        return Err(EncodingError);
+    let expected = kem_ciphertext_len(suite_kem);
+    if kem_ct.len() != expected {
+        return Err(EncodeError::KemCiphertextLength { suite_kem, got: kem_ct.len(), expected });
     }
 
-    let mut out = Vec::with_capacity(HEADER_BYTES + KEM_CIPHERTEXT_BYTES + NONCE_BYTES + aead_ct.len());
-
+    let mut flags = FLAGS_V1;
+    if label_len.is_some() {
+        flags |= FLAG_LABELED;
+    }
+    if header_bound {
+        flags |= FLAG_HEADER_BOUND;
+    }
+    let mut out = Vec::with_capacity(HEADER_BYTES + header_extension_len(flags));
     out.push(PROTOCOL_VERSION);
-    out.push(SUITE_KEM_HYBRID_X25519_MLKEM768);
-    out.push(SUITE_AEAD_AES256GCM);
-    out.push(FLAGS_V1);
-    out.extend_from_slice(&(KEM_CIPHERTEXT_BYTES as u16).to_be_bytes());
-
-    out.extend_from_slice(kem_ct);
-    out.extend_from_slice(nonce);
-    out.extend_from_slice(aead_ct);
-
+    out.push(suite_kem);
+    out.push(suite_aead);
+    out.push(flags);
+    out.extend_from_slice(&(expected as u16).to_be_bytes());
+    if let Some(len) = label_len {
+        out.extend_from_slice(&len.to_be_bytes());
+    }
     Ok(out)
 }
+
+/// Append `kem_ct`, `nonce` and `aead_ct` to a header from `encode_header`.
+pub(crate) fn finish_wire(
+    mut header: Vec<u8>,
+    kem_ct: &[u8],
+    nonce: &[u8; NONCE_BYTES],
+    aead_ct: &[u8],
+) -> Result<Vec<u8>, EncodeError> {
+    if aead_ct.len() < AEAD_TAG_BYTES {
+        return Err(EncodeError::AeadCiphertextTooShort { got: aead_ct.len() });
+    }
+    header.reserve_exact(kem_ct.len() + NONCE_BYTES + aead_ct.len());
+    header.extend_from_slice(kem_ct);
+    header.extend_from_slice(nonce);
+    header.extend_from_slice(aead_ct);
+    Ok(header)
+}
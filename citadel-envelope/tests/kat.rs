@@ -28,6 +28,7 @@ fn test_wire_format_structure() {
     assert_eq!(parts.suite_kem, SUITE_KEM_HYBRID_X25519_MLKEM768);
     assert_eq!(parts.suite_aead, SUITE_AEAD_AES256GCM);
     assert_eq!(parts.flags, FLAGS_V1);
+    assert_eq!(parts.header, &ct[..HEADER_BYTES]);
     assert_eq!(parts.kem_ct_len as usize, KEM_CIPHERTEXT_BYTES);
     assert_eq!(parts.kem_ciphertext.len(), 1120);
     assert_eq!(parts.nonce.len(), 12);
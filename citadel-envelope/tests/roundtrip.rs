@@ -1,7 +1,9 @@
-use citadel_envelope::{Citadel, Aad, Context, PublicKey, SecretKey, OpenError};
+use citadel_envelope::{Citadel, Aad, Context, PublicKey, SecretKey, OpenError, KemCiphertext};
+use citadel_envelope::{HybridX25519MlKem768Provider, KemProvider, SealError};
+use rand::{CryptoRng, RngCore};
 use citadel_envelope::wire::{
     PROTOCOL_VERSION, SUITE_KEM_HYBRID_X25519_MLKEM768, SUITE_AEAD_AES256GCM,
-    FLAGS_V1, KEM_CIPHERTEXT_BYTES, HEADER_BYTES, MIN_CIPHERTEXT_BYTES,
+    FLAGS_V1, FLAG_HEADER_BOUND, FLAG_LABELED, KEM_CIPHERTEXT_BYTES, HEADER_BYTES, MIN_CIPHERTEXT_BYTES,
 };
 
 fn setup() -> (Citadel, PublicKey, SecretKey) {
@@ -49,7 +51,7 @@ fn wrong_aad_fails() {
     let ctx = Context::raw(b"ctx");
     let ct = cit.seal(&pk, b"data", &Aad::raw(b"good-aad"), &ctx).unwrap();
     let result = cit.open(&sk, &ct, &Aad::raw(b"bad-aad"), &ctx);
-    assert_eq!(result, Err(OpenError));
+    assert_eq!(result, Err(OpenError::new()));
 }
 
 #[test]
@@ -58,7 +60,7 @@ fn wrong_context_fails() {
     let aad = Aad::raw(b"aad");
     let ct = cit.seal(&pk, b"data", &aad, &Context::raw(b"good-ctx")).unwrap();
     let result = cit.open(&sk, &ct, &aad, &Context::raw(b"bad-ctx"));
-    assert_eq!(result, Err(OpenError));
+    assert_eq!(result, Err(OpenError::new()));
 }
 
 #[test]
@@ -69,7 +71,7 @@ fn wrong_key_fails() {
     let ctx = Context::raw(b"ctx");
     let ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
     let result = cit.open(&sk2, &ct, &aad, &ctx);
-    assert_eq!(result, Err(OpenError));
+    assert_eq!(result, Err(OpenError::new()));
 }
 
 #[test]
@@ -100,7 +102,7 @@ fn tamper_version_fails() {
     let ctx = Context::raw(b"ctx");
     let mut ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
     ct[0] = 0xFF;
-    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError));
+    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError::new()));
 }
 
 #[test]
@@ -110,7 +112,32 @@ fn tamper_suite_kem_fails() {
     let ctx = Context::raw(b"ctx");
     let mut ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
     ct[1] = 0xA2; // old ML-KEM-only suite ID
-    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError));
+    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError::new()));
+}
+
+#[test]
+fn tamper_header_within_valid_range_fails() {
+    let (cit, pk, sk) = setup();
+    let bound = Citadel::new().with_header_binding(true);
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let ct = bound.seal(&pk, b"data", &aad, &ctx).unwrap();
+    assert_eq!(ct[3], FLAG_HEADER_BOUND);
+    assert_eq!(cit.open(&sk, &ct, &aad, &ctx).unwrap(), b"data");
+
+    // Every mutation below still parses; only the AEAD can reject it.
+    let mut unbound = ct.clone();
+    unbound[3] &= !FLAG_HEADER_BOUND;
+    assert!(citadel_envelope::wire::decode_wire(&unbound).is_ok());
+    assert_eq!(cit.open(&sk, &unbound, &aad, &ctx), Err(OpenError::new()));
+
+    let labeled = bound.seal_labeled(&pk, b"data", &aad, &ctx, b"ab").unwrap();
+    assert_eq!(labeled[3], FLAG_LABELED | FLAG_HEADER_BOUND);
+    let mut unbound = labeled.clone();
+    unbound[3] &= !FLAG_HEADER_BOUND;
+    assert!(citadel_envelope::wire::decode_wire(&unbound).is_ok());
+    assert_eq!(cit.open_labeled(&sk, &unbound, &aad, &ctx, b"ab"), Err(OpenError::new()));
+    assert_eq!(cit.open_labeled(&sk, &labeled, &aad, &ctx, b"ab").unwrap(), b"data");
 }
 
 #[test]
@@ -120,7 +147,7 @@ fn tamper_kem_ciphertext_fails() {
     let ctx = Context::raw(b"ctx");
     let mut ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
     ct[HEADER_BYTES + 10] ^= 0x01;
-    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError));
+    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError::new()));
 }
 
 #[test]
@@ -131,7 +158,7 @@ fn tamper_nonce_fails() {
     let mut ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
     let nonce_offset = HEADER_BYTES + KEM_CIPHERTEXT_BYTES;
     ct[nonce_offset] ^= 0x01;
-    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError));
+    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError::new()));
 }
 
 #[test]
@@ -142,7 +169,7 @@ fn tamper_aead_ciphertext_fails() {
     let mut ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
     let last = ct.len() - 1;
     ct[last] ^= 0x01;
-    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError));
+    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError::new()));
 }
 
 #[test]
@@ -151,9 +178,9 @@ fn truncated_fails() {
     let aad = Aad::raw(b"aad");
     let ctx = Context::raw(b"ctx");
     let ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
-    assert_eq!(cit.open(&sk, &ct[..10], &aad, &ctx), Err(OpenError));
-    assert_eq!(cit.open(&sk, b"short", &aad, &ctx), Err(OpenError));
-    assert_eq!(cit.open(&sk, b"", &aad, &ctx), Err(OpenError));
+    assert_eq!(cit.open(&sk, &ct[..10], &aad, &ctx), Err(OpenError::new()));
+    assert_eq!(cit.open(&sk, b"short", &aad, &ctx), Err(OpenError::new()));
+    assert_eq!(cit.open(&sk, b"", &aad, &ctx), Err(OpenError::new()));
 }
 
 #[test]
@@ -195,3 +222,564 @@ fn key_serialization_roundtrip() {
     let pt = cit.open(&sk2, &ct, &aad, &ctx).unwrap();
     assert_eq!(&pt, plaintext);
 }
+
+#[test]
+fn seeded_rng_is_reproducible() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let cit = Citadel::new();
+    let (pk, sk) = cit.generate_keypair_with_rng(&mut StdRng::seed_from_u64(7));
+    let (pk2, _) = cit.generate_keypair_with_rng(&mut StdRng::seed_from_u64(7));
+    assert_eq!(pk.to_bytes(), pk2.to_bytes());
+
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let ct1 = cit.seal_with_rng(&pk, b"data", &aad, &ctx, &mut StdRng::seed_from_u64(42)).unwrap();
+    let ct2 = cit.seal_with_rng(&pk, b"data", &aad, &ctx, &mut StdRng::seed_from_u64(42)).unwrap();
+    let ct3 = cit.seal_with_rng(&pk, b"data", &aad, &ctx, &mut StdRng::seed_from_u64(43)).unwrap();
+    assert_eq!(ct1, ct2);
+    assert_ne!(ct1, ct3);
+
+    assert_eq!(cit.open(&sk, &ct1, &aad, &ctx).unwrap(), b"data");
+}
+
+#[test]
+fn aad_context_equality_and_display() {
+    assert_eq!(Aad::for_storage("b", "o", 1), Aad::raw(b"storage|b|o|v1"));
+    assert_ne!(Context::raw(b"a"), Context::raw(b"b"));
+
+    assert_eq!(Aad::raw(b"db|users|42|email").to_string(), "db|users|42|email");
+    assert_eq!(Context::raw(&[0xff, 0x00, 0x10]).to_string(), "ff0010");
+
+    assert_eq!(Aad::raw(b"abc").len(), 3);
+    assert!(Aad::empty().is_empty());
+    assert!(Context::empty().is_empty());
+    assert!(!Context::for_application("app", "prod").is_empty());
+}
+
+#[test]
+fn aad_builder_matches_canonical_tlv() {
+    let msg_id = [0x5au8; 16];
+    let built = Aad::builder()
+        .sender("svc-a")
+        .recipient("svc-b")
+        .route("orders")
+        .timestamp_ms(1_700_000_000_123)
+        .sequence(9)
+        .msg_id(msg_id)
+        .build()
+        .unwrap();
+
+    let canonical = citadel_envelope::aad::build_aad("svc-a", "svc-b", "orders", 1_700_000_000_123, 9, msg_id).unwrap();
+    assert_eq!(built, Aad::raw(&canonical));
+
+    let (cit, pk, sk) = setup();
+    let ctx = Context::raw(b"ctx");
+    let ct = cit.seal(&pk, b"data", &built, &ctx).unwrap();
+    assert_eq!(cit.open(&sk, &ct, &built, &ctx).unwrap(), b"data");
+}
+
+#[test]
+fn aad_builder_rejects_oversized_field() {
+    let result = Aad::builder().route("r".repeat(70_000)).msg_id([0; 16]).build();
+    assert!(result.is_err());
+}
+
+#[test]
+fn field_maps_encode_independent_of_order() {
+    let a = Aad::from_fields(&[("table", "users"), ("col", "ssn"), ("row", "42")]).unwrap();
+    let b = Aad::from_fields(&[("row", "42"), ("table", "users"), ("col", "ssn")]).unwrap();
+    assert_eq!(a, b);
+    assert_ne!(a, Aad::from_fields(&[("table", "users"), ("col", "dob"), ("row", "42")]).unwrap());
+    // Length prefixes keep field boundaries unambiguous.
+    assert_ne!(
+        Aad::from_fields(&[("a", "bc")]).unwrap(),
+        Aad::from_fields(&[("ab", "c")]).unwrap()
+    );
+    assert!(Aad::from_fields(&[("k", "1"), ("k", "2")]).is_err());
+
+    let ctx_a = Context::from_fields(&[("app", "billing"), ("env", "prod")]).unwrap();
+    let ctx_b = Context::from_fields(&[("env", "prod"), ("app", "billing")]).unwrap();
+    assert_eq!(ctx_a, ctx_b);
+    assert_ne!(Aad::raw(ctx_a.as_bytes()), Aad::from_fields(&[("app", "billing"), ("env", "prod")]).unwrap());
+
+    let (cit, pk, sk) = setup();
+    let ct = cit.seal(&pk, b"data", &a, &ctx_a).unwrap();
+    assert_eq!(cit.open(&sk, &ct, &b, &ctx_b).unwrap(), b"data");
+}
+
+#[test]
+fn seal_enforces_max_aad_bytes() {
+    use citadel_envelope::MAX_AAD_BYTES;
+
+    let (cit, pk, sk) = setup();
+    let ctx = Context::raw(b"ctx");
+
+    let at_limit = Aad::raw(&vec![0xAB; MAX_AAD_BYTES]);
+    let ct = cit.seal(&pk, b"data", &at_limit, &ctx).unwrap();
+    assert_eq!(cit.open(&sk, &ct, &at_limit, &ctx).unwrap(), b"data");
+
+    let over_limit = Aad::raw(&vec![0xAB; MAX_AAD_BYTES + 1]);
+    assert!(cit.seal(&pk, b"data", &over_limit, &ctx).is_err());
+}
+
+#[test]
+fn ciphertext_len_matches_seal_output() {
+    let (cit, pk, _) = setup();
+    for len in [0usize, 1, 31, 4096] {
+        let ct = cit.seal(&pk, &vec![0u8; len], &Aad::raw(b"a"), &Context::raw(b"c")).unwrap();
+        assert_eq!(ct.len(), Citadel::ciphertext_len(len));
+        assert_eq!(Citadel::max_plaintext_len(ct.len()), Some(len));
+    }
+    assert_eq!(Citadel::max_plaintext_len(MIN_CIPHERTEXT_BYTES - 1), None);
+}
+
+#[test]
+fn open_diagnostic_reports_stage_but_open_stays_uniform() {
+    use citadel_envelope::OpenFailure;
+
+    let (cit, pk, sk) = setup();
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
+
+    assert_eq!(cit.open_diagnostic(&sk, &ct, &aad, &ctx).unwrap(), b"data");
+    assert_eq!(cit.open_diagnostic(&sk, b"short", &aad, &ctx), Err(OpenFailure::Decode));
+    assert_eq!(
+        cit.open_diagnostic(&sk, &ct, &aad, &Context::raw(b"other")),
+        Err(OpenFailure::AeadVerification)
+    );
+
+    assert_eq!(cit.open(&sk, b"short", &aad, &ctx), Err(OpenError::new()));
+    assert_eq!(cit.open(&sk, &ct, &aad, &Context::raw(b"other")), Err(OpenError::new()));
+}
+
+/// The hybrid KEM under a different suite byte, standing in for a future
+/// suite.
+struct Relabelled;
+
+impl KemProvider for Relabelled {
+    const SUITE_KEM: u8 = 0x7F;
+    fn keygen_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        HybridX25519MlKem768Provider::keygen_with_rng(rng)
+    }
+    fn encapsulate_with_rng<R: RngCore + CryptoRng>(
+        pk: &PublicKey,
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Vec<u8>), SealError> {
+        HybridX25519MlKem768Provider::encapsulate_with_rng(pk, rng)
+    }
+    fn decapsulate(sk: &SecretKey, ct: &[u8]) -> Result<Vec<u8>, OpenError> {
+        HybridX25519MlKem768Provider::decapsulate(sk, ct)
+    }
+}
+
+#[test]
+fn with_provider_pins_suite_and_rejects_mixing() {
+    let (default, pk, sk) = setup();
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+
+    let pinned = Citadel::with_provider::<HybridX25519MlKem768Provider>();
+    let ct = pinned.seal(&pk, b"data", &aad, &ctx).unwrap();
+    assert_eq!(default.open(&sk, &ct, &aad, &ctx).unwrap(), b"data");
+
+    let other = Citadel::with_provider::<Relabelled>();
+    let ct = other.seal(&pk, b"data", &aad, &ctx).unwrap();
+    assert_eq!(ct[1], 0x7F);
+    assert_eq!(other.open(&sk, &ct, &aad, &ctx).unwrap(), b"data");
+    assert_eq!(default.open(&sk, &ct, &aad, &ctx), Err(OpenError::new()));
+}
+
+#[test]
+fn provider_with_rng_is_deterministic() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let cit = Citadel::with_provider::<Relabelled>();
+    let (pk, sk) = cit.generate_keypair_with_rng(&mut StdRng::seed_from_u64(7));
+    let (pk2, _) = cit.generate_keypair_with_rng(&mut StdRng::seed_from_u64(7));
+    assert_eq!(pk.to_bytes(), pk2.to_bytes());
+
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let ct1 = cit.seal_with_rng(&pk, b"data", &aad, &ctx, &mut StdRng::seed_from_u64(42)).unwrap();
+    let ct2 = cit.seal_with_rng(&pk, b"data", &aad, &ctx, &mut StdRng::seed_from_u64(42)).unwrap();
+    assert_eq!(ct1, ct2);
+    assert_eq!(cit.open(&sk, &ct1, &aad, &ctx).unwrap(), b"data");
+}
+
+#[cfg(feature = "diagnostic-errors")]
+#[test]
+fn diagnostic_errors_categorize_without_changing_display() {
+    use citadel_envelope::OpenFailure;
+
+    let (cit, pk, sk) = setup();
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
+
+    let malformed = cit.open(&sk, b"short", &aad, &ctx).unwrap_err();
+    let wrong_aad = cit.open(&sk, &ct, &Aad::raw(b"other"), &ctx).unwrap_err();
+    assert_eq!(malformed.category(), Some(OpenFailure::Decode));
+    assert_eq!(wrong_aad.category(), Some(OpenFailure::AeadVerification));
+
+    assert_eq!(malformed.to_string(), "decryption failed");
+    assert_eq!(wrong_aad.to_string(), "decryption failed");
+    assert_eq!(malformed, OpenError::new());
+    assert!(format!("{:?}", malformed).contains("Decode"));
+}
+
+#[test]
+fn inspect_reports_unknown_versions_but_open_refuses_them() {
+    use citadel_envelope::{inspect, wire::peek_version};
+
+    let (cit, pk, sk) = setup();
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let mut ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
+    assert_eq!(peek_version(&ct), Ok(PROTOCOL_VERSION));
+    assert!(inspect(&ct).unwrap().supported);
+
+    ct[0] = 0x02;
+    assert_eq!(peek_version(&ct), Ok(0x02));
+    let info = inspect(&ct).unwrap();
+    assert_eq!(info.version, 0x02);
+    assert!(!info.supported);
+    assert_eq!((info.kem_suite_id, info.aead_suite_id), (None, None));
+    assert_eq!((info.kem_suite, info.aead_suite), ("unknown", "unknown"));
+    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError::new()));
+
+    // Truncated v1 is still an error, not "unknown format".
+    assert!(inspect(&[PROTOCOL_VERSION, 0xA3]).is_err());
+    assert_eq!(peek_version(&[]), Err(OpenError::new()));
+}
+
+#[test]
+fn validate_pinpoints_structural_damage() {
+    use citadel_envelope::{inspect, inspect_detailed, wire::validate, WireError};
+
+    let (cit, pk, sk) = setup();
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
+    assert_eq!(validate(&ct), Ok(()));
+
+    let damaged = |at: usize, byte: u8| {
+        let mut bad = ct.clone();
+        bad[at] = byte;
+        bad
+    };
+    assert_eq!(validate(&damaged(0, 0x07)), Err(WireError::BadVersion(0x07)));
+    assert_eq!(validate(&damaged(1, 0x00)), Err(WireError::BadKemSuite(0x00)));
+    assert_eq!(validate(&damaged(2, 0x00)), Err(WireError::BadAeadSuite(0x00)));
+    assert_eq!(validate(&damaged(3, 0x80)), Err(WireError::BadFlags(0x80)));
+    assert_eq!(
+        validate(&damaged(5, 0x00)),
+        Err(WireError::KemLenMismatch { got: KEM_CIPHERTEXT_BYTES & 0xFF00, expected: KEM_CIPHERTEXT_BYTES })
+    );
+    assert_eq!(
+        validate(&ct[..MIN_CIPHERTEXT_BYTES - 1]),
+        Err(WireError::TooShort { got: MIN_CIPHERTEXT_BYTES - 1, need: MIN_CIPHERTEXT_BYTES })
+    );
+    assert_eq!(validate(&[]), Err(WireError::TooShort { got: 0, need: MIN_CIPHERTEXT_BYTES }));
+
+    // inspect_detailed surfaces the same diagnosis; inspect and open stay opaque.
+    let bad = damaged(1, 0x00);
+    assert_eq!(inspect_detailed(&bad).unwrap_err(), WireError::BadKemSuite(0x00));
+    assert_eq!(inspect_detailed(&bad).unwrap_err().to_string(), "byte 1: unknown KEM suite 0x00");
+    assert_eq!(inspect(&bad).unwrap_err(), OpenError::new());
+    assert_eq!(cit.open(&sk, &bad, &aad, &ctx), Err(OpenError::new()));
+}
+
+#[test]
+fn encode_wire_names_suite_mismatches() {
+    use citadel_envelope::{
+        wire::{encode_wire, kem_ciphertext_len, EncodeError, KemSuite, NONCE_BYTES, AEAD_TAG_BYTES},
+        SealError,
+    };
+
+    let kem_ct = [0u8; KEM_CIPHERTEXT_BYTES];
+    let nonce = [0u8; NONCE_BYTES];
+    let tag = [0u8; AEAD_TAG_BYTES];
+
+    assert_eq!(
+        kem_ciphertext_len(SUITE_KEM_HYBRID_X25519_MLKEM768),
+        KemSuite::HybridX25519MlKem768.ciphertext_len()
+    );
+    assert_eq!(kem_ciphertext_len(0x7F), KEM_CIPHERTEXT_BYTES);
+
+    let ct = encode_wire(0x7F, SUITE_AEAD_AES256GCM, &kem_ct, &nonce, &tag).unwrap();
+    assert_eq!(&ct[..3], &[PROTOCOL_VERSION, 0x7F, SUITE_AEAD_AES256GCM]);
+
+    let short = encode_wire(0x7F, SUITE_AEAD_AES256GCM, &kem_ct[..32], &nonce, &tag).unwrap_err();
+    assert_eq!(
+        short,
+        EncodeError::KemCiphertextLength { suite_kem: 0x7F, got: 32, expected: KEM_CIPHERTEXT_BYTES }
+    );
+    assert_eq!(short.to_string(), "KEM suite 0x7f produced a 32-byte ciphertext (expected 1120)");
+    assert_eq!(SealError::from(short).to_string(), "encryption failed");
+    assert_eq!(
+        encode_wire(SUITE_KEM_HYBRID_X25519_MLKEM768, 0x00, &kem_ct, &nonce, &tag),
+        Err(EncodeError::UnsupportedAeadSuite(0x00))
+    );
+    assert_eq!(
+        encode_wire(SUITE_KEM_HYBRID_X25519_MLKEM768, SUITE_AEAD_AES256GCM, &kem_ct, &nonce, &[]),
+        Err(EncodeError::AeadCiphertextTooShort { got: 0 })
+    );
+}
+
+#[test]
+fn labeled_seal_separates_epochs() {
+    use citadel_envelope::inspect;
+
+    let (cit, pk, sk) = setup();
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let ct = cit.seal_labeled(&pk, b"epoch data", &aad, &ctx, b"2025").unwrap();
+
+    assert_eq!(ct[3], FLAG_LABELED);
+    assert_eq!(&ct[HEADER_BYTES..HEADER_BYTES + 2], &4u16.to_be_bytes());
+    assert_eq!(ct.len(), Citadel::ciphertext_len(10) + 2);
+    assert_eq!(cit.open_labeled(&sk, &ct, &aad, &ctx, b"2025").unwrap(), b"epoch data");
+
+    assert_eq!(cit.open_labeled(&sk, &ct, &aad, &ctx, b"2026"), Err(OpenError::new()));
+    assert_eq!(cit.open_labeled(&sk, &ct, &aad, &ctx, b"202"), Err(OpenError::new()));
+    assert_eq!(cit.open(&sk, &ct, &aad, &ctx), Err(OpenError::new()));
+
+    // An empty label is still a label.
+    let plain = cit.seal(&pk, b"x", &aad, &ctx).unwrap();
+    assert_eq!(cit.open_labeled(&sk, &plain, &aad, &ctx, b""), Err(OpenError::new()));
+    let empty = cit.seal_labeled(&pk, b"x", &aad, &ctx, b"").unwrap();
+    assert_eq!(cit.open(&sk, &empty, &aad, &ctx), Err(OpenError::new()));
+    assert_eq!(cit.open_labeled(&sk, &empty, &aad, &ctx, b"").unwrap(), b"x");
+
+    let info = inspect(&ct).unwrap();
+    assert!(info.labeled);
+    assert_eq!(info.plaintext_bytes, 10);
+    assert!(!inspect(&plain).unwrap().labeled);
+
+    let too_long = vec![0u8; citadel_envelope::MAX_LABEL_BYTES + 1];
+    assert!(cit.seal_labeled(&pk, b"x", &aad, &ctx, &too_long).is_err());
+}
+
+#[test]
+fn rekey_moves_ciphertext_to_new_recipient() {
+    let (cit, pk_a, sk_a) = setup();
+    let (pk_b, sk_b) = cit.generate_keypair();
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let ct_a = cit.seal(&pk_a, b"handover", &aad, &ctx).unwrap();
+
+    let ct_b = cit.rekey(&sk_a, &pk_b, &ct_a, &aad, &ctx).unwrap();
+    assert_eq!(cit.open(&sk_b, &ct_b, &aad, &ctx).unwrap(), b"handover");
+    assert_eq!(cit.open(&sk_a, &ct_b, &aad, &ctx), Err(OpenError::new()));
+
+    // A can't rekey what it can't open, and the binding must still match.
+    assert_eq!(cit.rekey(&sk_b, &pk_a, &ct_a, &aad, &ctx), Err(OpenError::new()));
+    assert_eq!(cit.rekey(&sk_a, &pk_b, &ct_a, &Aad::raw(b"other"), &ctx), Err(OpenError::new()));
+}
+
+#[test]
+fn child_contexts_nest_but_bind_exactly() {
+    let (cit, pk, sk) = setup();
+    let aad = Aad::raw(b"aad");
+    let billing = Context::for_application("billing", "prod");
+    let invoices = billing.child("invoices");
+    assert_eq!(invoices, Context::raw(b"app|billing|prod|invoices"));
+    assert_eq!(Context::empty().child("app"), Context::raw(b"app"));
+
+    assert!(billing.is_ancestor_of(&invoices));
+    assert!(billing.is_ancestor_of(&invoices.child("2024")));
+    assert!(!invoices.is_ancestor_of(&billing));
+    assert!(!billing.is_ancestor_of(&billing));
+    assert!(!Context::raw(b"app|bill").is_ancestor_of(&billing));
+
+    // The hierarchy is naming only: the parent context can't open a child's ciphertext.
+    let ct = cit.seal(&pk, b"inv-1", &aad, &invoices).unwrap();
+    assert_eq!(cit.open(&sk, &ct, &aad, &billing), Err(OpenError::new()));
+    assert_eq!(cit.open(&sk, &ct, &aad, &invoices).unwrap(), b"inv-1");
+}
+
+#[test]
+fn raw_kem_roundtrip_and_redaction() {
+    let (cit, pk, sk) = setup();
+    let (ss, ct) = cit.encapsulate(&pk).unwrap();
+    assert_eq!(ct.as_bytes().len(), KEM_CIPHERTEXT_BYTES);
+    assert_eq!(cit.decapsulate(&sk, &ct).unwrap().as_bytes(), ss.as_bytes());
+
+    // Another key's secret key decapsulates to something else (implicit rejection).
+    let (_, sk_other) = cit.generate_keypair();
+    assert_ne!(cit.decapsulate(&sk_other, &ct).unwrap().as_bytes(), ss.as_bytes());
+
+    let short = KemCiphertext::from_bytes(&ct.as_bytes()[1..]);
+    assert!(cit.decapsulate(&sk, &short).is_err());
+
+    assert_eq!(format!("{ss:?}"), format!("SharedSecret([REDACTED; {}])", ss.len()));
+}
+
+#[test]
+fn suite_enums_match_wire_bytes() {
+    use citadel_envelope::{inspect, AeadSuite, KemSuite};
+
+    assert_eq!(KemSuite::from_byte(SUITE_KEM_HYBRID_X25519_MLKEM768), Some(KemSuite::HybridX25519MlKem768));
+    assert_eq!(KemSuite::HybridX25519MlKem768.to_byte(), SUITE_KEM_HYBRID_X25519_MLKEM768);
+    assert_eq!(AeadSuite::from_byte(SUITE_AEAD_AES256GCM), Some(AeadSuite::Aes256Gcm));
+    assert_eq!(AeadSuite::Aes256Gcm.to_byte(), SUITE_AEAD_AES256GCM);
+    assert_eq!(KemSuite::from_byte(0x00), None);
+    assert_eq!(AeadSuite::from_byte(SUITE_KEM_HYBRID_X25519_MLKEM768), None);
+
+    let (cit, pk, _) = setup();
+    let ct = cit.seal(&pk, b"x", &Aad::empty(), &Context::empty()).unwrap();
+    let info = inspect(&ct).unwrap();
+    assert_eq!(info.kem_suite_id, Some(KemSuite::HybridX25519MlKem768));
+    assert_eq!(info.aead_suite_id, Some(AeadSuite::Aes256Gcm));
+    assert_eq!(info.kem_suite, KemSuite::HybridX25519MlKem768.name());
+    assert_eq!(info.aead_suite, AeadSuite::Aes256Gcm.name());
+    assert!(info.to_string().contains("X25519+ML-KEM-768 + AES-256-GCM"));
+}
+
+#[test]
+fn kdf_params_default_matches_frozen_construction() {
+    use citadel_envelope::kdf::{KdfParams, SaltSource};
+    use citadel_envelope::wire::PROTOCOL_ID;
+
+    let defaults = KdfParams::default();
+    assert_eq!(defaults.salt_source, SaltSource::Unsalted);
+    assert_eq!(defaults.info, PROTOCOL_ID);
+    assert!(defaults.is_default());
+
+    // Explicit defaults interoperate with a plain engine.
+    let (cit, pk, sk) = setup();
+    let explicit = Citadel::new().with_kdf_params(KdfParams::default());
+    let ct = cit.seal(&pk, b"compat", &Aad::empty(), &Context::empty()).unwrap();
+    assert_eq!(explicit.open(&sk, &ct, &Aad::empty(), &Context::empty()).unwrap(), b"compat");
+
+    // An overridden info prefix is a separate key domain.
+    let custom = Citadel::new().with_kdf_params(KdfParams::with_info(&b"acme-vault-v1"[..]));
+    assert_eq!(custom.kdf_params().info, b"acme-vault-v1");
+    let ct = custom.seal(&pk, b"scoped", &Aad::empty(), &Context::empty()).unwrap();
+    assert_eq!(custom.open(&sk, &ct, &Aad::empty(), &Context::empty()).unwrap(), b"scoped");
+    assert!(cit.open(&sk, &ct, &Aad::empty(), &Context::empty()).is_err());
+
+    assert_eq!(
+        defaults.to_string(),
+        "HKDF-SHA256(salt=none, info=\"citadel-env-v1\" || tag || SHA3-256(kem_ct) || context, len=32)"
+    );
+}
+
+#[test]
+fn error_display_strings_are_fixed() {
+    use citadel_envelope::SealError;
+
+    // Part of the contract: neither message names the step that failed.
+    assert_eq!(OpenError::new().to_string(), "decryption failed");
+    assert_eq!(SealError.to_string(), "encryption failed");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn errors_implement_std_error() {
+    use citadel_envelope::SealError;
+
+    fn source_of(e: &dyn std::error::Error) -> Option<String> {
+        e.source().map(|s| s.to_string())
+    }
+    assert_eq!(source_of(&OpenError::new()), None);
+    assert_eq!(source_of(&SealError), None);
+    let boxed: Box<dyn std::error::Error> = Box::new(SealError);
+    assert_eq!(boxed.to_string(), "encryption failed");
+}
+
+#[test]
+fn container_frame_roundtrips_and_leaves_raw_alone() {
+    use citadel_envelope::container;
+
+    let (cit, pk, sk) = setup();
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let ct = cit.seal(&pk, b"file body", &aad, &ctx).unwrap();
+
+    let framed = container::frame(&ct);
+    assert_eq!(&framed[..4], b"CTD1");
+    assert!(container::is_framed(&framed) && !container::is_framed(&ct));
+    assert_eq!(container::unframe(&framed), &ct[..]);
+    assert_eq!(container::unframe(&ct), &ct[..]);
+    assert_eq!(cit.open(&sk, container::unframe(&framed), &aad, &ctx).unwrap(), b"file body");
+
+    // The magic is not part of the wire format.
+    assert_eq!(cit.open(&sk, &framed, &aad, &ctx), Err(OpenError::new()));
+    assert!(!citadel_envelope::inspect(&framed).unwrap().supported);
+}
+
+#[test]
+fn nonce_source_supplies_seal_nonces() {
+    use citadel_envelope::{NonceSource, SealError};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    struct Counter(AtomicU64);
+    impl NonceSource for Counter {
+        fn next_nonce(&self) -> Result<[u8; 12], SealError> {
+            let mut n = [0u8; 12];
+            n[4..].copy_from_slice(&self.0.fetch_add(1, Ordering::SeqCst).to_be_bytes());
+            Ok(n)
+        }
+    }
+    struct Exhausted;
+    impl NonceSource for Exhausted {
+        fn next_nonce(&self) -> Result<[u8; 12], SealError> {
+            Err(SealError)
+        }
+    }
+
+    let (_, pk, sk) = setup();
+    let cit = Citadel::new().with_nonce_source(Counter(AtomicU64::new(7)));
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let nonce_at = HEADER_BYTES + KEM_CIPHERTEXT_BYTES;
+    for expected in [7u64, 8] {
+        let ct = cit.seal(&pk, b"data", &aad, &ctx).unwrap();
+        assert_eq!(&ct[nonce_at + 4..nonce_at + 12], &expected.to_be_bytes());
+        assert_eq!(cit.open(&sk, &ct, &aad, &ctx).unwrap(), b"data");
+    }
+    let labeled = cit.seal_labeled(&pk, b"data", &aad, &ctx, b"l").unwrap();
+    assert_eq!(&labeled[nonce_at + 2 + 4..nonce_at + 2 + 12], &9u64.to_be_bytes());
+
+    let dry = Citadel::new().with_nonce_source(Exhausted);
+    assert_eq!(dry.seal(&pk, b"data", &aad, &ctx), Err(SealError));
+}
+
+#[test]
+fn max_ciphertext_len_caps_open() {
+    let (cit, pk, sk) = setup();
+    let aad = Aad::raw(b"aad");
+    let ctx = Context::raw(b"ctx");
+    let ct = cit.seal(&pk, &[7u8; 100], &aad, &ctx).unwrap();
+    let labeled = cit.seal_labeled(&pk, &[7u8; 100], &aad, &ctx, b"l").unwrap();
+
+    let exact = Citadel::new().with_max_ciphertext_len(ct.len());
+    assert_eq!(exact.open(&sk, &ct, &aad, &ctx).unwrap(), [7u8; 100]);
+
+    let tight = Citadel::new().with_max_ciphertext_len(ct.len() - 1);
+    assert_eq!(tight.open(&sk, &ct, &aad, &ctx), Err(OpenError::new()));
+    assert_eq!(tight.open_labeled(&sk, &labeled, &aad, &ctx, b"l"), Err(OpenError::new()));
+    // Sealing is not capped.
+    assert!(tight.seal(&pk, &[7u8; 100], &aad, &ctx).is_ok());
+}
+
+#[test]
+fn borrowed_bindings_match_owned() {
+    use citadel_envelope::{AadRef, ContextRef};
+
+    let (cit, pk, sk) = setup();
+    let aad_bytes = b"batch|7".to_vec();
+    let ctx_bytes = b"app|export".to_vec();
+    let (aad, ctx) = (AadRef::raw(&aad_bytes), ContextRef::raw(&ctx_bytes));
+    assert_eq!(Aad::from(aad), Aad::raw(&aad_bytes));
+    assert_eq!(Context::from(ctx), Context::raw(&ctx_bytes));
+
+    let ct = cit.seal(&pk, b"row", aad, ctx).unwrap();
+    let (aad, ctx) = (Aad::from(aad), Context::from(ctx));
+    drop((aad_bytes, ctx_bytes));
+    assert_eq!(cit.open(&sk, &ct, &aad, &ctx).unwrap(), b"row");
+    assert_eq!(cit.open(&sk, &ct, AadRef::from(&aad), ContextRef::from(&ctx)).unwrap(), b"row");
+}
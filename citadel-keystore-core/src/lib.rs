@@ -0,0 +1,78 @@
+//! # Citadel Keystore Core
+//!
+//! The `no_std` + `alloc` subset of `citadel-keystore` for embedded use:
+//! the key lifecycle state machine, a synchronous `BTreeMap` keystore and
+//! envelope encrypt/decrypt. No async runtime, clock, audit log, policy
+//! engine or threat assessment; callers supply the RNG.
+//!
+//! `citadel-keystore` re-exports `KeyState` and `KeyType` from here, so
+//! both crates share one state machine.
+//!
+//! ```
+//! use citadel_envelope::{Aad, Context};
+//! use citadel_keystore_core::{CoreKeystore, KeyType};
+//! use rand_core::OsRng;
+//!
+//! let mut ks = CoreKeystore::new();
+//! let id = ks.generate_with_rng(KeyType::DataEncrypting, &mut OsRng).unwrap();
+//! ks.activate(&id).unwrap();
+//!
+//! let (aad, ctx) = (Aad::raw(b"aad"), Context::raw(b"ctx"));
+//! let ct = ks.encrypt_with_rng(&id, b"secret", &aad, &ctx, &mut OsRng).unwrap();
+//! assert_eq!(ks.decrypt(&id, &ct, &aad, &ctx).unwrap(), b"secret");
+//! ```
+
+#![no_std]
+#![deny(unsafe_code)]
+
+extern crate alloc;
+
+pub mod state;
+pub mod store;
+
+pub use state::{KeyState, KeyType};
+pub use store::{CoreError, CoreKey, CoreKeystore, KeyId};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citadel_envelope::{Aad, Context};
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_core_lifecycle_enforces_state_machine() {
+        let mut ks = CoreKeystore::new();
+        let id = ks.generate_with_rng(KeyType::DataEncrypting, &mut OsRng).unwrap();
+        let (aad, ctx) = (Aad::raw(b"aad"), Context::raw(b"ctx"));
+
+        assert_eq!(
+            ks.encrypt_with_rng(&id, b"early", &aad, &ctx, &mut OsRng),
+            Err(CoreError::NotActive(id))
+        );
+        ks.activate(&id).unwrap();
+        assert!(matches!(ks.activate(&id), Err(CoreError::InvalidTransition { .. })));
+
+        let ct = ks.encrypt_with_rng(&id, b"data", &aad, &ctx, &mut OsRng).unwrap();
+        let next = ks.rotate_with_rng(&id, &mut OsRng).unwrap();
+        assert_eq!(ks.state(&id), Some(KeyState::Rotated));
+        assert_eq!(ks.get(&id).unwrap().successor, Some(next));
+        assert_eq!(ks.state(&next), Some(KeyState::Active));
+        assert_eq!(ks.decrypt(&id, &ct, &aad, &ctx).unwrap(), b"data");
+
+        ks.transition(&id, KeyState::Expired).unwrap();
+        ks.destroy(&id).unwrap();
+        assert_eq!(ks.decrypt(&id, &ct, &aad, &ctx), Err(CoreError::NotDecryptable(id)));
+        assert_eq!(ks.len(), 2);
+    }
+
+    #[test]
+    fn test_core_rejects_duplicate_material() {
+        let citadel = citadel_envelope::Citadel::new();
+        let (pk, sk) = citadel.generate_keypair();
+        let (_, sk2) = citadel.generate_keypair();
+        let mut ks = CoreKeystore::new();
+
+        let id = ks.insert(KeyType::KeyEncrypting, pk.clone(), sk).unwrap();
+        assert_eq!(ks.insert(KeyType::KeyEncrypting, pk, sk2), Err(CoreError::DuplicateKey(id)));
+    }
+}
@@ -0,0 +1,107 @@
+//! Key types and the lifecycle state machine.
+
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+// ---------------------------------------------------------------------------
+// Key types (4-level hierarchy)
+// ---------------------------------------------------------------------------
+
+/// Position in the key hierarchy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeyType {
+    /// Root key — offline, protects the entire hierarchy.
+    Root,
+    /// Domain key — per-tenant or per-environment.
+    Domain,
+    /// Key-encrypting key — wraps DEKs.
+    KeyEncrypting,
+    /// Data-encrypting key — directly encrypts user data.
+    DataEncrypting,
+}
+
+impl fmt::Display for KeyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyType::Root => write!(f, "ROOT"),
+            KeyType::Domain => write!(f, "DOMAIN"),
+            KeyType::KeyEncrypting => write!(f, "KEK"),
+            KeyType::DataEncrypting => write!(f, "DEK"),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Key lifecycle state machine
+// ---------------------------------------------------------------------------
+
+/// Key lifecycle state.
+///
+/// ```text
+/// PENDING → ACTIVE → ROTATED → EXPIRED → DESTROYED
+///             │
+///             ├──→ REVOKED ─────────────────→ DESTROYED
+///             └──→ COMPROMISED ─────────────→ DESTROYED
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeyState {
+    /// Generated but not yet activated.
+    Pending,
+    /// Active — can encrypt and decrypt.
+    Active,
+    /// Rotated — superseded by a successor key. Can still decrypt (grace period).
+    Rotated,
+    /// Expired — can no longer encrypt or decrypt.
+    Expired,
+    /// Revoked — emergency deactivation. Cannot be reactivated.
+    Revoked,
+    /// Compromised — material believed exposed. Like `Revoked`, but also
+    /// raises the threat level and is audited as an incident.
+    Compromised,
+    /// Destroyed — key material has been purged.
+    Destroyed,
+}
+
+impl KeyState {
+    /// Whether this state allows encryption.
+    pub fn can_encrypt(&self) -> bool {
+        matches!(self, KeyState::Active)
+    }
+
+    /// Whether this state allows decryption.
+    pub fn can_decrypt(&self) -> bool {
+        matches!(self, KeyState::Active | KeyState::Rotated)
+    }
+
+    /// Valid transitions from this state.
+    pub fn valid_transitions(&self) -> &[KeyState] {
+        match self {
+            KeyState::Pending => &[KeyState::Active, KeyState::Destroyed],
+            KeyState::Active => &[KeyState::Rotated, KeyState::Revoked, KeyState::Compromised, KeyState::Expired],
+            KeyState::Rotated => &[KeyState::Expired],
+            KeyState::Expired => &[KeyState::Destroyed],
+            KeyState::Revoked => &[KeyState::Destroyed],
+            KeyState::Compromised => &[KeyState::Destroyed],
+            KeyState::Destroyed => &[],
+        }
+    }
+
+    /// Check if transitioning to `target` is valid.
+    pub fn can_transition_to(&self, target: KeyState) -> bool {
+        self.valid_transitions().contains(&target)
+    }
+}
+
+impl fmt::Display for KeyState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyState::Pending => write!(f, "PENDING"),
+            KeyState::Active => write!(f, "ACTIVE"),
+            KeyState::Rotated => write!(f, "ROTATED"),
+            KeyState::Expired => write!(f, "EXPIRED"),
+            KeyState::Revoked => write!(f, "REVOKED"),
+            KeyState::Compromised => write!(f, "COMPROMISED"),
+            KeyState::Destroyed => write!(f, "DESTROYED"),
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Synchronous in-memory keystore over a `BTreeMap`.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+
+use citadel_envelope::{Aad, Citadel, Context, OpenError, PublicKey, SealError, SecretKey};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::state::{KeyState, KeyType};
+
+// ---------------------------------------------------------------------------
+// Key identifiers
+// ---------------------------------------------------------------------------
+
+/// Key identifier: the first 16 bytes of SHA-256 over the public key, the
+/// same fingerprint `citadel_keystore::KeyId::from_public_key` hex-encodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyId([u8; 16]);
+
+impl KeyId {
+    pub fn from_public_key(pk: &PublicKey) -> Self {
+        use sha2::{Digest, Sha256};
+        let digest = Sha256::digest(pk.to_bytes());
+        let mut id = [0u8; 16];
+        id.copy_from_slice(&digest[..16]);
+        Self(id)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl fmt::Display for KeyId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreError {
+    KeyNotFound(KeyId),
+    DuplicateKey(KeyId),
+    InvalidTransition { id: KeyId, from: KeyState, to: KeyState },
+    NotActive(KeyId),
+    NotDecryptable(KeyId),
+    Seal(SealError),
+    Open(OpenError),
+}
+
+impl fmt::Display for CoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KeyNotFound(id) => write!(f, "key not found: {}", id),
+            Self::DuplicateKey(id) => write!(f, "duplicate key: {}", id),
+            Self::InvalidTransition { id, from, to } => {
+                write!(f, "invalid transition for {}: {} → {}", id, from, to)
+            }
+            Self::NotActive(id) => write!(f, "key not active: {}", id),
+            Self::NotDecryptable(id) => write!(f, "key cannot decrypt: {}", id),
+            Self::Seal(e) => write!(f, "{}", e),
+            Self::Open(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Keystore
+// ---------------------------------------------------------------------------
+
+/// One key: its place in the hierarchy, lifecycle state and material.
+pub struct CoreKey {
+    pub key_type: KeyType,
+    pub state: KeyState,
+    /// Successor minted by `rotate_with_rng`.
+    pub successor: Option<KeyId>,
+    public_key: PublicKey,
+    /// `None` once the key is destroyed.
+    secret_key: Option<SecretKey>,
+}
+
+impl CoreKey {
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+}
+
+/// Key lifecycle without async, audit, policy or threat tracking.
+///
+/// Every method that needs randomness takes the RNG, so firmware can
+/// supply its own TRNG-backed `CryptoRng`.
+#[derive(Default)]
+pub struct CoreKeystore {
+    keys: BTreeMap<KeyId, CoreKey>,
+    citadel: Citadel,
+}
+
+impl CoreKeystore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generate a PENDING key.
+    pub fn generate_with_rng<R: RngCore + CryptoRng>(
+        &mut self,
+        key_type: KeyType,
+        rng: &mut R,
+    ) -> Result<KeyId, CoreError> {
+        let (pk, sk) = self.citadel.generate_keypair_with_rng(rng);
+        self.insert(key_type, pk, sk)
+    }
+
+    /// Add existing key material as a PENDING key.
+    pub fn insert(&mut self, key_type: KeyType, pk: PublicKey, sk: SecretKey) -> Result<KeyId, CoreError> {
+        let id = KeyId::from_public_key(&pk);
+        if self.keys.contains_key(&id) {
+            return Err(CoreError::DuplicateKey(id));
+        }
+        self.keys.insert(id, CoreKey {
+            key_type,
+            state: KeyState::Pending,
+            successor: None,
+            public_key: pk,
+            secret_key: Some(sk),
+        });
+        Ok(id)
+    }
+
+    pub fn get(&self, id: &KeyId) -> Option<&CoreKey> {
+        self.keys.get(id)
+    }
+
+    pub fn state(&self, id: &KeyId) -> Option<KeyState> {
+        self.keys.get(id).map(|k| k.state)
+    }
+
+    /// IDs in ascending order.
+    pub fn ids(&self) -> impl Iterator<Item = &KeyId> {
+        self.keys.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Move a key to `target` if the state machine allows it. Entering
+    /// DESTROYED drops (and so zeroizes) the secret key.
+    pub fn transition(&mut self, id: &KeyId, target: KeyState) -> Result<(), CoreError> {
+        let key = self.keys.get_mut(id).ok_or(CoreError::KeyNotFound(*id))?;
+        if !key.state.can_transition_to(target) {
+            return Err(CoreError::InvalidTransition { id: *id, from: key.state, to: target });
+        }
+        key.state = target;
+        if target == KeyState::Destroyed {
+            key.secret_key = None;
+        }
+        Ok(())
+    }
+
+    pub fn activate(&mut self, id: &KeyId) -> Result<(), CoreError> {
+        self.transition(id, KeyState::Active)
+    }
+
+    pub fn revoke(&mut self, id: &KeyId) -> Result<(), CoreError> {
+        self.transition(id, KeyState::Revoked)
+    }
+
+    pub fn destroy(&mut self, id: &KeyId) -> Result<(), CoreError> {
+        self.transition(id, KeyState::Destroyed)
+    }
+
+    /// Retire an ACTIVE key in favour of a fresh ACTIVE successor of the
+    /// same type. Returns the successor's ID.
+    pub fn rotate_with_rng<R: RngCore + CryptoRng>(&mut self, id: &KeyId, rng: &mut R) -> Result<KeyId, CoreError> {
+        let key = self.keys.get(id).ok_or(CoreError::KeyNotFound(*id))?;
+        if key.state != KeyState::Active {
+            return Err(CoreError::NotActive(*id));
+        }
+        let key_type = key.key_type;
+
+        let successor = self.generate_with_rng(key_type, rng)?;
+        self.activate(&successor)?;
+        self.transition(id, KeyState::Rotated)?;
+        if let Some(key) = self.keys.get_mut(id) {
+            key.successor = Some(successor);
+        }
+        Ok(successor)
+    }
+
+    /// Seal to an ACTIVE key.
+    pub fn encrypt_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        id: &KeyId,
+        plaintext: &[u8],
+        aad: &Aad,
+        context: &Context,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, CoreError> {
+        let key = self.keys.get(id).ok_or(CoreError::KeyNotFound(*id))?;
+        if !key.state.can_encrypt() {
+            return Err(CoreError::NotActive(*id));
+        }
+        self.citadel
+            .seal_with_rng(&key.public_key, plaintext, aad, context, rng)
+            .map_err(CoreError::Seal)
+    }
+
+    /// Open with an ACTIVE or ROTATED key.
+    pub fn decrypt(
+        &self,
+        id: &KeyId,
+        ciphertext: &[u8],
+        aad: &Aad,
+        context: &Context,
+    ) -> Result<Vec<u8>, CoreError> {
+        let key = self.keys.get(id).ok_or(CoreError::KeyNotFound(*id))?;
+        let sk = match (&key.secret_key, key.state.can_decrypt()) {
+            (Some(sk), true) => sk,
+            _ => return Err(CoreError::NotDecryptable(*id)),
+        };
+        self.citadel
+            .open(sk, ciphertext, aad, context)
+            .map_err(CoreError::Open)
+    }
+}
@@ -3,6 +3,7 @@
 use crate::types::{KeyId, KeyState, KeyType};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -18,13 +19,25 @@ pub enum AuditAction {
     KeyRotated { new_version: u32 },
     KeyExpired { reason: String },
     KeyRevoked { reason: String },
+    KeyCompromised { detail: String },
     KeyDestroyed,
+    KeyExported { wrapped: bool },
+    KeyImported,
+    ArchiveExported { keys: usize },
+    ArchiveImported { keys: usize },
+    TagSet { key: String },
+    TagRemoved { key: String },
+    AllowedContextsSet { count: usize },
     EncryptionPerformed { key_version: u32 },
     DecryptionPerformed { key_version: u32 },
     DecryptionFailed { key_version: u32 },
     PolicyRegistered { policy_id: String },
+    PolicyUpdated { policy_id: String },
+    DefaultPolicySet { key_type: KeyType, policy_id: Option<String> },
     PolicyEvaluated { verdict: String },
+    ThreatConfigUpdated,
     ExpirationCheckRun { expired_count: usize, warning_count: usize },
+    DestructionSweepRun { destroyed_count: usize, blocked_count: usize },
 }
 
 /// A structured audit event.
@@ -119,6 +132,9 @@ impl AuditEvent {
 /// For async sinks, use interior mutability (e.g., channel-based).
 pub trait AuditSinkSync: Send + Sync {
     fn record(&self, event: AuditEvent);
+
+    /// Flush any buffered events to durable storage (called on shutdown).
+    fn flush(&self) {}
 }
 
 // ---------------------------------------------------------------------------
@@ -171,6 +187,10 @@ impl InMemoryAuditSink {
     pub async fn len(&self) -> usize {
         self.events.lock().await.len()
     }
+
+    pub async fn is_empty(&self) -> bool {
+        self.events.lock().await.is_empty()
+    }
 }
 
 impl Default for InMemoryAuditSink {
@@ -188,6 +208,66 @@ impl AuditSinkSync for InMemoryAuditSink {
     }
 }
 
+/// Keeps only the most recent `capacity` events, for long-running
+/// servers that need a recent-activity view without unbounded growth.
+///
+/// Same query surface as `InMemoryAuditSink`, oldest first. Unlike it,
+/// `record` never drops an event under contention; it evicts the oldest
+/// once the ring is full.
+pub struct RingAuditSink {
+    events: std::sync::Mutex<VecDeque<AuditEvent>>,
+    capacity: usize,
+}
+
+impl RingAuditSink {
+    /// A ring holding up to `capacity` events (0 keeps nothing).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: std::sync::Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub async fn events(&self) -> Vec<AuditEvent> {
+        self.events.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub async fn events_for_key(&self, key_id: &KeyId) -> Vec<AuditEvent> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.key_id.as_ref() == Some(key_id))
+            .cloned()
+            .collect()
+    }
+
+    pub async fn len(&self) -> usize {
+        self.events.lock().unwrap().len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.events.lock().unwrap().is_empty()
+    }
+}
+
+impl AuditSinkSync for RingAuditSink {
+    fn record(&self, event: AuditEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut events = self.events.lock().unwrap();
+        if events.len() == self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+}
+
 /// Writes JSON events to a file (append-only).
 pub struct FileAuditSink {
     path: std::path::PathBuf,
@@ -199,6 +279,43 @@ impl FileAuditSink {
     }
 }
 
+impl FileAuditSink {
+    /// Read every event back from the log, oldest first.
+    ///
+    /// A missing file yields an empty log. A line that does not parse
+    /// as an `AuditEvent` is reported as `InvalidData` with its line number.
+    pub fn read_events(&self) -> std::io::Result<Vec<AuditEvent>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("line {}: {}", n + 1, e),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Check that the log can be opened for appending, without writing
+    /// an event. Creates the file if it does not exist yet.
+    pub fn check_writable(&self) -> std::io::Result<()> {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map(|_| ())
+    }
+}
+
 impl AuditSinkSync for FileAuditSink {
     fn record(&self, event: AuditEvent) {
         use std::io::Write;
@@ -227,6 +344,63 @@ impl AuditSinkSync for FileAuditSink {
             }
         }
     }
+    fn flush(&self) {
+        if let Ok(file) = std::fs::OpenOptions::new().append(true).open(&self.path) {
+            if let Err(e) = file.sync_all() {
+                eprintln!("[audit] sync error: {}", e);
+            }
+        }
+    }
+}
+
+/// Fans every event out to several sinks (e.g. a file, a SIEM exporter
+/// and an in-memory ring for the API).
+///
+/// Delivery is best-effort per sink: a sink that panics is reported on
+/// stderr and skipped, and the rest still receive the event. Wrap the
+/// composite in an `IntegrityChainSink` to give every destination the
+/// same chained sequence.
+pub struct CompositeAuditSink {
+    sinks: Vec<Arc<dyn AuditSinkSync>>,
+}
+
+impl CompositeAuditSink {
+    pub fn new(sinks: Vec<Arc<dyn AuditSinkSync>>) -> Self {
+        Self { sinks }
+    }
+
+    /// Add another destination.
+    pub fn with_sink(mut self, sink: Arc<dyn AuditSinkSync>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    fn each(&self, op: &str, f: impl Fn(&dyn AuditSinkSync)) {
+        for (i, sink) in self.sinks.iter().enumerate() {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(sink.as_ref())));
+            if result.is_err() {
+                eprintln!("[audit] sink {} panicked during {}", i, op);
+            }
+        }
+    }
+}
+
+impl AuditSinkSync for CompositeAuditSink {
+    fn record(&self, event: AuditEvent) {
+        self.each("record", |sink| sink.record(event.clone()));
+    }
+
+    fn flush(&self) {
+        self.each("flush", |sink| sink.flush());
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -240,7 +414,9 @@ impl AuditSinkSync for FileAuditSink {
 /// Verifiers can replay the log and recompute hashes to detect
 /// any insertion, deletion, or modification of events.
 ///
-/// The genesis hash is `SHA-256("citadel-audit-genesis")`.
+/// The genesis hash is `SHA-256("citadel-audit-genesis")`. A sink
+/// appending to an existing log must continue its chain with
+/// [`resume_after`](Self::resume_after) rather than start a new one.
 pub struct IntegrityChainSink {
     inner: Arc<dyn AuditSinkSync>,
     state: std::sync::Mutex<ChainState>,
@@ -253,16 +429,91 @@ struct ChainState {
 
 impl IntegrityChainSink {
     pub fn new(inner: Arc<dyn AuditSinkSync>) -> Self {
-        use sha2::{Sha256, Digest};
-        let genesis = format!("{:x}", Sha256::digest(b"citadel-audit-genesis"));
         Self {
             inner,
             state: std::sync::Mutex::new(ChainState {
                 sequence: 0,
-                prev_hash: genesis,
+                prev_hash: genesis_hash(),
             }),
         }
     }
+
+    /// Continue the chain after `last`, the final event already in the log:
+    /// the next event gets `last`'s sequence + 1 and the hash of its JSON.
+    pub fn resume_after(self, last: &AuditEvent) -> Self {
+        use sha2::{Sha256, Digest};
+
+        if let Ok(json) = serde_json::to_string(last) {
+            let mut state = self.state.lock().unwrap();
+            state.sequence = last.sequence.map_or(0, |s| s + 1);
+            state.prev_hash = format!("{:x}", Sha256::digest(json.as_bytes()));
+        }
+        self
+    }
+}
+
+/// SHA-256 hex digest that the first event of every chain points back to.
+pub fn genesis_hash() -> String {
+    use sha2::{Sha256, Digest};
+    format!("{:x}", Sha256::digest(b"citadel-audit-genesis"))
+}
+
+/// The first link that failed verification in [`verify_chain`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainBreak {
+    /// Position of the offending event in the verified slice.
+    pub index: usize,
+    /// Sequence number recorded on the offending event, if any.
+    pub sequence: Option<u64>,
+    /// Why the link is considered broken.
+    pub reason: String,
+}
+
+/// Replay a log written through `IntegrityChainSink` and check every link.
+///
+/// Each event must carry the next sequence number and the SHA-256 of the
+/// previous event's JSON. Only the first event may point at the genesis
+/// hash: a restart resumes the chain (see
+/// [`IntegrityChainSink::resume_after`]), so a second genesis event means
+/// segments were spliced or a tail was cut.
+///
+/// Returns the number of events verified, or the first broken link.
+pub fn verify_chain(events: &[AuditEvent]) -> Result<usize, ChainBreak> {
+    use sha2::{Sha256, Digest};
+
+    let genesis = genesis_hash();
+    let mut expected: Option<(u64, String)> = None;
+
+    for (index, event) in events.iter().enumerate() {
+        let brk = |reason: String| ChainBreak { index, sequence: event.sequence, reason };
+
+        let (Some(sequence), Some(prev_hash)) = (event.sequence, event.prev_hash.as_ref()) else {
+            return Err(brk("missing chain metadata".into()));
+        };
+
+        match &expected {
+            None if sequence != 0 || *prev_hash != genesis => {
+                return Err(brk("chain does not start at genesis".into()));
+            }
+            None => {}
+            Some(_) if *prev_hash == genesis => {
+                return Err(brk("chain restarts at genesis after the first event".into()));
+            }
+            Some((seq, _)) if *seq != sequence => {
+                return Err(brk(format!("expected sequence {}, found {}", seq, sequence)));
+            }
+            Some((_, hash)) if hash != prev_hash => {
+                return Err(brk("prev_hash does not match previous event".into()));
+            }
+            Some(_) => {}
+        }
+
+        let json = serde_json::to_string(event)
+            .map_err(|e| brk(format!("cannot re-serialize event: {}", e)))?;
+        expected = Some((sequence + 1, format!("{:x}", Sha256::digest(json.as_bytes()))));
+    }
+
+    Ok(events.len())
 }
 
 impl AuditSinkSync for IntegrityChainSink {
@@ -285,4 +536,8 @@ impl AuditSinkSync for IntegrityChainSink {
         drop(state); // Release lock before forwarding
         self.inner.record(event);
     }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
 }
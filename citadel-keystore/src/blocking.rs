@@ -0,0 +1,180 @@
+//! Synchronous keystore facade for non-async callers (CLI tools, scripts).
+//!
+//! Enabled with the `blocking` feature. [`BlockingKeystore`] owns a
+//! [`Keystore`] and a single-threaded tokio runtime, and drives each async
+//! method to completion on it. The methods that are already synchronous
+//! (policies, threat state, metrics) are reached through
+//! [`BlockingKeystore::keystore`].
+//!
+//! Do not call these methods from inside an async runtime: like
+//! `Runtime::block_on`, they panic there. Async code should use
+//! [`Keystore`] directly.
+
+use crate::error::*;
+use crate::export::{ArchiveSummary, KeyExport};
+use crate::keystore::{EncryptedBlob, Keystore};
+use crate::types::*;
+use citadel_envelope::{Aad, Context};
+use tokio::runtime::{Builder, Runtime};
+
+/// A [`Keystore`] whose async methods block the calling thread.
+pub struct BlockingKeystore {
+    keystore: Keystore,
+    runtime: Runtime,
+}
+
+impl BlockingKeystore {
+    /// Wrap `keystore`, starting the runtime its methods run on.
+    pub fn new(keystore: Keystore) -> Result<Self, KeystoreError> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| KeystoreError::StorageError(format!("cannot start runtime: {}", e)))?;
+        Ok(Self { keystore, runtime })
+    }
+
+    /// The wrapped keystore, for its synchronous methods.
+    pub fn keystore(&self) -> &Keystore {
+        &self.keystore
+    }
+
+    pub fn into_inner(self) -> Keystore {
+        self.keystore
+    }
+
+    pub fn generate(
+        &self,
+        name: impl Into<String>,
+        key_type: KeyType,
+        policy_id: Option<PolicyId>,
+        parent_id: Option<KeyId>,
+    ) -> Result<KeyId, GenerateError> {
+        self.runtime.block_on(self.keystore.generate(name, key_type, policy_id, parent_id))
+    }
+
+    pub fn generate_derived(
+        &self,
+        name: impl Into<String>,
+        key_type: KeyType,
+        policy_id: Option<PolicyId>,
+        parent_id: KeyId,
+    ) -> Result<KeyId, GenerateError> {
+        self.runtime.block_on(self.keystore.generate_derived(name, key_type, policy_id, parent_id))
+    }
+
+    pub fn get(&self, id: &KeyId) -> Result<KeyMetadata, KeystoreError> {
+        self.runtime.block_on(self.keystore.get(id))
+    }
+
+    pub fn list_keys(&self) -> Result<Vec<KeyMetadata>, KeystoreError> {
+        self.runtime.block_on(self.keystore.list_keys())
+    }
+
+    pub fn list_by_state(&self, state: KeyState) -> Result<Vec<KeyMetadata>, KeystoreError> {
+        self.runtime.block_on(self.keystore.list_by_state(state))
+    }
+
+    pub fn list_by_tag(&self, key: &str, value: Option<&str>) -> Result<Vec<KeyMetadata>, KeystoreError> {
+        self.runtime.block_on(self.keystore.list_by_tag(key, value))
+    }
+
+    pub fn key_versions(&self, id: &KeyId) -> Result<Vec<VersionInfo>, KeystoreError> {
+        self.runtime.block_on(self.keystore.key_versions(id))
+    }
+
+    pub fn set_tag(&self, id: &KeyId, key: impl Into<String>, value: impl Into<String>) -> Result<(), KeystoreError> {
+        self.runtime.block_on(self.keystore.set_tag(id, key, value))
+    }
+
+    pub fn remove_tag(&self, id: &KeyId, key: &str) -> Result<Option<String>, KeystoreError> {
+        self.runtime.block_on(self.keystore.remove_tag(id, key))
+    }
+
+    pub fn set_allowed_contexts(&self, id: &KeyId, contexts: Vec<Vec<u8>>) -> Result<(), KeystoreError> {
+        self.runtime.block_on(self.keystore.set_allowed_contexts(id, contexts))
+    }
+
+    pub fn activate(&self, id: &KeyId) -> Result<(), LifecycleError> {
+        self.runtime.block_on(self.keystore.activate(id))
+    }
+
+    pub fn rotate(&self, id: &KeyId) -> Result<KeyId, RotateError> {
+        self.runtime.block_on(self.keystore.rotate(id))
+    }
+
+    pub fn revoke(&self, id: &KeyId, reason: impl Into<String>) -> Result<(), LifecycleError> {
+        self.runtime.block_on(self.keystore.revoke(id, reason))
+    }
+
+    pub fn compromise(&self, id: &KeyId, detail: impl Into<String>) -> Result<(), LifecycleError> {
+        self.runtime.block_on(self.keystore.compromise(id, detail))
+    }
+
+    pub fn expire(&self, id: &KeyId) -> Result<ExpirationSource, ExpireError> {
+        self.runtime.block_on(self.keystore.expire(id))
+    }
+
+    pub fn destroy(&self, id: &KeyId) -> Result<(), LifecycleError> {
+        self.runtime.block_on(self.keystore.destroy(id))
+    }
+
+    pub fn export_key(&self, id: &KeyId, passphrase: Option<&str>) -> Result<KeyExport, KeystoreError> {
+        self.runtime.block_on(self.keystore.export_key(id, passphrase))
+    }
+
+    pub fn import_key(&self, export: KeyExport, passphrase: Option<&str>) -> Result<KeyId, KeystoreError> {
+        self.runtime.block_on(self.keystore.import_key(export, passphrase))
+    }
+
+    pub fn export_archive<W: std::io::Write>(&self, writer: W) -> Result<ArchiveSummary, KeystoreError> {
+        self.runtime.block_on(self.keystore.export_archive(writer))
+    }
+
+    pub fn import_archive<R: std::io::Read>(&self, reader: R) -> Result<ArchiveSummary, KeystoreError> {
+        self.runtime.block_on(self.keystore.import_archive(reader))
+    }
+
+    pub fn check_rotation_due(&self) -> Result<Vec<(KeyId, String)>, KeystoreError> {
+        self.runtime.block_on(self.keystore.check_rotation_due())
+    }
+
+    pub fn expire_due_keys(&self) -> Result<ExpirationReport, KeystoreError> {
+        self.runtime.block_on(self.keystore.expire_due_keys())
+    }
+
+    pub fn destroy_eligible(&self) -> Result<DestructionReport, KeystoreError> {
+        self.runtime.block_on(self.keystore.destroy_eligible())
+    }
+
+    pub fn sweep_destroyable(&self) -> Result<DestructionReport, KeystoreError> {
+        self.runtime.block_on(self.keystore.sweep_destroyable())
+    }
+
+    pub fn encrypt(
+        &self,
+        key_id: &KeyId,
+        plaintext: &[u8],
+        aad: &Aad,
+        context: &Context,
+    ) -> Result<EncryptedBlob, EncryptError> {
+        self.runtime.block_on(self.keystore.encrypt(key_id, plaintext, aad, context))
+    }
+
+    pub fn decrypt(&self, blob: &EncryptedBlob, aad: &Aad, context: &Context) -> Result<Vec<u8>, DecryptError> {
+        self.runtime.block_on(self.keystore.decrypt(blob, aad, context))
+    }
+
+    pub fn seal_and_store(
+        &self,
+        key_id: &KeyId,
+        plaintext: &[u8],
+        aad: &Aad,
+        context: &Context,
+    ) -> Result<BlobId, EncryptError> {
+        self.runtime.block_on(self.keystore.seal_and_store(key_id, plaintext, aad, context))
+    }
+
+    pub fn open_stored(&self, blob_id: &BlobId, aad: &Aad, context: &Context) -> Result<Vec<u8>, DecryptError> {
+        self.runtime.block_on(self.keystore.open_stored(blob_id, aad, context))
+    }
+}
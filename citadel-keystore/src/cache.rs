@@ -0,0 +1,61 @@
+//! Bounded LRU cache of parsed secret keys, so hot keys skip the
+//! hex-decode + parse of 2432 bytes on every decrypt.
+//!
+//! Entries are `Arc<SecretKey>`; the underlying X25519 and ML-KEM keys
+//! zeroize on drop, so an evicted or invalidated key is wiped once the
+//! last in-flight decrypt using it finishes.
+
+use crate::types::KeyId;
+use citadel_envelope::SecretKey;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Default number of parsed secret keys kept in memory.
+pub const DEFAULT_SECRET_KEY_CACHE_CAPACITY: usize = 64;
+
+pub(crate) struct SecretKeyCache {
+    capacity: usize,
+    /// (key, version) -> (parsed key, last-use tick)
+    entries: HashMap<(KeyId, u32), (Arc<SecretKey>, u64)>,
+    tick: u64,
+}
+
+impl SecretKeyCache {
+    /// A capacity of 0 disables caching.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self { capacity, entries: HashMap::new(), tick: 0 }
+    }
+
+    pub(crate) fn get(&mut self, id: &KeyId, version: u32) -> Option<Arc<SecretKey>> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(&(id.clone(), version)).map(|(sk, used)| {
+            *used = tick;
+            sk.clone()
+        })
+    }
+
+    pub(crate) fn insert(&mut self, id: &KeyId, version: u32, sk: Arc<SecretKey>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            let oldest = self.entries.iter().min_by_key(|(_, (_, used))| *used).map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.tick += 1;
+        self.entries.insert((id.clone(), version), (sk, self.tick));
+    }
+
+    /// Drop every cached version of `id`.
+    pub(crate) fn invalidate(&mut self, id: &KeyId) {
+        self.entries.retain(|(key, _), _| key != id);
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
@@ -0,0 +1,25 @@
+//! Deterministic child-key derivation for `Keystore::generate_derived`.
+//!
+//! The child's keypair is drawn from a ChaCha20 stream seeded with
+//! HKDF-SHA256 over the parent's secret key, bound to the child's type and
+//! name. The same parent secret, type and name always give the same child,
+//! so a backup of the parent is enough to regenerate everything below it.
+
+use crate::types::KeyType;
+use hkdf::Hkdf;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+const DERIVE_SALT: &[u8] = b"citadel-keystore/derive/v1";
+
+/// RNG whose output is fully determined by `(parent_secret, key_type, name)`.
+pub(crate) fn child_rng(parent_secret: &[u8], key_type: KeyType, name: &str) -> ChaCha20Rng {
+    let info = format!("{}|{}", key_type, name);
+    let mut seed = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::new(Some(DERIVE_SALT), parent_secret)
+        .expand(info.as_bytes(), seed.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    ChaCha20Rng::from_seed(*seed)
+}
@@ -1,6 +1,10 @@
 //! Error types for the keystore.
 
+use crate::policy::{KeyPolicy, PolicyVerdict};
+use crate::threat::ThreatLevel;
 use crate::types::{KeyId, KeyState};
+use chrono::{DateTime, Utc};
+use citadel_envelope::{DecryptionError, EncodingError};
 use std::fmt;
 use std::time::Duration;
 
@@ -8,9 +12,11 @@ use std::time::Duration;
 // Top-level keystore error
 // ---------------------------------------------------------------------------
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum KeystoreError {
     KeyNotFound(KeyId),
+    /// The key (and the keys it supersedes) holds no such version.
+    VersionNotFound { id: KeyId, version: u32 },
     InvalidTransition { id: KeyId, from: KeyState, to: KeyState },
     PolicyViolation(String),
     StorageError(String),
@@ -20,12 +26,15 @@ pub enum KeystoreError {
     NotActive(KeyId),
     NotDecryptable(KeyId),
     PolicyNotFound(String),
+    InvalidInput(String),
+    ContextNotAllowed(KeyId),
 }
 
 impl fmt::Display for KeystoreError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::KeyNotFound(id) => write!(f, "key not found: {}", id),
+            Self::VersionNotFound { id, version } => write!(f, "version {} not found for key {}", version, id),
             Self::InvalidTransition { id, from, to } => {
                 write!(f, "invalid transition for {}: {} → {}", id, from, to)
             }
@@ -37,12 +46,43 @@ impl fmt::Display for KeystoreError {
             Self::NotActive(id) => write!(f, "key not active: {}", id),
             Self::NotDecryptable(id) => write!(f, "key cannot decrypt: {}", id),
             Self::PolicyNotFound(id) => write!(f, "policy not found: {}", id),
+            Self::InvalidInput(msg) => write!(f, "invalid input: {}", msg),
+            Self::ContextNotAllowed(id) => write!(f, "context not allowed for key {}", id),
         }
     }
 }
 
 impl std::error::Error for KeystoreError {}
 
+/// Why a string was refused by `KeyId::parse`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyIdError {
+    Empty,
+    /// Length in bytes.
+    TooLong(usize),
+    InvalidChar(char),
+}
+
+impl fmt::Display for KeyIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "key id is empty"),
+            Self::TooLong(len) => {
+                write!(f, "key id is {} bytes (max {})", len, crate::types::MAX_KEY_ID_LEN)
+            }
+            Self::InvalidChar(c) => write!(f, "key id contains {:?} (allowed: a-z 0-9 _ -)", c),
+        }
+    }
+}
+
+impl std::error::Error for KeyIdError {}
+
+impl From<KeyIdError> for KeystoreError {
+    fn from(e: KeyIdError) -> Self {
+        Self::InvalidInput(e.to_string())
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Specific operation errors (type-safe)
 // ---------------------------------------------------------------------------
@@ -87,19 +127,55 @@ impl From<KeystoreError> for ExpireError {
     fn from(e: KeystoreError) -> Self { Self(e) }
 }
 
+/// Why an encryption failed: the keystore refused it (unknown key, key
+/// state, policy, storage), or the envelope could not seal.
 #[derive(Debug)]
-pub struct EncryptError(pub String);
+pub enum EncryptError {
+    Keystore(KeystoreError),
+    Seal(EncodingError),
+}
 impl fmt::Display for EncryptError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "encrypt: {}", self.0) }
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Keystore(e) => write!(f, "encrypt: {}", e),
+            Self::Seal(e) => write!(f, "encrypt: {}", e),
+        }
+    }
 }
 impl std::error::Error for EncryptError {}
+impl From<KeystoreError> for EncryptError {
+    fn from(e: KeystoreError) -> Self { Self::Keystore(e) }
+}
+impl From<EncodingError> for EncryptError {
+    fn from(e: EncodingError) -> Self { Self::Seal(e) }
+}
 
-#[derive(Debug)]
-pub struct DecryptError(pub String);
+/// Why a decryption failed: the keystore refused it (unknown key or
+/// version, key state, storage), or the ciphertext did not open.
+///
+/// `Open` is deliberately uniform: a wrong key, AAD or context and a
+/// tampered ciphertext all look the same. The failing stage goes to the
+/// audit log, not here.
+#[derive(Clone, Debug)]
+pub enum DecryptError {
+    Keystore(KeystoreError),
+    Open(DecryptionError),
+}
 impl fmt::Display for DecryptError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "decrypt: {}", self.0) }
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Keystore(e) => write!(f, "decrypt: {}", e),
+            Self::Open(e) => write!(f, "decrypt: {}", e),
+        }
+    }
 }
 impl std::error::Error for DecryptError {}
+impl From<KeystoreError> for DecryptError {
+    fn from(e: KeystoreError) -> Self { Self::Keystore(e) }
+}
+impl From<DecryptionError> for DecryptError {
+    fn from(e: DecryptionError) -> Self { Self::Open(e) }
+}
 
 // ---------------------------------------------------------------------------
 // Expiration decision types
@@ -181,3 +257,46 @@ pub struct ExpirationReport {
     pub failed: Vec<(KeyId, String)>,
     pub skipped: usize,
 }
+
+// ---------------------------------------------------------------------------
+// Rotation preview
+// ---------------------------------------------------------------------------
+
+/// What `Keystore::rotate` would do to a key right now
+/// (`Keystore::rotation_preview`). Nothing is written to produce it.
+#[derive(Clone, Debug)]
+pub struct RotationPreview {
+    pub key_id: KeyId,
+    /// Versions the key holds, oldest first.
+    pub versions: Vec<u32>,
+    pub current_version: u32,
+    /// Version the successor starts at. Rotation mints a new key, so this
+    /// is always 1 under a fresh ID.
+    pub successor_version: u32,
+    /// Encryptions under the current version.
+    pub current_usage: u64,
+    /// Threat level the effective policy was adapted for.
+    pub threat_level: ThreatLevel,
+    /// The key's policy adapted to `threat_level` (None if it has none).
+    pub effective_policy: Option<KeyPolicy>,
+    /// That policy's verdict on the key (None if it has no policy).
+    pub verdict: Option<PolicyVerdict>,
+    /// How long the rotated key stays decryptable.
+    pub grace_period: Duration,
+    /// When rotation would happen (now).
+    pub rotated_at: DateTime<Utc>,
+    /// When the rotated key would expire: end of its grace period.
+    pub expires_at: DateTime<Utc>,
+    /// Earliest time it could then be destroyed, a further grace period
+    /// on (retention floors may push this later).
+    pub destroy_eligible_at: DateTime<Utc>,
+}
+
+/// Report from a bulk destruction sweep (`Keystore::destroy_eligible`,
+/// `Keystore::sweep_destroyable`).
+#[derive(Clone, Debug, Default)]
+pub struct DestructionReport {
+    pub destroyed: Vec<KeyId>,
+    pub blocked: Vec<(KeyId, String)>,
+    pub failed: Vec<(KeyId, String)>,
+}
@@ -0,0 +1,290 @@
+//! Key export/import for backup and migration between storage backends.
+//!
+//! A `KeyExport` carries the full `KeyMetadata`. Secret material is either
+//! left in place (plaintext export — treat the JSON like the key itself) or
+//! wrapped under a passphrase with Argon2id + AES-256-GCM.
+//!
+//! A keystore archive bundles plaintext exports of every key with the
+//! registered policies and a threat snapshot, as length-prefixed JSON frames
+//! after an 8-byte magic and a format version. It holds secret material in
+//! the clear: protect it like the keys themselves.
+
+use crate::error::KeystoreError;
+use crate::types::{KeyId, KeyMetadata, KeyState};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use zeroize::Zeroizing;
+
+/// Current export format version.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+const WRAP_ALGORITHM: &str = "argon2id+aes-256-gcm";
+
+/// A portable copy of one key, produced by `Keystore::export_key`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeyExport {
+    /// Format version (currently 1).
+    pub format_version: u32,
+    /// When the export was produced.
+    pub exported_at: DateTime<Utc>,
+    /// Key metadata. If `wrapping` is set, every `secret_key_hex` is empty.
+    pub metadata: KeyMetadata,
+    /// Passphrase-wrapped secret material, if a passphrase was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrapping: Option<PassphraseWrap>,
+}
+
+/// Secret keys of all versions, encrypted under a passphrase-derived key.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PassphraseWrap {
+    /// Always `argon2id+aes-256-gcm`.
+    pub algorithm: String,
+    /// Argon2id salt (hex).
+    pub salt_hex: String,
+    /// AES-GCM nonce (hex).
+    pub nonce_hex: String,
+    /// AES-256-GCM over the JSON list of secret keys in version order.
+    /// The export's metadata, with the secrets stripped, is bound as AAD,
+    /// so editing any of it (public keys included) fails the unwrap.
+    pub ciphertext_hex: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, KeystoreError> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| KeystoreError::InvalidInput(format!("passphrase kdf: {}", e)))?;
+    Ok(key)
+}
+
+/// AAD for a passphrase wrap: `meta` (secrets already stripped) as JSON.
+/// Going through `serde_json::Value` sorts object keys, so the bytes don't
+/// depend on `tags`' hash order.
+fn wrap_aad(meta: &KeyMetadata) -> Result<Vec<u8>, KeystoreError> {
+    serde_json::to_value(meta)
+        .and_then(|v| serde_json::to_vec(&v))
+        .map_err(|e| KeystoreError::StorageError(e.to_string()))
+}
+
+/// Move every version's secret key out of `meta` into a passphrase wrap.
+pub(crate) fn wrap_secrets(meta: &mut KeyMetadata, passphrase: &str) -> Result<PassphraseWrap, KeystoreError> {
+    use aes_gcm::aead::{Aead, KeyInit, Payload};
+    use aes_gcm::{Aes256Gcm, Nonce};
+    use rand_core::RngCore;
+
+    if passphrase.is_empty() {
+        return Err(KeystoreError::InvalidInput("export passphrase must not be empty".into()));
+    }
+
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    rand_core::OsRng.fill_bytes(&mut salt);
+    rand_core::OsRng.fill_bytes(&mut nonce);
+
+    let secrets: Zeroizing<Vec<String>> = Zeroizing::new(
+        meta.versions
+            .iter_mut()
+            .map(|v| std::mem::take(&mut v.secret_key_hex))
+            .collect(),
+    );
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(&*secrets).map_err(|e| KeystoreError::StorageError(e.to_string()))?,
+    );
+
+    let aad = wrap_aad(meta)?;
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new_from_slice(key.as_ref())
+        .map_err(|_| KeystoreError::EnvelopeError("invalid wrapping key".into()))?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: &aad })
+        .map_err(|_| KeystoreError::EnvelopeError("wrap secret material failed".into()))?;
+
+    Ok(PassphraseWrap {
+        algorithm: WRAP_ALGORITHM.into(),
+        salt_hex: hex::encode(salt),
+        nonce_hex: hex::encode(nonce),
+        ciphertext_hex: hex::encode(ciphertext),
+    })
+}
+
+/// Restore secret keys into `meta` from a passphrase wrap. `meta` must be
+/// exactly as exported, before any migration.
+pub(crate) fn unwrap_secrets(meta: &mut KeyMetadata, wrap: &PassphraseWrap, passphrase: &str) -> Result<(), KeystoreError> {
+    use aes_gcm::aead::{Aead, KeyInit, Payload};
+    use aes_gcm::{Aes256Gcm, Nonce};
+
+    if wrap.algorithm != WRAP_ALGORITHM {
+        return Err(KeystoreError::InvalidInput(format!("unsupported wrap algorithm: {}", wrap.algorithm)));
+    }
+    let decode = |field: &str, value: &str| {
+        hex::decode(value).map_err(|e| KeystoreError::InvalidInput(format!("{}: {}", field, e)))
+    };
+    let salt = decode("salt_hex", &wrap.salt_hex)?;
+    let nonce = decode("nonce_hex", &wrap.nonce_hex)?;
+    let ciphertext = decode("ciphertext_hex", &wrap.ciphertext_hex)?;
+    if nonce.len() != 12 {
+        return Err(KeystoreError::InvalidInput("nonce_hex must be 12 bytes".into()));
+    }
+
+    let aad = wrap_aad(meta)?;
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new_from_slice(key.as_ref())
+        .map_err(|_| KeystoreError::EnvelopeError("invalid wrapping key".into()))?;
+    let plaintext = Zeroizing::new(
+        cipher
+            .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &aad })
+            .map_err(|_| KeystoreError::InvalidInput("wrong passphrase or corrupted export".into()))?,
+    );
+    let mut secrets: Zeroizing<Vec<String>> = Zeroizing::new(
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| KeystoreError::InvalidInput(format!("wrapped secrets: {}", e)))?,
+    );
+    if secrets.len() != meta.versions.len() {
+        return Err(KeystoreError::InvalidInput("wrapped secrets do not match versions".into()));
+    }
+    for (version, secret) in meta.versions.iter_mut().zip(secrets.iter_mut()) {
+        version.secret_key_hex = std::mem::take(secret);
+    }
+    Ok(())
+}
+
+/// Check that imported metadata is internally consistent and carries
+/// usable key material, with each version's public key matching its
+/// secret key.
+pub(crate) fn validate_import(meta: &KeyMetadata) -> Result<(), KeystoreError> {
+    let invalid = |msg: String| Err(KeystoreError::InvalidInput(msg));
+
+    KeyId::parse(meta.id.as_str())?;
+    if meta.state == KeyState::Destroyed {
+        return Err(KeystoreError::KeyDestroyed(meta.id.clone()));
+    }
+
+    let timestamp_ok = match meta.state {
+        KeyState::Pending => meta.activated_at.is_none(),
+        KeyState::Active => meta.activated_at.is_some(),
+        KeyState::Rotated => meta.rotated_at.is_some(),
+        KeyState::Revoked => meta.revoked_at.is_some(),
+        KeyState::Compromised => meta.compromised_at.is_some(),
+        KeyState::Expired | KeyState::Destroyed => true,
+    };
+    if !timestamp_ok {
+        return invalid(format!("{} key has inconsistent lifecycle timestamps", meta.state));
+    }
+
+    if meta.versions.is_empty() {
+        return invalid("key has no versions".into());
+    }
+    let mut seen = HashSet::new();
+    for v in &meta.versions {
+        if !seen.insert(v.version) {
+            return invalid(format!("duplicate version {}", v.version));
+        }
+        let pk = hex::decode(&v.public_key_hex)
+            .map_err(|e| KeystoreError::InvalidInput(format!("version {} public key: {}", v.version, e)))?;
+        citadel_envelope::PublicKey::from_bytes(&pk)
+            .map_err(|_| KeystoreError::InvalidInput(format!("version {} public key is malformed", v.version)))?;
+        let sk = Zeroizing::new(
+            hex::decode(&v.secret_key_hex)
+                .map_err(|e| KeystoreError::InvalidInput(format!("version {} secret key: {}", v.version, e)))?,
+        );
+        let sk = citadel_envelope::SecretKey::from_bytes(&sk)
+            .map_err(|_| KeystoreError::InvalidInput(format!("version {} secret key is malformed", v.version)))?;
+        if sk.public_key().to_bytes()[..] != pk[..] {
+            return invalid(format!("version {} public key does not match its secret key", v.version));
+        }
+    }
+    if !seen.contains(&meta.current_version) {
+        return invalid(format!("current version {} not present", meta.current_version));
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Whole-keystore archive
+// ---------------------------------------------------------------------------
+
+/// Leading bytes of an archive produced by `Keystore::export_archive`.
+pub const ARCHIVE_MAGIC: &[u8; 8] = b"CITADELA";
+
+/// Current archive format version.
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// Largest frame body accepted on import, so a corrupt length cannot
+/// trigger a huge allocation.
+const MAX_FRAME_BYTES: usize = 64 * 1024 * 1024;
+
+/// Frame tags. Every frame is `tag[1] || len[4, BE] || JSON body[len]`.
+pub(crate) const FRAME_END: u8 = 0;
+pub(crate) const FRAME_KEY: u8 = 1;
+pub(crate) const FRAME_POLICY: u8 = 2;
+pub(crate) const FRAME_THREAT: u8 = 3;
+
+/// What an archive export or import covered.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveSummary {
+    /// Keys written or restored (destroyed keys are skipped on export).
+    pub keys: usize,
+    /// Policies written or registered.
+    pub policies: usize,
+    /// Threat events carried in the threat snapshot.
+    pub threat_events: usize,
+}
+
+fn io_err(e: std::io::Error) -> KeystoreError {
+    KeystoreError::StorageError(format!("archive: {}", e))
+}
+
+/// Write the archive header (magic and format version).
+pub(crate) fn write_archive_header(w: &mut impl std::io::Write) -> Result<(), KeystoreError> {
+    w.write_all(ARCHIVE_MAGIC).map_err(io_err)?;
+    w.write_all(&ARCHIVE_FORMAT_VERSION.to_be_bytes()).map_err(io_err)
+}
+
+/// Read and check the archive header.
+pub(crate) fn read_archive_header(r: &mut impl std::io::Read) -> Result<(), KeystoreError> {
+    let mut magic = [0u8; 8];
+    r.read_exact(&mut magic).map_err(io_err)?;
+    if &magic != ARCHIVE_MAGIC {
+        return Err(KeystoreError::InvalidInput("not a keystore archive".into()));
+    }
+    let mut version = [0u8; 4];
+    r.read_exact(&mut version).map_err(io_err)?;
+    let version = u32::from_be_bytes(version);
+    if version != ARCHIVE_FORMAT_VERSION {
+        return Err(KeystoreError::InvalidInput(format!("unsupported archive format version {}", version)));
+    }
+    Ok(())
+}
+
+/// Write one frame with `value` serialized as JSON.
+pub(crate) fn write_frame<T: Serialize>(w: &mut impl std::io::Write, tag: u8, value: &T) -> Result<(), KeystoreError> {
+    let body = Zeroizing::new(
+        serde_json::to_vec(value).map_err(|e| KeystoreError::StorageError(e.to_string()))?,
+    );
+    let len = u32::try_from(body.len())
+        .ok()
+        .filter(|&n| n as usize <= MAX_FRAME_BYTES)
+        .ok_or_else(|| KeystoreError::InvalidInput("archive frame too large".into()))?;
+    w.write_all(&[tag]).map_err(io_err)?;
+    w.write_all(&len.to_be_bytes()).map_err(io_err)?;
+    w.write_all(&body).map_err(io_err)
+}
+
+/// Write the terminating frame. An archive without one is truncated.
+pub(crate) fn write_end_frame(w: &mut impl std::io::Write) -> Result<(), KeystoreError> {
+    w.write_all(&[FRAME_END, 0, 0, 0, 0]).map_err(io_err)
+}
+
+/// Read the next frame's tag and body.
+pub(crate) fn read_frame(r: &mut impl std::io::Read) -> Result<(u8, Zeroizing<Vec<u8>>), KeystoreError> {
+    let mut head = [0u8; 5];
+    r.read_exact(&mut head).map_err(io_err)?;
+    let len = u32::from_be_bytes([head[1], head[2], head[3], head[4]]) as usize;
+    if len > MAX_FRAME_BYTES {
+        return Err(KeystoreError::InvalidInput(format!("archive frame of {} bytes exceeds limit", len)));
+    }
+    let mut body = Zeroizing::new(vec![0u8; len]);
+    r.read_exact(&mut body).map_err(io_err)?;
+    Ok((head[0], body))
+}
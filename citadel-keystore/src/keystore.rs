@@ -1,17 +1,21 @@
 //! Main keystore: key lifecycle management with policy, audit, and envelope integration.
 
 use crate::audit::{AuditAction, AuditEvent, AuditSinkSync};
+use crate::cache::{SecretKeyCache, DEFAULT_SECRET_KEY_CACHE_CAPACITY};
+use crate::clock::{Clock, SystemClock};
 use crate::error::*;
+use crate::export::{self, ArchiveSummary, KeyExport};
+use crate::metrics::{LatencyHistogram, OperationMetrics};
 use crate::policy::{self, KeyPolicy};
-use crate::storage::StorageBackend;
-use crate::threat::{PolicyAdapter, SecurityMetrics, ThreatAssessor, ThreatConfig, ThreatEvent, ThreatEventKind, ThreatLevel};
+use crate::storage::{BlobStorageBackend, StorageBackend};
+use crate::threat::{LevelChangeHook, PolicyAdapter, SecurityMetrics, ThreatAssessor, ThreatConfig, ThreatEvent, ThreatEventKind, ThreatEventLog, ThreatLevel, ThreatSnapshot};
 use crate::types::*;
 
 use chrono::Utc;
 use citadel_envelope::{Aad, Citadel, Context};
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 // ---------------------------------------------------------------------------
 // Encrypted blob (output of convenience encrypt)
@@ -34,12 +38,62 @@ pub struct EncryptedBlob {
 // Keystore
 // ---------------------------------------------------------------------------
 
+tokio::task_local! {
+    /// Actor attributed to audit events recorded inside an `ActingKeystore` call.
+    static AUDIT_ACTOR: String;
+}
+
+/// Default cap on the AAD and context accepted by `encrypt`.
+pub const DEFAULT_MAX_BINDING_BYTES: usize = 4096;
+
+/// Threat severity of one policy-blocked encrypt: a nudge, so only a
+/// sustained stream of violations moves the level.
+const POLICY_VIOLATION_SEVERITY: f64 = 0.5;
+
+/// How far back `decrypt` follows `supersedes` links, so a corrupted
+/// cycle of records can't loop forever.
+const MAX_SUPERSEDES_HOPS: usize = 64;
+
+/// Idle per-key locks are pruned once the lock table grows past this.
+const KEY_LOCK_PRUNE_THRESHOLD: usize = 1024;
+
+/// Callback invoked for each expiration warning: `(key_id, reason, remaining)`.
+pub type ExpirationWarningHook = Box<dyn Fn(&KeyId, &str, Duration) + Send + Sync>;
+
+/// Key lifecycle manager.
+///
+/// All methods take `&self`, so a keystore can be shared behind an `Arc`
+/// (as the API server does) and still have policies registered or
+/// updated at runtime.
+///
+/// Operations that read a key's metadata, change it and write it back
+/// (`encrypt`, lifecycle transitions, tags) hold that key's lock across
+/// the whole span, so a concurrent `rotate` cannot be overwritten by an
+/// `encrypt` that read the record before it. Each operation holds at most
+/// one key lock at a time, so there is no lock ordering to get wrong.
 pub struct Keystore {
     storage: Arc<dyn StorageBackend>,
     audit: Arc<dyn AuditSinkSync>,
-    policies: HashMap<String, KeyPolicy>,
+    /// Registered policies by ID. Behind a lock so they can be hot-reloaded.
+    policies: RwLock<HashMap<String, KeyPolicy>>,
+    /// Policy assigned to keys generated without one, by key type.
+    default_policies: RwLock<HashMap<KeyType, PolicyId>>,
     envelope: Citadel,
     threat: Mutex<ThreatAssessor>,
+    /// Optional alerting hook for `expire_due_keys` warnings.
+    expiration_warning_hook: RwLock<Option<ExpirationWarningHook>>,
+    /// Time source for lifecycle timestamps, expiry and policy age checks.
+    clock: Arc<dyn Clock>,
+    /// Parsed secret keys by (key, version), shared by concurrent decrypts.
+    sk_cache: Arc<Mutex<SecretKeyCache>>,
+    /// Latency of successful encrypts / decrypts.
+    latency: Mutex<(LatencyHistogram, LatencyHistogram)>,
+    /// Largest AAD / context, in bytes, that `encrypt` accepts.
+    max_binding_bytes: usize,
+    /// Per-key locks serializing metadata read-modify-write.
+    key_locks: Mutex<HashMap<KeyId, Arc<tokio::sync::Mutex<()>>>>,
+    /// Where `seal_and_store` keeps blobs, if configured.
+    blob_store: Option<Arc<dyn BlobStorageBackend>>,
 }
 
 impl Keystore {
@@ -51,9 +105,17 @@ impl Keystore {
         Self {
             storage,
             audit: audit.clone(),
-            policies: HashMap::new(),
+            policies: RwLock::new(HashMap::new()),
+            default_policies: RwLock::new(HashMap::new()),
             envelope: Citadel::new(),
             threat: Mutex::new(ThreatAssessor::new(ThreatConfig::default()).with_audit(audit)),
+            expiration_warning_hook: RwLock::new(None),
+            clock: Arc::new(SystemClock),
+            sk_cache: Arc::new(Mutex::new(SecretKeyCache::new(DEFAULT_SECRET_KEY_CACHE_CAPACITY))),
+            latency: Mutex::new((LatencyHistogram::new(), LatencyHistogram::new())),
+            max_binding_bytes: DEFAULT_MAX_BINDING_BYTES,
+            key_locks: Mutex::new(HashMap::new()),
+            blob_store: None,
         }
     }
 
@@ -66,36 +128,155 @@ impl Keystore {
         Self {
             storage,
             audit: audit.clone(),
-            policies: HashMap::new(),
+            policies: RwLock::new(HashMap::new()),
+            default_policies: RwLock::new(HashMap::new()),
             envelope: Citadel::new(),
             threat: Mutex::new(ThreatAssessor::new(threat_config).with_audit(audit)),
+            expiration_warning_hook: RwLock::new(None),
+            clock: Arc::new(SystemClock),
+            sk_cache: Arc::new(Mutex::new(SecretKeyCache::new(DEFAULT_SECRET_KEY_CACHE_CAPACITY))),
+            latency: Mutex::new((LatencyHistogram::new(), LatencyHistogram::new())),
+            max_binding_bytes: DEFAULT_MAX_BINDING_BYTES,
+            key_locks: Mutex::new(HashMap::new()),
+            blob_store: None,
         }
     }
 
+    /// Use a custom time source for the keystore and its threat assessor
+    /// (e.g. `MockClock` to fast-forward grace periods in tests).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.threat = Mutex::new(self.threat.into_inner().unwrap().with_clock(clock.clone()));
+        self.clock = clock;
+        self
+    }
+
+    /// Set how many parsed secret keys `decrypt` keeps cached
+    /// (default 64). A capacity of 0 disables the cache.
+    pub fn with_secret_key_cache(self, capacity: usize) -> Self {
+        *self.sk_cache.lock().unwrap() = SecretKeyCache::new(capacity);
+        self
+    }
+
+    /// Cap the AAD and context length (each) that `encrypt` accepts
+    /// (default 4 KiB). Both are authenticated on every open, so unbounded
+    /// values let a caller inflate the cost of every blob. Decryption is
+    /// not capped: blobs sealed before the cap (or under a larger one)
+    /// must stay readable.
+    pub fn with_max_binding_bytes(mut self, max: usize) -> Self {
+        self.max_binding_bytes = max;
+        self
+    }
+
+    /// Keep blobs from `seal_and_store` in `store`.
+    pub fn with_blob_store(mut self, store: Arc<dyn BlobStorageBackend>) -> Self {
+        self.blob_store = Some(store);
+        self
+    }
+
+    /// Scope subsequent operations to an actor (e.g. the authenticated API
+    /// key) so their audit events name it instead of `"system"`.
+    pub fn with_actor(&self, actor: impl Into<String>) -> ActingKeystore<'_> {
+        ActingKeystore { keystore: self, actor: actor.into() }
+    }
+
+    /// Take `id`'s metadata lock. Hold the guard from the `get` to the
+    /// `put`; never while awaiting another key's lock.
+    async fn lock_key(&self, id: &KeyId) -> tokio::sync::OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.key_locks.lock().unwrap();
+            if locks.len() >= KEY_LOCK_PRUNE_THRESHOLD {
+                // Only the table holds an idle lock, and new holders clone
+                // it under this mutex, so dropping those entries is safe.
+                locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            }
+            locks.entry(id.clone()).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+
+    fn record_audit(&self, event: AuditEvent) {
+        let event = match AUDIT_ACTOR.try_with(|actor| actor.clone()) {
+            Ok(actor) => event.with_actor(actor),
+            Err(_) => event,
+        };
+        self.audit.record(event);
+    }
+
     // -----------------------------------------------------------------------
     // Policy management
     // -----------------------------------------------------------------------
 
-    /// Register a policy.
-    pub fn register_policy(&mut self, policy: KeyPolicy) {
-        self.audit.record(AuditEvent::system_event(
+    /// Register a policy (replaces any policy with the same ID).
+    pub fn register_policy(&self, policy: KeyPolicy) {
+        self.record_audit(AuditEvent::system_event(
             AuditAction::PolicyRegistered {
                 policy_id: policy.id.as_str().to_string(),
             },
         ));
-        self.policies.insert(policy.id.as_str().to_string(), policy);
+        self.policies.write().unwrap().insert(policy.id.as_str().to_string(), policy);
+    }
+
+    /// Update an already-registered policy. The policy is validated first.
+    pub fn update_policy(&self, policy: KeyPolicy) -> Result<(), KeystoreError> {
+        policy.validate()?;
+        let mut policies = self.policies.write().unwrap();
+        if !policies.contains_key(policy.id.as_str()) {
+            return Err(KeystoreError::PolicyNotFound(policy.id.as_str().to_string()));
+        }
+        self.record_audit(AuditEvent::system_event(
+            AuditAction::PolicyUpdated {
+                policy_id: policy.id.as_str().to_string(),
+            },
+        ));
+        policies.insert(policy.id.as_str().to_string(), policy);
+        Ok(())
+    }
+
+    /// Get a registered policy (owned copy).
+    pub fn get_policy(&self, id: &PolicyId) -> Option<KeyPolicy> {
+        self.policies.read().unwrap().get(id.as_str()).cloned()
+    }
+
+    /// Make `policy_id` the policy of every `key_type` key generated
+    /// without an explicit one. The policy must already be registered.
+    /// Existing keys are not touched.
+    pub fn set_default_policy(&self, key_type: KeyType, policy_id: PolicyId) -> Result<(), KeystoreError> {
+        if !self.policies.read().unwrap().contains_key(policy_id.as_str()) {
+            return Err(KeystoreError::PolicyNotFound(policy_id.as_str().to_string()));
+        }
+        self.record_audit(AuditEvent::system_event(AuditAction::DefaultPolicySet {
+            key_type,
+            policy_id: Some(policy_id.as_str().to_string()),
+        }));
+        self.default_policies.write().unwrap().insert(key_type, policy_id);
+        Ok(())
+    }
+
+    /// Stop assigning a default policy to new `key_type` keys.
+    pub fn clear_default_policy(&self, key_type: KeyType) {
+        if self.default_policies.write().unwrap().remove(&key_type).is_some() {
+            self.record_audit(AuditEvent::system_event(AuditAction::DefaultPolicySet { key_type, policy_id: None }));
+        }
+    }
+
+    /// The policy new `key_type` keys get when generated without one.
+    pub fn default_policy(&self, key_type: KeyType) -> Option<PolicyId> {
+        self.default_policies.read().unwrap().get(&key_type).cloned()
     }
 
-    /// Get a registered policy.
-    pub fn get_policy(&self, id: &PolicyId) -> Option<&KeyPolicy> {
-        self.policies.get(id.as_str())
+    /// IDs of all registered policies, sorted.
+    pub fn policy_ids(&self) -> Vec<PolicyId> {
+        let mut ids: Vec<PolicyId> = self.policies.read().unwrap().keys().map(PolicyId::new).collect();
+        ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        ids
     }
 
     // -----------------------------------------------------------------------
     // Key generation
     // -----------------------------------------------------------------------
 
-    /// Generate a new key, returning its ID.
+    /// Generate a new key, returning its ID. Without a `policy_id` the key
+    /// gets its type's default policy, if one is set.
     pub async fn generate(
         &self,
         name: impl Into<String>,
@@ -103,42 +284,124 @@ impl Keystore {
         policy_id: Option<PolicyId>,
         parent_id: Option<KeyId>,
     ) -> Result<KeyId, GenerateError> {
-        let id = KeyId::generate();
-        let now = Utc::now();
-
         // Generate actual cryptographic keypair
         let (pk, sk) = self.envelope.generate_keypair();
+        self.store_new_key(name.into(), key_type, policy_id, parent_id, None, &pk, &sk)
+    }
+
+    /// Generate a key under `parent_id` whose material is derived from the
+    /// parent's current secret key (HKDF-SHA256 + ChaCha20, bound to
+    /// `key_type` and `name`) instead of drawn at random.
+    ///
+    /// Deriving the same name and type from the same parent secret always
+    /// yields the same keypair, so a backup of the parent (e.g. an exported
+    /// root) can regenerate the tree below it.
+    ///
+    /// The parent must be `Active` or `Rotated`: a child of a revoked,
+    /// compromised or expired key would be known to whoever holds that
+    /// secret. A parent whose secret material is gone is an error rather
+    /// than a silent fallback to random material, which no backup could
+    /// regenerate.
+    ///
+    /// Because the material is reproducible, a derived sibling with the same
+    /// name and type must not already exist: deriving again would recreate
+    /// it, even after it was destroyed. Rotating a derived key mints a
+    /// random successor.
+    pub async fn generate_derived(
+        &self,
+        name: impl Into<String>,
+        key_type: KeyType,
+        policy_id: Option<PolicyId>,
+        parent_id: KeyId,
+    ) -> Result<KeyId, GenerateError> {
+        let name = name.into();
+        // Held until the child is stored, so the parent can't change state
+        // and two callers can't both pass the duplicate check.
+        let _guard = self.lock_key(&parent_id).await;
+        let parent = self.get(&parent_id).await?;
+        match parent.state {
+            KeyState::Active | KeyState::Rotated => {}
+            KeyState::Destroyed => return Err(GenerateError(KeystoreError::KeyDestroyed(parent_id))),
+            _ => return Err(GenerateError(KeystoreError::NotActive(parent_id))),
+        }
+        let (parent_version, parent_secret) = parent.current_key_version()
+            .and_then(|v| hex::decode(&v.secret_key_hex).ok().map(|bytes| (v.version, zeroize::Zeroizing::new(bytes))))
+            .ok_or_else(|| GenerateError(KeystoreError::StorageError(format!(
+                "key {} has no secret material to derive from", parent_id
+            ))))?;
+
+        if let Some(existing) = self.storage.list()?.into_iter().find(|k| {
+            KeyId::ct_matches(k.parent_id.as_ref(), &parent_id)
+                && k.derived_from_version == Some(parent_version)
+                && k.key_type == key_type
+                && k.name == name
+        }) {
+            return Err(GenerateError(match existing.state {
+                KeyState::Destroyed => KeystoreError::KeyDestroyed(existing.id),
+                _ => KeystoreError::DuplicateKey(existing.id),
+            }));
+        }
+
+        let mut rng = crate::derive::child_rng(&parent_secret, key_type, &name);
+        let (pk, sk) = self.envelope.generate_keypair_with_rng(&mut rng);
+        self.store_new_key(name, key_type, policy_id, Some(parent_id), Some(parent_version), &pk, &sk)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn store_new_key(
+        &self,
+        name: String,
+        key_type: KeyType,
+        policy_id: Option<PolicyId>,
+        parent_id: Option<KeyId>,
+        derived_from_version: Option<u32>,
+        pk: &citadel_envelope::PublicKey,
+        sk: &citadel_envelope::SecretKey,
+    ) -> Result<KeyId, GenerateError> {
+        let id = KeyId::generate();
+        let now = self.clock.now();
+        let policy_id = policy_id.or_else(|| self.default_policy(key_type));
 
         let version = KeyVersion {
             version: 1,
             created_at: now,
             public_key_hex: hex::encode(pk.to_bytes()),
             secret_key_hex: hex::encode(sk.to_bytes()),
+            usage_count: Some(0),
         };
 
         let meta = KeyMetadata {
+            schema_version: KEY_METADATA_SCHEMA_VERSION,
             id: id.clone(),
-            name: name.into(),
+            name,
             key_type,
             state: KeyState::Pending,
             policy_id,
             parent_id,
+            derived_from_version,
+            supersedes: None,
+            superseded_by: None,
             created_at: now,
             updated_at: now,
             activated_at: None,
             rotated_at: None,
             revoked_at: None,
+            compromised_at: None,
+            expired_at: None,
             destroyed_at: None,
             versions: vec![version],
             current_version: 1,
             usage_count: 0,
             tags: HashMap::new(),
+            allowed_contexts: Vec::new(),
         };
 
-        self.storage.put(&meta).map_err(|e| GenerateError(e))?;
-        self.audit.record(AuditEvent::key_event(
-            &id, key_type, KeyState::Pending, AuditAction::KeyGenerated,
-        ));
+        self.storage.put(&meta).map_err(GenerateError)?;
+        let event = AuditEvent::key_event(&id, key_type, KeyState::Pending, AuditAction::KeyGenerated);
+        self.record_audit(match (&meta.parent_id, derived_from_version) {
+            (Some(parent), Some(v)) => event.with_detail(format!("derived_from={}@v{}", parent, v)),
+            _ => event,
+        });
 
         Ok(id)
     }
@@ -159,76 +422,233 @@ impl Keystore {
         self.storage.list()
     }
 
+    /// Number of keys in each state, without loading every key where the
+    /// backend can avoid it. States with no keys are absent.
+    pub async fn key_counts(&self) -> Result<HashMap<KeyState, usize>, KeystoreError> {
+        self.storage.count_by_state()
+    }
+
     /// List keys in a specific state.
     pub async fn list_by_state(&self, state: KeyState) -> Result<Vec<KeyMetadata>, KeystoreError> {
         self.storage.list_by_state(state)
     }
 
+    /// List keys carrying tag `key` (optionally with a specific value).
+    pub async fn list_by_tag(&self, key: &str, value: Option<&str>) -> Result<Vec<KeyMetadata>, KeystoreError> {
+        Ok(self
+            .storage
+            .list()?
+            .into_iter()
+            .filter(|m| match (m.tags.get(key), value) {
+                (Some(v), Some(want)) => v == want,
+                (Some(_), None) => true,
+                (None, _) => false,
+            })
+            .collect())
+    }
+
+    // -----------------------------------------------------------------------
+    // Tags
+    // -----------------------------------------------------------------------
+
+    /// Set (or overwrite) a metadata tag on a key.
+    pub async fn set_tag(
+        &self,
+        id: &KeyId,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<(), KeystoreError> {
+        let key = key.into();
+        let value = value.into();
+        if key.is_empty() || key.len() > 128 {
+            return Err(KeystoreError::InvalidInput("tag key must be 1-128 characters".into()));
+        }
+        if value.len() > 1024 {
+            return Err(KeystoreError::InvalidInput("tag value must be at most 1024 characters".into()));
+        }
+
+        let _guard = self.lock_key(id).await;
+        let mut meta = self.get(id).await?;
+        meta.tags.insert(key.clone(), value);
+        meta.updated_at = self.clock.now();
+        self.storage.put(&meta)?;
+        self.record_audit(AuditEvent::key_event(
+            id, meta.key_type, meta.state, AuditAction::TagSet { key },
+        ));
+        Ok(())
+    }
+
+    /// Restrict which encryption contexts the key may be used with.
+    /// An empty list lifts the restriction.
+    pub async fn set_allowed_contexts(&self, id: &KeyId, contexts: Vec<Vec<u8>>) -> Result<(), KeystoreError> {
+        let _guard = self.lock_key(id).await;
+        let mut meta = self.get(id).await?;
+        let count = contexts.len();
+        meta.allowed_contexts = contexts;
+        meta.updated_at = self.clock.now();
+        self.storage.put(&meta)?;
+        self.record_audit(AuditEvent::key_event(
+            id, meta.key_type, meta.state, AuditAction::AllowedContextsSet { count },
+        ));
+        Ok(())
+    }
+
+    /// Remove a metadata tag, returning its previous value (if any).
+    pub async fn remove_tag(&self, id: &KeyId, key: &str) -> Result<Option<String>, KeystoreError> {
+        let _guard = self.lock_key(id).await;
+        let mut meta = self.get(id).await?;
+        let removed = meta.tags.remove(key);
+        if removed.is_some() {
+            meta.updated_at = self.clock.now();
+            self.storage.put(&meta)?;
+            self.record_audit(AuditEvent::key_event(
+                id, meta.key_type, meta.state, AuditAction::TagRemoved { key: key.to_string() },
+            ));
+        }
+        Ok(removed)
+    }
+
     // -----------------------------------------------------------------------
     // State transitions
     // -----------------------------------------------------------------------
 
     /// Activate a PENDING key.
     pub async fn activate(&self, id: &KeyId) -> Result<(), LifecycleError> {
+        let _guard = self.lock_key(id).await;
         let mut meta = self.get(id).await.map_err(LifecycleError)?;
         self.transition(&mut meta, KeyState::Active)?;
-        meta.activated_at = Some(Utc::now());
+        meta.activated_at = Some(self.clock.now());
         self.storage.put(&meta).map_err(LifecycleError)?;
-        self.audit.record(AuditEvent::key_event(
+        self.record_audit(AuditEvent::key_event(
             id, meta.key_type, meta.state, AuditAction::KeyActivated,
         ));
         Ok(())
     }
 
-    /// Rotate an ACTIVE key: generates a new version, moves old to ROTATED.
+    /// Rotate an ACTIVE key: the key moves to ROTATED (decrypt-only until it
+    /// expires) and a successor is minted under a fresh `KeyId`, ACTIVE at
+    /// version 1. The successor keeps the rotated key's `parent_id`, so its
+    /// place in the hierarchy is unchanged; the lineage is recorded through
+    /// `supersedes` / `superseded_by`. Returns the successor's ID.
     pub async fn rotate(&self, id: &KeyId) -> Result<KeyId, RotateError> {
+        let _guard = self.lock_key(id).await;
         let mut meta = self.get(id).await.map_err(RotateError)?;
 
         if meta.state != KeyState::Active {
             return Err(RotateError(KeystoreError::NotActive(id.clone())));
         }
 
-        // Generate new keypair for the new version
         let (pk, sk) = self.envelope.generate_keypair();
-        let new_version_num = meta.current_version + 1;
-        let now = Utc::now();
-
-        let new_version = KeyVersion {
-            version: new_version_num,
+        let new_id = KeyId::generate();
+        let now = self.clock.now();
+
+        let successor = KeyMetadata {
+            schema_version: KEY_METADATA_SCHEMA_VERSION,
+            id: new_id.clone(),
+            name: meta.name.clone(),
+            key_type: meta.key_type,
+            state: KeyState::Active,
+            policy_id: meta.policy_id.clone(),
+            parent_id: meta.parent_id.clone(),
+            derived_from_version: None,
+            supersedes: Some(id.clone()),
+            superseded_by: None,
             created_at: now,
-            public_key_hex: hex::encode(pk.to_bytes()),
-            secret_key_hex: hex::encode(sk.to_bytes()),
+            updated_at: now,
+            activated_at: Some(now),
+            rotated_at: None,
+            revoked_at: None,
+            compromised_at: None,
+            expired_at: None,
+            destroyed_at: None,
+            versions: vec![KeyVersion {
+                version: 1,
+                created_at: now,
+                public_key_hex: hex::encode(pk.to_bytes()),
+                secret_key_hex: hex::encode(sk.to_bytes()),
+                usage_count: Some(0),
+            }],
+            current_version: 1,
+            usage_count: 0,
+            tags: meta.tags.clone(),
+            allowed_contexts: meta.allowed_contexts.clone(),
         };
 
+        // Store the successor first so a failed write never leaves the
+        // lineage without an active key.
+        self.storage.put(&successor).map_err(RotateError)?;
+        self.record_audit(AuditEvent::key_event(
+            &new_id, successor.key_type, KeyState::Pending, AuditAction::KeyGenerated,
+        ).with_detail(format!("rotated_from={}", id)));
+        self.record_audit(AuditEvent::key_event(
+            &new_id, successor.key_type, successor.state, AuditAction::KeyActivated,
+        ));
+
         // Old key enters ROTATED state
-        meta.state = KeyState::Rotated;
+        self.transition(&mut meta, KeyState::Rotated).map_err(|e| RotateError(e.0))?;
         meta.rotated_at = Some(now);
-        meta.updated_at = now;
-        meta.versions.push(new_version);
-        meta.current_version = new_version_num;
-
+        meta.superseded_by = Some(new_id.clone());
         self.storage.put(&meta).map_err(RotateError)?;
-        self.audit.record(AuditEvent::key_event(
+        self.record_audit(AuditEvent::key_event(
             id,
             meta.key_type,
             meta.state,
-            AuditAction::KeyRotated { new_version: new_version_num },
-        ));
+            AuditAction::KeyRotated { new_version: successor.current_version },
+        ).with_detail(format!("successor={}", new_id)));
 
-        // If we want a separate active key, the caller creates a new one.
-        // For simplicity, the same KeyId keeps its history and the latest version is ACTIVE-ready.
-        // Let's re-activate with the new version.
-        meta.state = KeyState::Active;
-        meta.activated_at = Some(now);
-        meta.rotated_at = None;
-        meta.updated_at = now;
-        self.storage.put(&meta).map_err(RotateError)?;
+        Ok(new_id)
+    }
 
-        Ok(id.clone())
+    /// Version history of the key's whole rotation lineage, oldest first:
+    /// every key it was rotated from or into, each row naming the key that
+    /// holds the version. Only the ACTIVE head's current version is marked
+    /// `is_current`.
+    pub async fn key_versions(&self, id: &KeyId) -> Result<Vec<VersionInfo>, KeystoreError> {
+        let meta = self.get(id).await?;
+        Ok(self.lineage(&meta)?
+            .iter()
+            .rev()
+            .flat_map(KeyMetadata::version_history)
+            .collect())
+    }
+
+    /// Describe what `rotate` would do to a key, without changing anything:
+    /// its versions, the policy in effect at the current threat level, and
+    /// when the rotated key would expire and become destroyable. Fails the
+    /// same way `rotate` would for a key that is not ACTIVE.
+    pub async fn rotation_preview(&self, id: &KeyId) -> Result<RotationPreview, KeystoreError> {
+        let meta = self.get(id).await?;
+        if meta.state != KeyState::Active {
+            return Err(KeystoreError::NotActive(id.clone()));
+        }
+
+        let threat_level = self.current_threat_level();
+        let effective_policy = self.effective_policy_for(&meta);
+        let now = self.clock.now();
+        let verdict = effective_policy.as_ref().map(|p| policy::evaluate_at(p, &meta, now));
+        let grace_period = self.grace_period_for(&meta);
+        let grace = chrono::Duration::from_std(grace_period).unwrap_or(chrono::Duration::MAX);
+        let expires_at = now.checked_add_signed(grace).unwrap_or(chrono::DateTime::<Utc>::MAX_UTC);
+
+        Ok(RotationPreview {
+            key_id: meta.id.clone(),
+            versions: meta.versions.iter().map(|v| v.version).collect(),
+            current_version: meta.current_version,
+            successor_version: 1,
+            current_usage: meta.current_usage(),
+            threat_level,
+            effective_policy,
+            verdict,
+            grace_period,
+            rotated_at: now,
+            expires_at,
+            destroy_eligible_at: expires_at.checked_add_signed(grace).unwrap_or(chrono::DateTime::<Utc>::MAX_UTC),
+        })
     }
 
     /// Revoke a key (emergency deactivation).
     pub async fn revoke(&self, id: &KeyId, reason: impl Into<String>) -> Result<(), LifecycleError> {
+        let _guard = self.lock_key(id).await;
         let mut meta = self.get(id).await.map_err(LifecycleError)?;
         let reason = reason.into();
 
@@ -241,10 +661,10 @@ impl Keystore {
         }
 
         meta.state = KeyState::Revoked;
-        meta.revoked_at = Some(Utc::now());
-        meta.updated_at = Utc::now();
+        meta.revoked_at = Some(self.clock.now());
+        meta.updated_at = self.clock.now();
         self.storage.put(&meta).map_err(LifecycleError)?;
-        self.audit.record(AuditEvent::key_event(
+        self.record_audit(AuditEvent::key_event(
             id,
             meta.key_type,
             meta.state,
@@ -253,17 +673,55 @@ impl Keystore {
         Ok(())
     }
 
+    /// Declare an ACTIVE key compromised.
+    ///
+    /// Unlike `revoke`, this is an incident: the key moves to COMPROMISED
+    /// (no encrypt or decrypt, destroy only), its cached secret is dropped,
+    /// and a max-severity `KeyCompromise` threat event is recorded so every
+    /// policy tightens.
+    pub async fn compromise(&self, id: &KeyId, detail: impl Into<String>) -> Result<(), LifecycleError> {
+        let _guard = self.lock_key(id).await;
+        let mut meta = self.get(id).await.map_err(LifecycleError)?;
+        let detail = detail.into();
+
+        if meta.state != KeyState::Active {
+            return Err(LifecycleError(KeystoreError::InvalidTransition {
+                id: id.clone(),
+                from: meta.state,
+                to: KeyState::Compromised,
+            }));
+        }
+
+        meta.state = KeyState::Compromised;
+        meta.compromised_at = Some(self.clock.now());
+        meta.updated_at = self.clock.now();
+        self.storage.put(&meta).map_err(LifecycleError)?;
+        self.sk_cache.lock().unwrap().invalidate(id);
+
+        self.record_observed_threat(ThreatEventKind::KeyCompromise, 10.0, format!("key={}: {}", id, detail));
+        self.record_audit(AuditEvent::key_event(
+            id,
+            meta.key_type,
+            meta.state,
+            AuditAction::KeyCompromised { detail },
+        ));
+        Ok(())
+    }
+
     /// Expire a key (ROTATED past grace period, or ACTIVE past max_lifetime).
     pub async fn expire(&self, id: &KeyId) -> Result<ExpirationSource, ExpireError> {
+        let _guard = self.lock_key(id).await;
         let mut meta = self.get(id).await.map_err(ExpireError)?;
         let decision = self.check_expiration(&meta);
 
         match decision {
             ExpirationDecision::Required { reason, source } => {
+                let now = self.clock.now();
                 meta.state = KeyState::Expired;
-                meta.updated_at = Utc::now();
+                meta.expired_at = Some(now);
+                meta.updated_at = now;
                 self.storage.put(&meta).map_err(ExpireError)?;
-                self.audit.record(AuditEvent::key_event(
+                self.record_audit(AuditEvent::key_event(
                     id,
                     meta.key_type,
                     meta.state,
@@ -279,8 +737,10 @@ impl Keystore {
         }
     }
 
-    /// Destroy a key (purge material). Only EXPIRED or REVOKED keys can be destroyed.
+    /// Destroy a key (purge material). Only EXPIRED, REVOKED or COMPROMISED
+    /// keys can be destroyed.
     pub async fn destroy(&self, id: &KeyId) -> Result<(), LifecycleError> {
+        let _guard = self.lock_key(id).await;
         let mut meta = self.get(id).await.map_err(LifecycleError)?;
 
         if !meta.state.can_transition_to(KeyState::Destroyed) {
@@ -293,20 +753,220 @@ impl Keystore {
 
         // Purge key material from all versions
         for version in &mut meta.versions {
-            version.public_key_hex = String::from("DESTROYED");
-            version.secret_key_hex = String::from("DESTROYED");
+            version.public_key_hex = String::from(DESTROYED_KEY_MATERIAL);
+            version.secret_key_hex = String::from(DESTROYED_KEY_MATERIAL);
         }
 
+        self.sk_cache.lock().unwrap().invalidate(id);
+
         meta.state = KeyState::Destroyed;
-        meta.destroyed_at = Some(Utc::now());
-        meta.updated_at = Utc::now();
+        meta.destroyed_at = Some(self.clock.now());
+        meta.updated_at = self.clock.now();
         self.storage.put(&meta).map_err(LifecycleError)?;
-        self.audit.record(AuditEvent::key_event(
+        self.record_audit(AuditEvent::key_event(
             id, meta.key_type, meta.state, AuditAction::KeyDestroyed,
         ));
         Ok(())
     }
 
+    /// `activate` each key in turn. A failure is reported against its ID
+    /// and does not stop the rest.
+    pub async fn activate_many(&self, ids: &[KeyId]) -> Vec<(KeyId, Result<(), LifecycleError>)> {
+        let mut results = Vec::with_capacity(ids.len());
+        for id in ids {
+            results.push((id.clone(), self.activate(id).await));
+        }
+        results
+    }
+
+    /// `revoke` each key in turn with the same reason; see `activate_many`.
+    pub async fn revoke_many(&self, ids: &[KeyId], reason: impl Into<String>) -> Vec<(KeyId, Result<(), LifecycleError>)> {
+        let reason = reason.into();
+        let mut results = Vec::with_capacity(ids.len());
+        for id in ids {
+            results.push((id.clone(), self.revoke(id, reason.clone()).await));
+        }
+        results
+    }
+
+    /// `destroy` each key in turn; see `activate_many`.
+    pub async fn destroy_many(&self, ids: &[KeyId]) -> Vec<(KeyId, Result<(), LifecycleError>)> {
+        let mut results = Vec::with_capacity(ids.len());
+        for id in ids {
+            results.push((id.clone(), self.destroy(id).await));
+        }
+        results
+    }
+
+    // -----------------------------------------------------------------------
+    // Export / import
+    // -----------------------------------------------------------------------
+
+    /// Export a key for backup or migration.
+    ///
+    /// With a passphrase, secret material is wrapped (Argon2id + AES-256-GCM);
+    /// without one it is exported in the clear. Destroyed keys cannot be exported.
+    pub async fn export_key(&self, id: &KeyId, passphrase: Option<&str>) -> Result<KeyExport, KeystoreError> {
+        let mut meta = self.get(id).await?;
+        if meta.state == KeyState::Destroyed {
+            return Err(KeystoreError::KeyDestroyed(id.clone()));
+        }
+
+        let wrapping = match passphrase {
+            Some(p) => Some(export::wrap_secrets(&mut meta, p)?),
+            None => None,
+        };
+        self.record_audit(AuditEvent::key_event(
+            id, meta.key_type, meta.state,
+            AuditAction::KeyExported { wrapped: wrapping.is_some() },
+        ));
+
+        Ok(KeyExport {
+            format_version: export::EXPORT_FORMAT_VERSION,
+            exported_at: self.clock.now(),
+            metadata: meta,
+            wrapping,
+        })
+    }
+
+    /// Import a key produced by `export_key`, keeping its ID, state and history.
+    ///
+    /// Rejects unknown format versions, inconsistent lifecycle state,
+    /// malformed key material and IDs that already exist in this keystore.
+    pub async fn import_key(&self, export: KeyExport, passphrase: Option<&str>) -> Result<KeyId, KeystoreError> {
+        if export.format_version != export::EXPORT_FORMAT_VERSION {
+            return Err(KeystoreError::InvalidInput(format!(
+                "unsupported export format version {}", export.format_version
+            )));
+        }
+
+        let mut meta = export.metadata;
+        if let Some(wrap) = &export.wrapping {
+            let passphrase = passphrase.ok_or_else(|| {
+                KeystoreError::InvalidInput("export is passphrase-wrapped".into())
+            })?;
+            export::unwrap_secrets(&mut meta, wrap, passphrase)?;
+        }
+        meta.migrate()?;
+        export::validate_import(&meta)?;
+
+        let _guard = self.lock_key(&meta.id).await;
+        if self.storage.get(&meta.id)?.is_some() {
+            return Err(KeystoreError::DuplicateKey(meta.id));
+        }
+
+        self.storage.put(&meta)?;
+        self.record_audit(AuditEvent::key_event(
+            &meta.id, meta.key_type, meta.state, AuditAction::KeyImported,
+        ));
+        Ok(meta.id)
+    }
+
+    /// Write every key, registered policy and the threat state to `writer`
+    /// as one archive. Destroyed keys are skipped; secret material is
+    /// written in the clear.
+    pub async fn export_archive<W: std::io::Write>(&self, mut writer: W) -> Result<ArchiveSummary, KeystoreError> {
+        let mut summary = ArchiveSummary::default();
+        export::write_archive_header(&mut writer)?;
+
+        for id in self.policy_ids() {
+            if let Some(policy) = self.get_policy(&id) {
+                export::write_frame(&mut writer, export::FRAME_POLICY, &policy)?;
+                summary.policies += 1;
+            }
+        }
+
+        let mut keys = self.list_keys().await?;
+        keys.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+        for meta in keys.iter().filter(|m| m.state != KeyState::Destroyed) {
+            let key = self.export_key(&meta.id, None).await?;
+            export::write_frame(&mut writer, export::FRAME_KEY, &key)?;
+            summary.keys += 1;
+        }
+
+        let snapshot = self.threat_snapshot();
+        summary.threat_events = snapshot.events.len();
+        export::write_frame(&mut writer, export::FRAME_THREAT, &snapshot)?;
+        export::write_end_frame(&mut writer)?;
+        writer.flush().map_err(|e| KeystoreError::StorageError(format!("archive: {}", e)))?;
+
+        self.record_audit(AuditEvent::system_event(AuditAction::ArchiveExported { keys: summary.keys }));
+        Ok(summary)
+    }
+
+    /// Restore an archive written by `export_archive`.
+    ///
+    /// The whole archive is read, and every key and policy validated, before
+    /// anything is written. A key ID that already exists here or appears
+    /// twice in the archive fails the import, so a rejected archive leaves
+    /// the keystore untouched (short of a storage error partway through the
+    /// writes). Policies are registered once every key is in. Intended for a
+    /// fresh keystore; the threat state replaces the current one.
+    pub async fn import_archive<R: std::io::Read>(&self, mut reader: R) -> Result<ArchiveSummary, KeystoreError> {
+        export::read_archive_header(&mut reader)?;
+
+        let mut policies = Vec::new();
+        let mut keys = Vec::new();
+        let mut threat = None;
+        loop {
+            let (tag, body) = export::read_frame(&mut reader)?;
+            let invalid = |what: &str, e: serde_json::Error| {
+                KeystoreError::InvalidInput(format!("archive {} frame: {}", what, e))
+            };
+            match tag {
+                export::FRAME_END => break,
+                export::FRAME_POLICY => {
+                    policies.push(serde_json::from_slice::<KeyPolicy>(&body).map_err(|e| invalid("policy", e))?)
+                }
+                export::FRAME_KEY => {
+                    keys.push(serde_json::from_slice::<KeyExport>(&body).map_err(|e| invalid("key", e))?)
+                }
+                export::FRAME_THREAT => {
+                    threat = Some(serde_json::from_slice::<ThreatSnapshot>(&body).map_err(|e| invalid("threat", e))?)
+                }
+                other => {
+                    return Err(KeystoreError::InvalidInput(format!("unknown archive frame tag {}", other)));
+                }
+            }
+        }
+
+        for policy in &policies {
+            policy.validate()?;
+        }
+        let mut seen = HashSet::new();
+        for key in &keys {
+            if key.format_version != export::EXPORT_FORMAT_VERSION {
+                return Err(KeystoreError::InvalidInput(format!(
+                    "unsupported export format version {}", key.format_version
+                )));
+            }
+            if key.wrapping.is_some() {
+                return Err(KeystoreError::InvalidInput("archive key is passphrase-wrapped".into()));
+            }
+            let mut meta = key.metadata.clone();
+            meta.migrate()?;
+            export::validate_import(&meta)?;
+            if self.storage.get(&meta.id)?.is_some() || !seen.insert(meta.id.clone()) {
+                return Err(KeystoreError::DuplicateKey(meta.id));
+            }
+        }
+
+        let mut summary = ArchiveSummary { keys: keys.len(), policies: policies.len(), threat_events: 0 };
+        for key in keys {
+            self.import_key(key, None).await?;
+        }
+        for policy in policies {
+            self.register_policy(policy);
+        }
+        if let Some(snapshot) = threat {
+            summary.threat_events = snapshot.events.len();
+            self.restore_threat_snapshot(snapshot);
+        }
+
+        self.record_audit(AuditEvent::system_event(AuditAction::ArchiveImported { keys: summary.keys }));
+        Ok(summary)
+    }
+
     // -----------------------------------------------------------------------
     // Expiration checks
     // -----------------------------------------------------------------------
@@ -319,69 +979,48 @@ impl Keystore {
 
     /// Internal expiration check logic.
     fn check_expiration(&self, meta: &KeyMetadata) -> ExpirationDecision {
-        match meta.state {
-            // ROTATED keys: check grace period
-            KeyState::Rotated => {
-                if let Some(rotated_at) = meta.rotated_at {
-                    let grace = self.grace_period_for(meta);
-                    let elapsed = Utc::now() - rotated_at;
-                    let grace_chrono = chrono::Duration::from_std(grace)
-                        .unwrap_or(chrono::Duration::MAX);
-
-                    if elapsed >= grace_chrono {
-                        return ExpirationDecision::Required {
-                            reason: format!("rotated {}s ago, grace period {}s", 
-                                elapsed.num_seconds(), grace.as_secs()),
-                            source: ExpirationSource::GracePeriodExpired,
-                        };
-                    }
+        let Some((since, span)) = meta.expiry_span(self.grace_period_for(meta), self.max_lifetime_for(meta)) else {
+            return ExpirationDecision::NotNeeded;
+        };
+        let elapsed = self.clock.now() - since;
+        let span_chrono = chrono::Duration::from_std(span).unwrap_or(chrono::Duration::MAX);
+        let (source, overdue, warning) = if meta.state == KeyState::Rotated {
+            (
+                ExpirationSource::GracePeriodExpired,
+                format!("rotated {}s ago, grace period {}s", elapsed.num_seconds(), span.as_secs()),
+                "grace period expiring soon",
+            )
+        } else {
+            (
+                ExpirationSource::MaxLifetimeExceeded,
+                format!("active for {}s, max lifetime {}s", elapsed.num_seconds(), span.as_secs()),
+                "max lifetime expiring soon",
+            )
+        };
 
-                    // Warn at 90%
-                    let warn_secs = (grace.as_secs() as f64 * 0.9) as i64;
-                    if elapsed.num_seconds() >= warn_secs {
-                        let remaining = grace_chrono - elapsed;
-                        return ExpirationDecision::Warning {
-                            reason: format!("grace period expiring soon"),
-                            remaining: remaining.to_std().unwrap_or(Duration::ZERO),
-                            source: ExpirationSource::GracePeriodExpired,
-                        };
-                    }
-                }
-                ExpirationDecision::NotNeeded
-            }
+        if elapsed >= span_chrono {
+            return ExpirationDecision::Required { reason: overdue, source };
+        }
+        let warn_secs = (span.as_secs() as f64 * self.warn_fraction_for(meta)) as i64;
+        if elapsed.num_seconds() >= warn_secs {
+            return ExpirationDecision::Warning {
+                reason: warning.to_string(),
+                remaining: (span_chrono - elapsed).to_std().unwrap_or(Duration::ZERO),
+                source,
+            };
+        }
+        ExpirationDecision::NotNeeded
+    }
 
-            // ACTIVE keys: check max_lifetime
-            KeyState::Active => {
-                if let Some(max_lifetime) = self.max_lifetime_for(meta) {
-                    if let Some(activated_at) = meta.activated_at {
-                        let elapsed = Utc::now() - activated_at;
-                        let max_chrono = chrono::Duration::from_std(max_lifetime)
-                            .unwrap_or(chrono::Duration::MAX);
-
-                        if elapsed >= max_chrono {
-                            return ExpirationDecision::Required {
-                                reason: format!("active for {}s, max lifetime {}s",
-                                    elapsed.num_seconds(), max_lifetime.as_secs()),
-                                source: ExpirationSource::MaxLifetimeExceeded,
-                            };
-                        }
-
-                        // Warn at 90%
-                        let warn_secs = (max_lifetime.as_secs() as f64 * 0.9) as i64;
-                        if elapsed.num_seconds() >= warn_secs {
-                            let remaining = max_chrono - elapsed;
-                            return ExpirationDecision::Warning {
-                                reason: format!("max lifetime expiring soon"),
-                                remaining: remaining.to_std().unwrap_or(Duration::ZERO),
-                                source: ExpirationSource::MaxLifetimeExceeded,
-                            };
-                        }
-                    }
-                }
-                ExpirationDecision::NotNeeded
-            }
+    /// Register a callback invoked for every warning raised by `expire_due_keys`
+    /// (e.g. to push a Slack or PagerDuty alert). Replaces any previous hook.
+    pub fn on_expiration_warning(&self, hook: ExpirationWarningHook) {
+        *self.expiration_warning_hook.write().unwrap() = Some(hook);
+    }
 
-            _ => ExpirationDecision::NotNeeded,
+    fn notify_expiration_warning(&self, id: &KeyId, reason: &str, remaining: Duration) {
+        if let Some(hook) = self.expiration_warning_hook.read().unwrap().as_ref() {
+            hook(id, reason, remaining);
         }
     }
 
@@ -400,6 +1039,7 @@ impl Keystore {
                     }
                 }
                 ExpirationDecision::Warning { reason, remaining, .. } => {
+                    self.notify_expiration_warning(&meta.id, &reason, remaining);
                     report.warnings.push((meta.id.clone(), reason, remaining));
                 }
                 ExpirationDecision::NotNeeded => {
@@ -419,6 +1059,7 @@ impl Keystore {
                     }
                 }
                 ExpirationDecision::Warning { reason, remaining, .. } => {
+                    self.notify_expiration_warning(&meta.id, &reason, remaining);
                     report.warnings.push((meta.id.clone(), reason, remaining));
                 }
                 ExpirationDecision::NotNeeded => {
@@ -427,7 +1068,7 @@ impl Keystore {
             }
         }
 
-        self.audit.record(AuditEvent::system_event(
+        self.record_audit(AuditEvent::system_event(
             AuditAction::ExpirationCheckRun {
                 expired_count: report.expired.len(),
                 warning_count: report.warnings.len(),
@@ -437,6 +1078,210 @@ impl Keystore {
         Ok(report)
     }
 
+    /// Dry run of `expire_due_keys`: the same checks over ROTATED and
+    /// ACTIVE keys, but nothing is expired, the warning hook is not called
+    /// and no audit event is written. `expired` lists the keys that would
+    /// expire; `failed` is always empty.
+    pub async fn expiration_preview(&self) -> Result<ExpirationReport, KeystoreError> {
+        let mut report = ExpirationReport::default();
+        for state in [KeyState::Rotated, KeyState::Active] {
+            for meta in self.storage.list_by_state(state)? {
+                match self.check_expiration(&meta) {
+                    ExpirationDecision::Required { source, .. } => report.expired.push((meta.id, source)),
+                    ExpirationDecision::Warning { reason, remaining, .. } => {
+                        report.warnings.push((meta.id, reason, remaining));
+                    }
+                    ExpirationDecision::NotNeeded => report.skipped += 1,
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    // -----------------------------------------------------------------------
+    // Destruction checks
+    // -----------------------------------------------------------------------
+
+    /// Check whether a key can be destroyed without losing data that may
+    /// still need decrypting.
+    ///
+    /// Blocked unless the key is EXPIRED, REVOKED or COMPROMISED, has been in that state
+    /// for at least the (threat-adapted) grace period, and its policy's
+    /// `min_versions_retained` would still be met by the versions held by
+    /// the other retired keys in its lineage (the keys it supersedes and
+    /// that supersede it). Unrelated keys under the same policy don't count.
+    pub async fn should_destroy(&self, id: &KeyId) -> Result<DestroyDecision, KeystoreError> {
+        let meta = self.get(id).await?;
+        self.check_destruction(&meta)
+    }
+
+    /// When a retired key left service, or None if it is not retired.
+    /// Falls back to `updated_at` only for records written before the
+    /// state's timestamp was kept.
+    fn retired_at(meta: &KeyMetadata) -> Option<chrono::DateTime<Utc>> {
+        let at = match meta.state {
+            KeyState::Revoked => meta.revoked_at,
+            KeyState::Compromised => meta.compromised_at,
+            KeyState::Expired => meta.expired_at,
+            _ => return None,
+        };
+        Some(at.unwrap_or(meta.updated_at))
+    }
+
+    fn check_destruction(&self, meta: &KeyMetadata) -> Result<DestroyDecision, KeystoreError> {
+        let Some(retired_at) = Self::retired_at(meta) else {
+            return Ok(DestroyDecision::Blocked {
+                reason: format!("key is {}, only EXPIRED, REVOKED or COMPROMISED keys can be destroyed", meta.state),
+            });
+        };
+
+        // Blobs sealed shortly before retirement may still be in flight
+        let grace = self.grace_period_for(meta);
+        let elapsed = self.clock.now() - retired_at;
+        let grace_chrono = chrono::Duration::from_std(grace).unwrap_or(chrono::Duration::MAX);
+        if elapsed < grace_chrono {
+            return Ok(DestroyDecision::Blocked {
+                reason: format!("retired {}s ago, grace period {}s", elapsed.num_seconds(), grace.as_secs()),
+            });
+        }
+
+        if let Some(policy) = meta.policy_id.as_ref().and_then(|pid| self.get_policy(pid)) {
+            let retained: usize = self.lineage(meta)?
+                .iter()
+                .filter(|k| k.id != meta.id && Self::retired_at(k).is_some())
+                .map(|k| k.versions.len())
+                .sum();
+            if (retained as u32) < policy.min_versions_retained {
+                return Ok(DestroyDecision::Blocked {
+                    reason: format!(
+                        "policy {} retains at least {} retired versions per lineage, only {} others exist",
+                        policy.id, policy.min_versions_retained, retained
+                    ),
+                });
+            }
+        }
+
+        Ok(DestroyDecision::Safe {
+            reason: format!("{} for {}s, past grace period", meta.state, elapsed.num_seconds()),
+        })
+    }
+
+    /// Destroy every EXPIRED, REVOKED or COMPROMISED key that `should_destroy` reports as safe.
+    ///
+    /// Candidates are processed oldest-retired first, so the retention floor
+    /// keeps the most recently retired keys.
+    pub async fn destroy_eligible(&self) -> Result<DestructionReport, KeystoreError> {
+        let mut report = DestructionReport::default();
+
+        for meta in &self.retired_keys()? {
+            match self.check_destruction(meta)? {
+                DestroyDecision::Safe { .. } => match self.destroy(&meta.id).await {
+                    Ok(()) => report.destroyed.push(meta.id.clone()),
+                    Err(e) => report.failed.push((meta.id.clone(), e.to_string())),
+                },
+                DestroyDecision::Blocked { reason } => {
+                    report.blocked.push((meta.id.clone(), reason));
+                }
+            }
+        }
+
+        self.record_audit(AuditEvent::system_event(
+            AuditAction::DestructionSweepRun {
+                destroyed_count: report.destroyed.len(),
+                blocked_count: report.blocked.len(),
+            },
+        ));
+
+        Ok(report)
+    }
+
+    /// Destroy retired keys held longer than their policy's
+    /// `destroy_after` retention window.
+    ///
+    /// Keys whose policy sets no window are left alone. A key past its
+    /// window is still subject to `should_destroy`, so the grace period
+    /// and `min_versions_retained` hold; candidates go oldest-retired
+    /// first, as in `destroy_eligible`. Each destruction is audited as
+    /// `KeyDestroyed`, and the sweep as a whole as `DestructionSweepRun`.
+    pub async fn sweep_destroyable(&self) -> Result<DestructionReport, KeystoreError> {
+        let mut report = DestructionReport::default();
+        let now = self.clock.now();
+
+        for meta in &self.retired_keys()? {
+            let Some(window) = meta.policy_id.as_ref()
+                .and_then(|pid| self.get_policy(pid))
+                .and_then(|policy| policy.destroy_after)
+            else {
+                continue;
+            };
+            let Some(retired_at) = Self::retired_at(meta) else {
+                continue;
+            };
+            let window = chrono::Duration::from_std(window).unwrap_or(chrono::Duration::MAX);
+            let retain_until = retired_at.checked_add_signed(window).unwrap_or(chrono::DateTime::<Utc>::MAX_UTC);
+            if now < retain_until {
+                report.blocked.push((meta.id.clone(), format!("retained until {}", retain_until.to_rfc3339())));
+                continue;
+            }
+
+            match self.check_destruction(meta)? {
+                DestroyDecision::Safe { .. } => match self.destroy(&meta.id).await {
+                    Ok(()) => report.destroyed.push(meta.id.clone()),
+                    Err(e) => report.failed.push((meta.id.clone(), e.to_string())),
+                },
+                DestroyDecision::Blocked { reason } => {
+                    report.blocked.push((meta.id.clone(), reason));
+                }
+            }
+        }
+
+        self.record_audit(AuditEvent::system_event(
+            AuditAction::DestructionSweepRun {
+                destroyed_count: report.destroyed.len(),
+                blocked_count: report.blocked.len(),
+            },
+        ).with_detail("retention sweep (destroy_after)"));
+
+        Ok(report)
+    }
+
+    /// `meta` and every record linked to it through `supersedes` /
+    /// `superseded_by`, newest first. Each direction stops after
+    /// [`MAX_SUPERSEDES_HOPS`] links, at a missing record, or at a cycle.
+    fn lineage(&self, meta: &KeyMetadata) -> Result<Vec<KeyMetadata>, KeystoreError> {
+        let mut seen = HashSet::from([meta.id.clone()]);
+
+        let mut newer = Vec::new();
+        let mut next = meta.superseded_by.clone();
+        while let Some(id) = next.filter(|id| newer.len() < MAX_SUPERSEDES_HOPS && seen.insert(id.clone())) {
+            let Some(record) = self.storage.get(&id)? else { break };
+            next = record.superseded_by.clone();
+            newer.push(record);
+        }
+
+        let mut lineage: Vec<KeyMetadata> = newer.into_iter().rev().collect();
+        lineage.push(meta.clone());
+
+        let mut hops = 0;
+        let mut prev = meta.supersedes.clone();
+        while let Some(id) = prev.filter(|id| hops < MAX_SUPERSEDES_HOPS && seen.insert(id.clone())) {
+            let Some(record) = self.storage.get(&id)? else { break };
+            prev = record.supersedes.clone();
+            lineage.push(record);
+            hops += 1;
+        }
+        Ok(lineage)
+    }
+
+    /// EXPIRED, REVOKED and COMPROMISED keys, oldest-retired first.
+    fn retired_keys(&self) -> Result<Vec<KeyMetadata>, KeystoreError> {
+        let mut keys = self.storage.list_by_state(KeyState::Expired)?;
+        keys.extend(self.storage.list_by_state(KeyState::Revoked)?);
+        keys.extend(self.storage.list_by_state(KeyState::Compromised)?);
+        keys.sort_by_key(Self::retired_at);
+        Ok(keys)
+    }
+
     // -----------------------------------------------------------------------
     // Policy evaluation
     // -----------------------------------------------------------------------
@@ -445,13 +1290,13 @@ impl Keystore {
     pub async fn evaluate_policy(&self, id: &KeyId) -> Result<policy::PolicyVerdict, KeystoreError> {
         let meta = self.get(id).await?;
         let policy = match &meta.policy_id {
-            Some(pid) => self.policies.get(pid.as_str())
+            Some(pid) => self.get_policy(pid)
                 .ok_or_else(|| KeystoreError::PolicyNotFound(pid.as_str().to_string()))?,
             None => return Ok(policy::PolicyVerdict::Compliant),
         };
 
-        let verdict = policy::evaluate(policy, &meta);
-        self.audit.record(
+        let verdict = policy::evaluate_at(&policy, &meta, self.clock.now());
+        self.record_audit(
             AuditEvent::key_event(
                 id, meta.key_type, meta.state,
                 AuditAction::PolicyEvaluated { verdict: format!("{:?}", verdict) },
@@ -467,8 +1312,8 @@ impl Keystore {
 
         for meta in active {
             if let Some(pid) = &meta.policy_id {
-                if let Some(policy) = self.policies.get(pid.as_str()) {
-                    let verdict = policy::evaluate(policy, &meta);
+                if let Some(policy) = self.get_policy(pid) {
+                    let verdict = policy::evaluate_at(&policy, &meta, self.clock.now());
                     if let policy::PolicyVerdict::RotationNeeded { reason } = verdict {
                         due.push((meta.id.clone(), reason));
                     }
@@ -497,43 +1342,63 @@ impl Keystore {
         aad: &Aad,
         context: &Context,
     ) -> Result<EncryptedBlob, EncryptError> {
-        let mut meta = self.get(key_id).await
-            .map_err(|e| EncryptError(e.to_string()))?;
+        let started = Instant::now();
+        self.check_binding_len(aad, context)?;
+        let _guard = self.lock_key(key_id).await;
+        let mut meta = self.get(key_id).await?;
 
         if !meta.state.can_encrypt() {
-            return Err(EncryptError(format!("key {} is {}, cannot encrypt", key_id, meta.state)));
+            return Err(KeystoreError::NotActive(key_id.clone()).into());
+        }
+
+        if !meta.allows_context(context.as_bytes()) {
+            self.record_audit(AuditEvent::key_event(
+                key_id, meta.key_type, meta.state,
+                AuditAction::PolicyEvaluated {
+                    verdict: "BLOCKED: context not allowed".into(),
+                },
+            ));
+            return Err(KeystoreError::ContextNotAllowed(key_id.clone()).into());
         }
 
         // ── Enforcement gate: evaluate threat-adapted policy ───────────
         if let Some(adapted) = self.effective_policy_for(&meta) {
-            let verdict = policy::evaluate(&adapted, &meta);
+            let verdict = policy::evaluate_at(&adapted, &meta, self.clock.now());
             match &verdict {
                 policy::PolicyVerdict::RotationNeeded { reason } => {
-                    self.audit.record(AuditEvent::key_event(
+                    self.record_audit(AuditEvent::key_event(
                         key_id, meta.key_type, meta.state,
                         AuditAction::PolicyEvaluated {
                             verdict: format!("BLOCKED: {}", reason),
                         },
                     ));
-                    return Err(EncryptError(format!(
-                        "policy violation: {}. Rotate key before encrypting.", reason
-                    )));
+                    self.record_observed_threat(
+                        ThreatEventKind::PolicyViolation, POLICY_VIOLATION_SEVERITY,
+                        format!("key={}, blocked=rotation_needed", key_id),
+                    );
+                    return Err(KeystoreError::PolicyViolation(format!(
+                        "{}. Rotate key before encrypting.", reason
+                    )).into());
                 }
                 policy::PolicyVerdict::UsageLimitExceeded { count, limit } => {
-                    self.audit.record(AuditEvent::key_event(
+                    self.record_audit(AuditEvent::key_event(
                         key_id, meta.key_type, meta.state,
                         AuditAction::PolicyEvaluated {
                             verdict: format!("BLOCKED: usage {}/{}", count, limit),
                         },
                     ));
-                    return Err(EncryptError(format!(
-                        "policy violation: usage {}/{} exceeded. Rotate key before encrypting.",
+                    self.record_observed_threat(
+                        ThreatEventKind::PolicyViolation, POLICY_VIOLATION_SEVERITY,
+                        format!("key={}, blocked=usage_limit", key_id),
+                    );
+                    return Err(KeystoreError::PolicyViolation(format!(
+                        "usage {}/{} exceeded. Rotate key before encrypting.",
                         count, limit
-                    )));
+                    )).into());
                 }
                 policy::PolicyVerdict::Warning { reason } => {
                     // Advisory only — log but allow through
-                    self.audit.record(AuditEvent::key_event(
+                    self.record_audit(AuditEvent::key_event(
                         key_id, meta.key_type, meta.state,
                         AuditAction::PolicyEvaluated {
                             verdict: format!("WARNING: {}", reason),
@@ -546,31 +1411,30 @@ impl Keystore {
         // ── End enforcement gate ───────────────────────────────────────
 
         let version = meta.current_key_version()
-            .ok_or_else(|| EncryptError("no current version".into()))?;
+            .ok_or_else(|| KeystoreError::StorageError(format!("key {} has no current version", key_id)))?;
 
         let pk = citadel_envelope::PublicKey::from_bytes(
             &hex::decode(&version.public_key_hex)
-                .map_err(|e| EncryptError(format!("decode pk: {}", e)))?
-        ).map_err(|_| EncryptError("parse public key failed".into()))?;
+                .map_err(|e| KeystoreError::StorageError(format!("decode pk: {}", e)))?
+        ).map_err(|_| KeystoreError::EnvelopeError("parse public key failed".into()))?;
 
-        let ciphertext = self.envelope.seal(&pk, plaintext, aad, context)
-            .map_err(|e| EncryptError(format!("seal: {}", e)))?;
+        let ciphertext = self.envelope.seal(&pk, plaintext, aad, context)?;
 
-        // Increment usage count
-        meta.usage_count += 1;
-        meta.updated_at = Utc::now();
-        self.storage.put(&meta).map_err(|e| EncryptError(e.to_string()))?;
+        meta.record_usage();
+        meta.updated_at = self.clock.now();
+        self.storage.put(&meta)?;
 
-        self.audit.record(AuditEvent::key_event(
+        self.record_audit(AuditEvent::key_event(
             key_id, meta.key_type, meta.state,
             AuditAction::EncryptionPerformed { key_version: meta.current_version },
         ));
+        self.latency.lock().unwrap().0.record(started.elapsed());
 
         Ok(EncryptedBlob {
             key_id: key_id.as_str().to_string(),
             key_version: meta.current_version,
             ciphertext_hex: hex::encode(&ciphertext),
-            encrypted_at: Utc::now(),
+            encrypted_at: self.clock.now(),
         })
     }
 
@@ -581,55 +1445,249 @@ impl Keystore {
         aad: &Aad,
         context: &Context,
     ) -> Result<Vec<u8>, DecryptError> {
-        let key_id = KeyId::new(&blob.key_id);
-        let meta = self.get(&key_id).await
-            .map_err(|e| DecryptError(e.to_string()))?;
+        let meta = self.get(&KeyId::new(&blob.key_id)).await?;
+        let meta = self.record_holding_version(meta, blob.key_version).await?;
+        self.decrypt_with_meta(&meta, blob, aad, context)
+    }
 
+    /// Decrypt many blobs in one call. Each blob is resolved independently
+    /// (they may name different keys and versions) and fails on its own;
+    /// key metadata is read once per distinct key within the batch.
+    pub async fn decrypt_batch(
+        &self,
+        blobs: &[(EncryptedBlob, Aad, Context)],
+    ) -> Vec<Result<Vec<u8>, DecryptError>> {
+        let mut metas: HashMap<&str, Result<KeyMetadata, KeystoreError>> = HashMap::new();
+        let mut results = Vec::with_capacity(blobs.len());
+
+        for (blob, aad, context) in blobs {
+            if !metas.contains_key(blob.key_id.as_str()) {
+                let meta = self.get(&KeyId::new(&blob.key_id)).await;
+                metas.insert(blob.key_id.as_str(), meta);
+            }
+            let result = match &metas[blob.key_id.as_str()] {
+                Ok(meta) if meta.versions.iter().any(|v| v.version == blob.key_version) => {
+                    self.decrypt_with_meta(meta, blob, aad, context)
+                }
+                Ok(meta) => match self.record_holding_version(meta.clone(), blob.key_version).await {
+                    Ok(holder) => self.decrypt_with_meta(&holder, blob, aad, context),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e.clone().into()),
+            };
+            results.push(result);
+        }
+        results
+    }
+
+    /// Walk `supersedes` links back from `meta` to the record that holds
+    /// `version`. Returns `meta` itself when it has the version; otherwise
+    /// the first predecessor that does, up to [`MAX_SUPERSEDES_HOPS`] back.
+    async fn record_holding_version(&self, meta: KeyMetadata, version: u32) -> Result<KeyMetadata, DecryptError> {
+        let id = meta.id.clone();
+        let mut current = meta;
+        for _ in 0..=MAX_SUPERSEDES_HOPS {
+            if current.versions.iter().any(|v| v.version == version) {
+                return Ok(current);
+            }
+            let Some(prev) = current.supersedes.clone() else { break };
+            current = self.get(&prev).await?;
+        }
+        Err(KeystoreError::VersionNotFound { id, version }.into())
+    }
+
+    fn decrypt_with_meta(
+        &self,
+        meta: &KeyMetadata,
+        blob: &EncryptedBlob,
+        aad: &Aad,
+        context: &Context,
+    ) -> Result<Vec<u8>, DecryptError> {
+        let started = Instant::now();
+        let key_id = &meta.id;
         if !meta.state.can_decrypt() {
-            return Err(DecryptError(format!("key {} is {}, cannot decrypt", key_id, meta.state)));
+            return Err(KeystoreError::NotDecryptable(key_id.clone()).into());
         }
 
         // Find the version that encrypted this blob
         let version = meta.versions.iter()
             .find(|v| v.version == blob.key_version)
-            .ok_or_else(|| DecryptError(format!("version {} not found", blob.key_version)))?;
+            .ok_or_else(|| KeystoreError::VersionNotFound { id: key_id.clone(), version: blob.key_version })?;
 
-        let sk = citadel_envelope::SecretKey::from_bytes(
-            &hex::decode(&version.secret_key_hex)
-                .map_err(|e| DecryptError(format!("decode sk: {}", e)))?
-        ).map_err(|_| DecryptError("parse secret key failed".into()))?;
+        let sk = self.secret_key_for(key_id, version)?;
 
         let ciphertext = hex::decode(&blob.ciphertext_hex)
-            .map_err(|e| DecryptError(format!("decode ct: {}", e)))?;
+            .map_err(|e| KeystoreError::InvalidInput(format!("ciphertext_hex: {}", e)))?;
 
-        let plaintext = self.envelope.open(&sk, &ciphertext, aad, context)
-            .map_err(|_| {
+        let plaintext = self.envelope.open_diagnostic(&sk, &ciphertext, aad, context)
+            .map_err(|failure| {
                 // ── Measured threat event: emit DecryptionFailure ──────
                 // This is no longer modeled — the system observes real failures.
-                self.record_threat_event(ThreatEvent::new(
-                    ThreatEventKind::DecryptionFailure, 3.0,
-                ).with_detail(format!("key={}, version={}", blob.key_id, blob.key_version)));
+                // The failure stage goes only into the audit event, for
+                // operators; threat events are served to `read`-scoped
+                // clients, and the caller gets the same opaque error for
+                // every category.
+                self.record_observed_threat(ThreatEventKind::DecryptionFailure, 3.0, format!(
+                    "key={}, version={}", blob.key_id, blob.key_version
+                ));
 
-                self.audit.record(AuditEvent::key_event(
-                    &key_id, meta.key_type, meta.state,
+                self.record_audit(AuditEvent::key_event(
+                    key_id, meta.key_type, meta.state,
                     AuditAction::DecryptionFailed { key_version: blob.key_version },
-                ));
+                ).with_detail(format!("stage={}", failure)));
 
-                DecryptError("decryption failed".into())
+                DecryptError::Open(failure.into())
             })?;
 
-        self.audit.record(AuditEvent::key_event(
-            &key_id, meta.key_type, meta.state,
+        self.record_audit(AuditEvent::key_event(
+            key_id, meta.key_type, meta.state,
             AuditAction::DecryptionPerformed { key_version: blob.key_version },
         ));
+        self.latency.lock().unwrap().1.record(started.elapsed());
 
         Ok(plaintext)
     }
 
+    /// Recovery tool: open `blob` with every version still held across
+    /// the named key's lineage (the key, the keys it was rotated from and
+    /// the keys it was rotated into), newest first, ignoring
+    /// `blob.key_id`'s version and `blob.key_version`. Returns the key and
+    /// version that worked alongside the plaintext.
+    ///
+    /// Meant for blobs whose recorded key or version is wrong, e.g. a blob
+    /// relabeled with its lineage's newest key; use `decrypt` for
+    /// everything else. Keys that can no longer decrypt are skipped. Each
+    /// attempt is a full decapsulation, the result is audited once, and a
+    /// blob nothing opens counts as one decryption failure.
+    pub async fn decrypt_try_all_versions(
+        &self,
+        blob: &EncryptedBlob,
+        aad: &Aad,
+        context: &Context,
+    ) -> Result<(KeyId, u32, Vec<u8>), DecryptError> {
+        let meta = self.get(&KeyId::new(&blob.key_id)).await?;
+        let candidates: Vec<KeyMetadata> = self.lineage(&meta)?
+            .into_iter()
+            .filter(|k| k.state.can_decrypt())
+            .collect();
+        if candidates.is_empty() {
+            return Err(KeystoreError::NotDecryptable(meta.id).into());
+        }
+        let ciphertext = hex::decode(&blob.ciphertext_hex)
+            .map_err(|e| KeystoreError::InvalidInput(format!("ciphertext_hex: {}", e)))?;
+
+        let mut attempts = 0;
+        for record in &candidates {
+            for version in record.versions.iter().rev() {
+                let Ok(sk) = self.secret_key_for(&record.id, version) else { continue };
+                attempts += 1;
+                if let Ok(plaintext) = self.envelope.open(&sk, &ciphertext, aad, context) {
+                    self.record_audit(AuditEvent::key_event(
+                        &record.id, record.key_type, record.state,
+                        AuditAction::DecryptionPerformed { key_version: version.version },
+                    ).with_detail(format!(
+                        "recovered: blob named key {} version {}", blob.key_id, blob.key_version
+                    )));
+                    return Ok((record.id.clone(), version.version, plaintext));
+                }
+            }
+        }
+
+        self.record_observed_threat(ThreatEventKind::DecryptionFailure, 3.0, format!(
+            "key={}, version={}, stage=recovery", blob.key_id, blob.key_version
+        ));
+        self.record_audit(AuditEvent::key_event(
+            &meta.id, meta.key_type, meta.state,
+            AuditAction::DecryptionFailed { key_version: blob.key_version },
+        ).with_detail(format!(
+            "recovery: none of {} versions across {} keys opened the blob", attempts, candidates.len()
+        )));
+        Err(DecryptError::Open(citadel_envelope::DecryptionError::new()))
+    }
+
+    /// `encrypt`, then persist the blob in the configured blob store and
+    /// return its ID. Open it again with `open_stored`.
+    ///
+    /// Fails without encrypting if no store was set with `with_blob_store`.
+    /// If the store refuses the blob, the encryption has still been
+    /// counted against the key's usage.
+    pub async fn seal_and_store(
+        &self,
+        key_id: &KeyId,
+        plaintext: &[u8],
+        aad: &Aad,
+        context: &Context,
+    ) -> Result<BlobId, EncryptError> {
+        let store = self.blob_store()?;
+        let blob = self.encrypt(key_id, plaintext, aad, context).await?;
+        let blob_id = BlobId::generate();
+        store.put_blob(&blob_id, &blob)?;
+        Ok(blob_id)
+    }
+
+    /// Load a blob saved by `seal_and_store` and `decrypt` it.
+    pub async fn open_stored(
+        &self,
+        blob_id: &BlobId,
+        aad: &Aad,
+        context: &Context,
+    ) -> Result<Vec<u8>, DecryptError> {
+        let blob = self.blob_store()?
+            .get_blob(blob_id)?
+            .ok_or_else(|| KeystoreError::InvalidInput(format!("blob not found: {}", blob_id)))?;
+        self.decrypt(&blob, aad, context).await
+    }
+
+    /// Remove a blob saved by `seal_and_store`. Removing a missing blob
+    /// is not an error.
+    pub fn delete_stored(&self, blob_id: &BlobId) -> Result<(), KeystoreError> {
+        self.blob_store()?.delete_blob(blob_id)
+    }
+
+    fn blob_store(&self) -> Result<&Arc<dyn BlobStorageBackend>, KeystoreError> {
+        self.blob_store.as_ref().ok_or_else(|| {
+            KeystoreError::InvalidInput("no blob store configured (see Keystore::with_blob_store)".into())
+        })
+    }
+
     // -----------------------------------------------------------------------
     // Helper methods
     // -----------------------------------------------------------------------
 
+    fn check_binding_len(&self, aad: &Aad, context: &Context) -> Result<(), KeystoreError> {
+        for (name, len) in [("aad", aad.len()), ("context", context.len())] {
+            if len > self.max_binding_bytes {
+                return Err(KeystoreError::InvalidInput(format!(
+                    "{} is {} bytes (max {})", name, len, self.max_binding_bytes
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub(crate) fn cached_secret_keys(&self) -> usize {
+        self.sk_cache.lock().unwrap().len()
+    }
+
+    /// Parsed secret key for one version, from the cache when possible.
+    fn secret_key_for(&self, id: &KeyId, version: &KeyVersion) -> Result<Arc<citadel_envelope::SecretKey>, DecryptError> {
+        if let Some(sk) = self.sk_cache.lock().unwrap().get(id, version.version) {
+            return Ok(sk);
+        }
+
+        let bytes = zeroize::Zeroizing::new(
+            hex::decode(&version.secret_key_hex)
+                .map_err(|e| KeystoreError::StorageError(format!("decode sk: {}", e)))?,
+        );
+        let sk = Arc::new(
+            citadel_envelope::SecretKey::from_bytes(&bytes)
+                .map_err(|_| KeystoreError::EnvelopeError("parse secret key failed".into()))?,
+        );
+        self.sk_cache.lock().unwrap().insert(id, version.version, sk.clone());
+        Ok(sk)
+    }
+
     fn transition(&self, meta: &mut KeyMetadata, target: KeyState) -> Result<(), LifecycleError> {
         if !meta.state.can_transition_to(target) {
             return Err(LifecycleError(KeystoreError::InvalidTransition {
@@ -639,10 +1697,18 @@ impl Keystore {
             }));
         }
         meta.state = target;
-        meta.updated_at = Utc::now();
+        meta.updated_at = self.clock.now();
         Ok(())
     }
 
+    /// Record a threat event observed by the keystore itself, timestamped
+    /// by the keystore clock so window pruning sees it at the right time.
+    fn record_observed_threat(&self, kind: ThreatEventKind, severity: f64, detail: String) {
+        let mut event = ThreatEvent::new(kind, severity).with_detail(detail);
+        event.timestamp = self.clock.now();
+        self.record_observed_threat_event(event);
+    }
+
     /// Snapshot the current threat level (short lock).
     fn current_threat_level(&self) -> ThreatLevel {
         self.threat.lock().unwrap().current_level()
@@ -653,8 +1719,8 @@ impl Keystore {
         let level = self.current_threat_level();
         meta.policy_id
             .as_ref()
-            .and_then(|pid| self.policies.get(pid.as_str()))
-            .map(|base| PolicyAdapter::adapt(base, level))
+            .and_then(|pid| self.get_policy(pid))
+            .map(|base| PolicyAdapter::adapt(&base, level))
     }
 
     fn grace_period_for(&self, meta: &KeyMetadata) -> Duration {
@@ -668,18 +1734,88 @@ impl Keystore {
             .and_then(|p| p.max_lifetime)
     }
 
+    fn warn_fraction_for(&self, meta: &KeyMetadata) -> f64 {
+        self.effective_policy_for(meta)
+            .map(|p| p.warn_fraction)
+            .unwrap_or(policy::DEFAULT_WARN_FRACTION)
+    }
+
     // -----------------------------------------------------------------------
     // Threat assessment API
     // -----------------------------------------------------------------------
 
-    /// Record a threat event and recompute the threat level.
-    pub fn record_threat_event(&self, event: ThreatEvent) {
-        self.threat.lock().unwrap().record_event(event);
+    /// Ingest an externally reported threat event and recompute the threat
+    /// level. Returns `false` if the assessor's ingestion limit refused it.
+    pub fn record_threat_event(&self, event: ThreatEvent) -> bool {
+        self.with_threat(|t| t.record_event(event))
+    }
+
+    /// Ingest multiple externally reported threat events. Returns how many
+    /// the ingestion limit refused.
+    pub fn record_threat_events(&self, events: Vec<ThreatEvent>) -> usize {
+        self.with_threat(|t| t.record_events(events))
+    }
+
+    /// Record a threat event the caller observed itself (the API's auth
+    /// failures and rate-limit rejections), bypassing the ingestion limit
+    /// and dedup. See [`ThreatAssessor::record_observed`].
+    pub fn record_observed_threat_event(&self, event: ThreatEvent) {
+        self.with_threat(|t| t.record_observed(event))
+    }
+
+    /// Call `hook` with `(old, new)` whenever the threat level changes,
+    /// replacing any previous hook. It runs after the threat lock is
+    /// released, on the thread whose call moved the level, so it may call
+    /// back into the keystore.
+    pub fn on_threat_change(&self, hook: LevelChangeHook) {
+        self.threat.lock().unwrap().on_level_change(hook);
+    }
+
+    /// Append every accepted threat event to `log` from now on, replacing
+    /// any previous log. See [`ThreatAssessor::with_event_log`].
+    pub fn set_threat_event_log(&self, log: ThreatEventLog) {
+        self.threat.lock().unwrap().set_event_log(log);
+    }
+
+    /// Run `f` on the threat assessor, then fire any level changes it
+    /// caused once the lock is dropped.
+    fn with_threat<R>(&self, f: impl FnOnce(&mut ThreatAssessor) -> R) -> R {
+        let (result, changes) = {
+            let mut threat = self.threat.lock().unwrap();
+            let result = f(&mut threat);
+            (result, threat.take_level_changes())
+        };
+        changes.fire();
+        result
+    }
+
+    /// Threat events refused by the ingestion limit since startup.
+    pub fn dropped_threat_events(&self) -> u64 {
+        self.threat.lock().unwrap().dropped_events()
+    }
+
+    /// Current threat assessor configuration.
+    pub fn threat_config(&self) -> ThreatConfig {
+        self.threat.lock().unwrap().config().clone()
+    }
+
+    /// Replace the threat assessor configuration at runtime (see
+    /// [`ThreatConfig::validate`]). Not persisted: a restart goes back to
+    /// the configuration the keystore was built with.
+    pub fn set_threat_config(&self, config: ThreatConfig) -> Result<(), KeystoreError> {
+        let detail = format!(
+            "window={}s decay_rate={} thresholds={:?} hysteresis={}",
+            config.window.as_secs(), config.decay_rate, config.thresholds, config.hysteresis
+        );
+        self.with_threat(|t| t.set_config(config))?;
+        self.record_audit(AuditEvent::system_event(AuditAction::ThreatConfigUpdated).with_detail(detail));
+        Ok(())
     }
 
-    /// Record multiple threat events.
-    pub fn record_threat_events(&self, events: Vec<ThreatEvent>) {
-        self.threat.lock().unwrap().record_events(events);
+    /// Re-apply decay to the threat score and update the level. Drive this
+    /// from a timer so the level can fall when events stop arriving.
+    pub fn recompute_threat(&self) {
+        self.with_threat(ThreatAssessor::tick);
     }
 
     /// Get the current threat level.
@@ -692,6 +1828,24 @@ impl Keystore {
         self.threat.lock().unwrap().raw_score()
     }
 
+    /// p50 / p99 / max latency of successful encrypts and decrypts since
+    /// startup. A rising decrypt p99 as the key count grows usually means
+    /// the secret-key cache is too small for the working set.
+    pub fn operation_metrics(&self) -> OperationMetrics {
+        let latency = self.latency.lock().unwrap();
+        let (encrypt, decrypt) = &*latency;
+        OperationMetrics {
+            encrypt_count: encrypt.count(),
+            encrypt_p50: encrypt.percentile(0.50),
+            encrypt_p99: encrypt.percentile(0.99),
+            encrypt_max: encrypt.max(),
+            decrypt_count: decrypt.count(),
+            decrypt_p50: decrypt.percentile(0.50),
+            decrypt_p99: decrypt.percentile(0.99),
+            decrypt_max: decrypt.max(),
+        }
+    }
+
     /// Get comprehensive security metrics for the dashboard.
     pub async fn security_metrics(&self) -> Result<SecurityMetrics, KeystoreError> {
         let level = self.current_threat_level();
@@ -701,9 +1855,9 @@ impl Keystore {
 
         for meta in &all_keys {
             if let Some(pid) = &meta.policy_id {
-                if let Some(base_policy) = self.policies.get(pid.as_str()) {
-                    let adapted = PolicyAdapter::adapt(base_policy, level);
-                    let verdict = policy::evaluate(&adapted, meta);
+                if let Some(base_policy) = self.get_policy(pid) {
+                    let adapted = PolicyAdapter::adapt(&base_policy, level);
+                    let verdict = policy::evaluate_at(&adapted, meta, self.clock.now());
                     if matches!(verdict, policy::PolicyVerdict::Compliant | policy::PolicyVerdict::Warning { .. }) {
                         compliant += 1;
                     }
@@ -723,12 +1877,39 @@ impl Keystore {
         self.threat.lock().unwrap().level_history().to_vec()
     }
 
+    /// The last `n` events the threat assessor has retained, newest first.
+    pub fn recent_threat_events(&self, n: usize) -> Vec<ThreatEvent> {
+        self.threat.lock().unwrap().recent_events(n).into_iter().cloned().collect()
+    }
+
+    /// Snapshot threat assessor state (for persisting across restarts).
+    pub fn threat_snapshot(&self) -> ThreatSnapshot {
+        self.threat.lock().unwrap().snapshot()
+    }
+
+    /// Restore threat assessor state from a snapshot.
+    pub fn restore_threat_snapshot(&self, snapshot: ThreatSnapshot) {
+        self.with_threat(|t| t.restore(snapshot));
+    }
+
+    /// Flush the audit sink (call before shutdown).
+    pub fn flush_audit(&self) {
+        self.audit.flush();
+    }
+
     /// Get adaptation summary for a specific policy at the current threat level.
     pub fn policy_adaptation_summary(&self, policy_id: &PolicyId) -> Option<crate::threat::AdaptationSummary> {
         let level = self.current_threat_level();
-        self.policies
-            .get(policy_id.as_str())
-            .map(|base| PolicyAdapter::summarize(base, level))
+        self.get_policy(policy_id)
+            .map(|base| PolicyAdapter::summarize(&base, level))
+    }
+
+    /// How the policy that applies to this key is adapted right now:
+    /// `policy_adaptation_summary` for the key's own policy. `None` if the
+    /// key has no policy, or names one that is not registered.
+    pub async fn effective_policy_summary(&self, id: &KeyId) -> Result<Option<crate::threat::AdaptationSummary>, KeystoreError> {
+        let meta = self.get(id).await?;
+        Ok(meta.policy_id.as_ref().and_then(|pid| self.policy_adaptation_summary(pid)))
     }
 
     /// Evaluate policy using threat-adapted parameters.
@@ -737,15 +1918,15 @@ impl Keystore {
         let meta = self.get(id).await?;
         let adapted_policy = match &meta.policy_id {
             Some(pid) => {
-                let base = self.policies.get(pid.as_str())
+                let base = self.get_policy(pid)
                     .ok_or_else(|| KeystoreError::PolicyNotFound(pid.as_str().to_string()))?;
-                PolicyAdapter::adapt(base, level)
+                PolicyAdapter::adapt(&base, level)
             }
             None => return Ok(policy::PolicyVerdict::Compliant),
         };
 
-        let verdict = policy::evaluate(&adapted_policy, &meta);
-        self.audit.record(
+        let verdict = policy::evaluate_at(&adapted_policy, &meta, self.clock.now());
+        self.record_audit(
             AuditEvent::key_event(
                 id, meta.key_type, meta.state,
                 AuditAction::PolicyEvaluated {
@@ -764,9 +1945,9 @@ impl Keystore {
 
         for meta in active {
             if let Some(pid) = &meta.policy_id {
-                if let Some(base_policy) = self.policies.get(pid.as_str()) {
-                    let adapted = PolicyAdapter::adapt(base_policy, level);
-                    let verdict = policy::evaluate(&adapted, &meta);
+                if let Some(base_policy) = self.get_policy(pid) {
+                    let adapted = PolicyAdapter::adapt(&base_policy, level);
+                    let verdict = policy::evaluate_at(&adapted, &meta, self.clock.now());
                     if let policy::PolicyVerdict::RotationNeeded { reason } = verdict {
                         due.push((meta.id.clone(), format!("{} [threat:{}]", reason, level.label())));
                     }
@@ -776,3 +1957,172 @@ impl Keystore {
         Ok(due)
     }
 }
+
+// ---------------------------------------------------------------------------
+// Actor-scoped handle
+// ---------------------------------------------------------------------------
+
+/// A borrowed keystore that attributes the audit events of every mutating
+/// operation to `actor`. Created by [`Keystore::with_actor`].
+pub struct ActingKeystore<'a> {
+    keystore: &'a Keystore,
+    actor: String,
+}
+
+impl ActingKeystore<'_> {
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+
+    pub fn register_policy(&self, policy: KeyPolicy) {
+        AUDIT_ACTOR.sync_scope(self.actor.clone(), || self.keystore.register_policy(policy))
+    }
+
+    pub fn update_policy(&self, policy: KeyPolicy) -> Result<(), KeystoreError> {
+        AUDIT_ACTOR.sync_scope(self.actor.clone(), || self.keystore.update_policy(policy))
+    }
+
+    pub fn set_default_policy(&self, key_type: KeyType, policy_id: PolicyId) -> Result<(), KeystoreError> {
+        AUDIT_ACTOR.sync_scope(self.actor.clone(), || self.keystore.set_default_policy(key_type, policy_id))
+    }
+
+    pub fn clear_default_policy(&self, key_type: KeyType) {
+        AUDIT_ACTOR.sync_scope(self.actor.clone(), || self.keystore.clear_default_policy(key_type))
+    }
+
+    pub fn set_threat_config(&self, config: ThreatConfig) -> Result<(), KeystoreError> {
+        AUDIT_ACTOR.sync_scope(self.actor.clone(), || self.keystore.set_threat_config(config))
+    }
+
+    pub async fn generate(
+        &self,
+        name: impl Into<String>,
+        key_type: KeyType,
+        policy_id: Option<PolicyId>,
+        parent_id: Option<KeyId>,
+    ) -> Result<KeyId, GenerateError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.generate(name, key_type, policy_id, parent_id)).await
+    }
+
+    pub async fn generate_derived(
+        &self,
+        name: impl Into<String>,
+        key_type: KeyType,
+        policy_id: Option<PolicyId>,
+        parent_id: KeyId,
+    ) -> Result<KeyId, GenerateError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.generate_derived(name, key_type, policy_id, parent_id)).await
+    }
+
+    pub async fn set_tag(&self, id: &KeyId, key: impl Into<String>, value: impl Into<String>) -> Result<(), KeystoreError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.set_tag(id, key, value)).await
+    }
+
+    pub async fn set_allowed_contexts(&self, id: &KeyId, contexts: Vec<Vec<u8>>) -> Result<(), KeystoreError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.set_allowed_contexts(id, contexts)).await
+    }
+
+    pub async fn remove_tag(&self, id: &KeyId, key: &str) -> Result<Option<String>, KeystoreError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.remove_tag(id, key)).await
+    }
+
+    pub async fn activate(&self, id: &KeyId) -> Result<(), LifecycleError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.activate(id)).await
+    }
+
+    pub async fn rotate(&self, id: &KeyId) -> Result<KeyId, RotateError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.rotate(id)).await
+    }
+
+    pub async fn revoke(&self, id: &KeyId, reason: impl Into<String>) -> Result<(), LifecycleError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.revoke(id, reason)).await
+    }
+
+    pub async fn compromise(&self, id: &KeyId, detail: impl Into<String>) -> Result<(), LifecycleError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.compromise(id, detail)).await
+    }
+
+    pub async fn expire(&self, id: &KeyId) -> Result<ExpirationSource, ExpireError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.expire(id)).await
+    }
+
+    pub async fn destroy(&self, id: &KeyId) -> Result<(), LifecycleError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.destroy(id)).await
+    }
+
+    pub async fn activate_many(&self, ids: &[KeyId]) -> Vec<(KeyId, Result<(), LifecycleError>)> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.activate_many(ids)).await
+    }
+
+    pub async fn revoke_many(&self, ids: &[KeyId], reason: impl Into<String>) -> Vec<(KeyId, Result<(), LifecycleError>)> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.revoke_many(ids, reason)).await
+    }
+
+    pub async fn destroy_many(&self, ids: &[KeyId]) -> Vec<(KeyId, Result<(), LifecycleError>)> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.destroy_many(ids)).await
+    }
+
+    pub async fn export_key(&self, id: &KeyId, passphrase: Option<&str>) -> Result<KeyExport, KeystoreError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.export_key(id, passphrase)).await
+    }
+
+    pub async fn import_key(&self, export: KeyExport, passphrase: Option<&str>) -> Result<KeyId, KeystoreError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.import_key(export, passphrase)).await
+    }
+
+    pub async fn export_archive<W: std::io::Write>(&self, writer: W) -> Result<ArchiveSummary, KeystoreError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.export_archive(writer)).await
+    }
+
+    pub async fn import_archive<R: std::io::Read>(&self, reader: R) -> Result<ArchiveSummary, KeystoreError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.import_archive(reader)).await
+    }
+
+    pub async fn expire_due_keys(&self) -> Result<ExpirationReport, KeystoreError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.expire_due_keys()).await
+    }
+
+    pub async fn destroy_eligible(&self) -> Result<DestructionReport, KeystoreError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.destroy_eligible()).await
+    }
+
+    pub async fn sweep_destroyable(&self) -> Result<DestructionReport, KeystoreError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.sweep_destroyable()).await
+    }
+
+    pub async fn encrypt(
+        &self,
+        key_id: &KeyId,
+        plaintext: &[u8],
+        aad: &Aad,
+        context: &Context,
+    ) -> Result<EncryptedBlob, EncryptError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.encrypt(key_id, plaintext, aad, context)).await
+    }
+
+    pub async fn decrypt(&self, blob: &EncryptedBlob, aad: &Aad, context: &Context) -> Result<Vec<u8>, DecryptError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.decrypt(blob, aad, context)).await
+    }
+
+    pub async fn decrypt_batch(&self, blobs: &[(EncryptedBlob, Aad, Context)]) -> Vec<Result<Vec<u8>, DecryptError>> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.decrypt_batch(blobs)).await
+    }
+
+    pub async fn decrypt_try_all_versions(&self, blob: &EncryptedBlob, aad: &Aad, context: &Context) -> Result<(KeyId, u32, Vec<u8>), DecryptError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.decrypt_try_all_versions(blob, aad, context)).await
+    }
+
+    pub async fn seal_and_store(
+        &self,
+        key_id: &KeyId,
+        plaintext: &[u8],
+        aad: &Aad,
+        context: &Context,
+    ) -> Result<BlobId, EncryptError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.seal_and_store(key_id, plaintext, aad, context)).await
+    }
+
+    pub async fn open_stored(&self, blob_id: &BlobId, aad: &Aad, context: &Context) -> Result<Vec<u8>, DecryptError> {
+        AUDIT_ACTOR.scope(self.actor.clone(), self.keystore.open_stored(blob_id, aad, context)).await
+    }
+}
@@ -17,7 +17,7 @@
 //! # tokio_test::block_on(async {
 //! let storage = Arc::new(InMemoryBackend::new());
 //! let audit = Arc::new(InMemoryAuditSink::new());
-//! let mut ks = Keystore::new(storage, audit);
+//! let ks = Keystore::new(storage, audit);
 //!
 //! // Register a policy
 //! ks.register_policy(KeyPolicy::default_dek());
@@ -38,27 +38,50 @@
 //! ```
 
 pub mod audit;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod cache;
+pub mod clock;
+mod derive;
 pub mod error;
+pub mod export;
 pub mod keystore;
+pub mod metrics;
+#[cfg(feature = "otlp")]
+pub mod otlp;
 pub mod policy;
 pub mod storage;
 pub mod threat;
 pub mod types;
 
 // Re-export main types for convenience
-pub use audit::{AuditEvent, AuditSinkSync, FileAuditSink, InMemoryAuditSink, IntegrityChainSink, TracingAuditSink};
+pub use audit::{
+    genesis_hash, verify_chain, AuditEvent, AuditSinkSync, ChainBreak, CompositeAuditSink, FileAuditSink,
+    InMemoryAuditSink, IntegrityChainSink, RingAuditSink, TracingAuditSink,
+};
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingKeystore;
+pub use cache::DEFAULT_SECRET_KEY_CACHE_CAPACITY;
+pub use clock::{Clock, MockClock, SystemClock};
 pub use error::{
     DecryptError, DestroyDecision, EncryptError, ExpirationDecision, ExpirationReport,
-    ExpirationSource, ExpireError, GenerateError, KeystoreError, LifecycleError, RotateError,
+    DestructionReport, ExpirationSource, ExpireError, GenerateError, KeyIdError, KeystoreError, LifecycleError, RotateError,
+    RotationPreview,
 };
-pub use keystore::{EncryptedBlob, Keystore};
-pub use policy::{KeyPolicy, PolicyVerdict, RotationTrigger};
-pub use storage::{FileBackend, InMemoryBackend, StorageBackend};
+pub use export::{ArchiveSummary, KeyExport, PassphraseWrap, ARCHIVE_FORMAT_VERSION, EXPORT_FORMAT_VERSION};
+pub use keystore::{ActingKeystore, EncryptedBlob, ExpirationWarningHook, Keystore, DEFAULT_MAX_BINDING_BYTES};
+pub use metrics::OperationMetrics;
+#[cfg(feature = "otlp")]
+pub use otlp::{OtlpAuditSink, OtlpConfig};
+pub use policy::{KeyPolicy, PolicyVerdict, RotationTrigger, DEFAULT_WARN_FRACTION};
+pub use storage::{BlobStorageBackend, FileBackend, FileBlobBackend, InMemoryBackend, InMemoryBlobBackend, StorageBackend};
 pub use threat::{
-    AdaptationSummary, PolicyAdapter, SecurityMetrics, ThreatAssessor, ThreatConfig,
-    ThreatEvent, ThreatEventKind, ThreatLevel,
+    AdaptationSummary, LevelChangeHook, LevelChanges, PolicyAdapter, SecurityMetrics, ThreatAssessor,
+    ThreatConfig, ThreatEvent, ThreatEventKind, ThreatEventLog, ThreatLevel, ThreatLogEntry, ThreatSnapshot,
+};
+pub use types::{
+    BlobId, KeyId, KeyMetadata, KeyState, KeyType, KeyVersion, PolicyId, VersionInfo, KEY_METADATA_SCHEMA_VERSION, MAX_KEY_ID_LEN,
 };
-pub use types::{KeyId, KeyMetadata, KeyState, KeyType, KeyVersion, PolicyId};
 
 // ---------------------------------------------------------------------------
 // Tests
@@ -84,6 +107,24 @@ mod tests {
         (ks, audit)
     }
 
+    /// Rotate a fresh `default-dek` key `retired` times and expire every
+    /// rotated key, a minute apart. Returns the expired keys, oldest first.
+    async fn expired_lineage(ks: &Keystore, clock: &MockClock, name: &str, retired: usize) -> Vec<KeyId> {
+        let mut id = ks.generate(name, KeyType::DataEncrypting, Some(PolicyId::new("default-dek")), None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+        let mut ids = Vec::new();
+        for _ in 0..retired {
+            let next = ks.rotate(&id).await.unwrap();
+            ids.push(std::mem::replace(&mut id, next));
+        }
+        clock.advance(Duration::from_secs(2 * 86400));
+        for id in &ids {
+            ks.expire(id).await.unwrap();
+            clock.advance(Duration::from_secs(60));
+        }
+        ids
+    }
+
     // === Key Generation ===
 
     #[tokio::test]
@@ -119,6 +160,52 @@ mod tests {
         assert_eq!(meta.parent_id, Some(parent));
     }
 
+    #[test]
+    fn test_key_id_from_public_key_is_stable() {
+        let citadel = citadel_envelope::Citadel::new();
+        let (pk, _) = citadel.generate_keypair();
+        let (other, _) = citadel.generate_keypair();
+
+        let id = KeyId::from_public_key(&pk);
+        assert_eq!(id, KeyId::from_public_key(&pk));
+        assert_ne!(id, KeyId::from_public_key(&other));
+        assert_eq!(id.as_str().len(), KeyId::generate().as_str().len());
+    }
+
+    #[test]
+    fn test_key_id_ct_eq() {
+        let id = KeyId::generate();
+        assert!(id.ct_eq(&id.clone()));
+        assert!(!id.ct_eq(&KeyId::generate()));
+        assert!(!id.ct_eq(&KeyId::new(&id.as_str()[..8])));
+    }
+
+    #[test]
+    fn test_key_id_parse_rejects_traversal() {
+        let generated = KeyId::generate();
+        assert_eq!(KeyId::parse(generated.as_str()), Ok(generated));
+        assert!(KeyId::parse("demo_key-1").is_ok());
+
+        assert_eq!(KeyId::parse("../../etc/passwd"), Err(KeyIdError::InvalidChar('.')));
+        assert_eq!(KeyId::parse("a/b"), Err(KeyIdError::InvalidChar('/')));
+        assert_eq!(KeyId::parse("Key"), Err(KeyIdError::InvalidChar('K')));
+        assert_eq!(KeyId::parse(""), Err(KeyIdError::Empty));
+        assert_eq!(KeyId::parse(&"a".repeat(MAX_KEY_ID_LEN + 1)), Err(KeyIdError::TooLong(MAX_KEY_ID_LEN + 1)));
+    }
+
+    #[tokio::test]
+    async fn test_file_backend_refuses_traversal_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys = dir.path().join("keys");
+        let storage = FileBackend::new(&keys).unwrap();
+        std::fs::write(dir.path().join("outside.json"), "{}").unwrap();
+
+        let escape = KeyId::new("../outside");
+        assert!(matches!(storage.get(&escape), Err(KeystoreError::InvalidInput(_))));
+        assert!(matches!(storage.delete(&escape), Err(KeystoreError::InvalidInput(_))));
+        assert!(dir.path().join("outside.json").exists());
+    }
+
     // === Activation ===
 
     #[tokio::test]
@@ -148,28 +235,152 @@ mod tests {
         let ks = test_keystore();
         let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
         ks.activate(&id).await.unwrap();
-        ks.rotate(&id).await.unwrap();
+        let new_id = ks.rotate(&id).await.unwrap();
+        assert_ne!(new_id, id);
+
+        let old = ks.get(&id).await.unwrap();
+        assert_eq!(old.state, KeyState::Rotated);
+        assert!(old.rotated_at.is_some());
+        assert_eq!(old.versions.len(), 1);
+
+        let new = ks.get(&new_id).await.unwrap();
+        assert_eq!(new.state, KeyState::Active);
+        assert_eq!(new.parent_id, None);
+        assert_eq!(new.supersedes, Some(id));
+        assert_eq!(new.current_version, 1);
+        assert_eq!(new.versions.len(), 1);
+    }
 
-        let meta = ks.get(&id).await.unwrap();
-        assert_eq!(meta.state, KeyState::Active); // Re-activated with new version
-        assert_eq!(meta.current_version, 2);
-        assert_eq!(meta.versions.len(), 2);
+    #[tokio::test]
+    async fn test_decrypt_across_rotated_key_ids() {
+        let storage = Arc::new(InMemoryBackend::new());
+        let ks = Keystore::new(storage.clone(), Arc::new(InMemoryAuditSink::new()));
+        let (aad, ctx) = (Aad::raw(b"aad"), Context::raw(b"ctx"));
+
+        let k1 = ks.generate("lineage", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&k1).await.unwrap();
+        // A record carrying a later version number, as an imported legacy key would.
+        let mut meta = ks.get(&k1).await.unwrap();
+        meta.versions[0].version = 2;
+        meta.current_version = 2;
+        storage.put(&meta).unwrap();
+
+        let b1 = ks.encrypt(&k1, b"one", &aad, &ctx).await.unwrap();
+        let k2 = ks.rotate(&k1).await.unwrap();
+        let b2 = ks.encrypt(&k2, b"two", &aad, &ctx).await.unwrap();
+        let k3 = ks.rotate(&k2).await.unwrap();
+        let b3 = ks.encrypt(&k3, b"three", &aad, &ctx).await.unwrap();
+
+        assert_eq!(ks.get(&k1).await.unwrap().superseded_by, Some(k2.clone()));
+        assert_eq!(ks.get(&k3).await.unwrap().supersedes, Some(k2.clone()));
+
+        assert_eq!(ks.decrypt(&b1, &aad, &ctx).await.unwrap(), b"one");
+        assert_eq!(ks.decrypt(&b2, &aad, &ctx).await.unwrap(), b"two");
+        assert_eq!(ks.decrypt(&b3, &aad, &ctx).await.unwrap(), b"three");
+
+        // A blob naming the newest key but a version only its ancestor holds
+        // is resolved by walking the chain back.
+        let mut relabeled = b1.clone();
+        relabeled.key_id = k3.to_string();
+        assert_eq!(ks.decrypt(&relabeled, &aad, &ctx).await.unwrap(), b"one");
+        let batch = ks.decrypt_batch(&[(relabeled.clone(), aad.clone(), ctx.clone())]).await;
+        assert_eq!(batch[0].as_deref().unwrap(), b"one");
+
+        relabeled.key_version = 9;
+        assert!(ks.decrypt(&relabeled, &aad, &ctx).await.is_err());
     }
 
     #[tokio::test]
-    async fn test_rotate_preserves_old_versions() {
+    async fn test_rotate_chains_successors() {
         let ks = test_keystore();
-        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        let first = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&first).await.unwrap();
+        let second = ks.rotate(&first).await.unwrap();
+        let third = ks.rotate(&second).await.unwrap();
+
+        // A rotated key cannot be rotated again.
+        assert!(ks.rotate(&first).await.is_err());
+
+        assert_eq!(ks.get(&second).await.unwrap().state, KeyState::Rotated);
+        assert_eq!(ks.get(&second).await.unwrap().supersedes, Some(first));
+        assert_eq!(ks.get(&third).await.unwrap().supersedes, Some(second));
+        assert_eq!(ks.list_by_state(KeyState::Active).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_keeps_hierarchy_parent() {
+        let storage = Arc::new(InMemoryBackend::new());
+        let ks = Keystore::new(storage.clone(), Arc::new(InMemoryAuditSink::new()));
+        let kek = ks.generate("kek", KeyType::KeyEncrypting, None, None).await.unwrap();
+        let dek = ks.generate("dek", KeyType::DataEncrypting, None, Some(kek.clone())).await.unwrap();
+        ks.activate(&dek).await.unwrap();
+        let successor = ks.rotate(&dek).await.unwrap();
+
+        assert_eq!(ks.get(&successor).await.unwrap().parent_id, Some(kek.clone()));
+        let mut children: Vec<KeyId> = storage.list_by_parent(&kek).unwrap().into_iter().map(|k| k.id).collect();
+        children.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        let mut expected = vec![dek, successor];
+        expected.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        assert_eq!(children, expected);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_resets_usage_count() {
+        let ks = test_keystore();
+        ks.register_policy(KeyPolicy {
+            id: PolicyId::new("two-uses"),
+            max_usage_count: Some(2),
+            ..KeyPolicy::default_dek()
+        });
+        let id = ks.generate("key", KeyType::DataEncrypting, Some(PolicyId::new("two-uses")), None).await.unwrap();
         ks.activate(&id).await.unwrap();
-        ks.rotate(&id).await.unwrap();
-        ks.rotate(&id).await.unwrap();
 
-        let meta = ks.get(&id).await.unwrap();
-        assert_eq!(meta.current_version, 3);
-        assert_eq!(meta.versions.len(), 3);
-        assert_eq!(meta.versions[0].version, 1);
-        assert_eq!(meta.versions[1].version, 2);
-        assert_eq!(meta.versions[2].version, 3);
+        let aad = Aad::raw(b"aad");
+        let ctx = Context::raw(b"ctx");
+        ks.encrypt(&id, b"1", &aad, &ctx).await.unwrap();
+        ks.encrypt(&id, b"2", &aad, &ctx).await.unwrap();
+        assert!(ks.evaluate_policy(&id).await.unwrap().needs_rotation());
+
+        let successor = ks.rotate(&id).await.unwrap();
+        assert_eq!(ks.get(&successor).await.unwrap().usage_count, 0);
+        assert_eq!(ks.get(&id).await.unwrap().usage_count, 2);
+        assert!(matches!(ks.evaluate_policy(&successor).await.unwrap(), PolicyVerdict::Compliant));
+        ks.encrypt(&successor, b"3", &aad, &ctx).await.unwrap();
+        assert_eq!(ks.get(&successor).await.unwrap().usage_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_usage_limit_counts_current_version() {
+        let ks = test_keystore();
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+        ks.encrypt(&id, b"x", &Aad::raw(b"aad"), &Context::raw(b"ctx")).await.unwrap();
+        let mut meta = ks.get(&id).await.unwrap();
+        assert_eq!(meta.current_key_version().unwrap().usage_count, Some(1));
+
+        // A second version with its own count: only it is checked against the limit.
+        let mut v2 = meta.versions[0].clone();
+        v2.version = 2;
+        v2.usage_count = Some(0);
+        meta.versions[0].usage_count = Some(9);
+        meta.versions.push(v2);
+        meta.current_version = 2;
+        meta.usage_count = 9;
+        let limit = KeyPolicy { max_usage_count: Some(5), ..KeyPolicy::default_dek() };
+        assert_eq!(meta.current_usage(), 0);
+        assert!(matches!(policy::evaluate(&limit, &meta), PolicyVerdict::Compliant));
+
+        // Metadata written before per-version counts falls back to the key total.
+        let mut json = serde_json::to_value(&meta).unwrap();
+        for v in json["versions"].as_array_mut().unwrap() {
+            v.as_object_mut().unwrap().remove("usage_count");
+        }
+        let mut legacy: KeyMetadata = serde_json::from_value(json).unwrap();
+        assert_eq!(legacy.current_usage(), 9);
+        assert!(policy::evaluate(&limit, &legacy).needs_rotation());
+        legacy.record_usage();
+        assert_eq!(legacy.current_key_version().unwrap().usage_count, Some(10));
+        assert_eq!(legacy.usage_count, 10);
     }
 
     #[tokio::test]
@@ -181,6 +392,73 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_rotate_is_not_clobbered_by_concurrent_encrypt() {
+        let ks = Arc::new(test_keystore());
+
+        for _ in 0..20 {
+            let id = ks.generate("contended", KeyType::DataEncrypting, None, None).await.unwrap();
+            ks.activate(&id).await.unwrap();
+
+            let encrypts: Vec<_> = (0..8)
+                .map(|_| {
+                    let (ks, id) = (ks.clone(), id.clone());
+                    tokio::spawn(async move {
+                        ks.encrypt(&id, b"data", &Aad::raw(b"a"), &Context::raw(b"c")).await.is_ok()
+                    })
+                })
+                .collect();
+            let rotate = {
+                let (ks, id) = (ks.clone(), id.clone());
+                tokio::spawn(async move { ks.rotate(&id).await })
+            };
+            let (successor, succeeded) = tokio::join!(rotate, async {
+                let mut n = 0;
+                for handle in encrypts {
+                    n += u64::from(handle.await.unwrap());
+                }
+                n
+            });
+            let successor = successor.unwrap().unwrap();
+
+            // Every encrypt either ran before the rotation (and is counted)
+            // or saw the ROTATED key and was refused; none reverted it.
+            let old = ks.get(&id).await.unwrap();
+            assert_eq!(old.state, KeyState::Rotated);
+            assert_eq!(old.superseded_by, Some(successor));
+            assert_eq!(old.usage_count, succeeded);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rotation_preview_is_read_only() {
+        let clock = Arc::new(MockClock::default());
+        let ks = test_keystore().with_clock(clock.clone());
+        ks.register_policy(KeyPolicy::default_dek());
+        let id = ks.generate("key", KeyType::DataEncrypting, Some(PolicyId::new("default-dek")), None).await.unwrap();
+        assert!(matches!(ks.rotation_preview(&id).await, Err(KeystoreError::NotActive(_))));
+        ks.activate(&id).await.unwrap();
+
+        let preview = ks.rotation_preview(&id).await.unwrap();
+        assert_eq!(preview.versions, vec![1]);
+        assert_eq!((preview.current_version, preview.successor_version), (1, 1));
+        assert_eq!(preview.threat_level, ThreatLevel::Low);
+        assert!(matches!(preview.verdict, Some(PolicyVerdict::Compliant)));
+        assert_eq!(preview.grace_period, Duration::from_secs(7 * 86400));
+        assert_eq!(preview.expires_at, clock.now() + chrono::Duration::days(7));
+        assert_eq!(preview.destroy_eligible_at, clock.now() + chrono::Duration::days(14));
+
+        assert_eq!(ks.get(&id).await.unwrap().state, KeyState::Active);
+        assert_eq!(ks.list_keys().await.unwrap().len(), 1);
+
+        for _ in 0..20 {
+            ks.record_threat_event(ThreatEvent::new(ThreatEventKind::ExternalAdvisory, 8.0));
+        }
+        let escalated = ks.rotation_preview(&id).await.unwrap();
+        assert!(escalated.threat_level >= ThreatLevel::High);
+        assert!(escalated.grace_period < preview.grace_period);
+    }
+
     // === Revocation ===
 
     #[tokio::test]
@@ -195,6 +473,30 @@ mod tests {
         assert!(meta.revoked_at.is_some());
     }
 
+    #[tokio::test]
+    async fn test_compromise_blocks_use_and_raises_threat() {
+        let ks = test_keystore();
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+        let aad = Aad::raw(b"aad");
+        let ctx = Context::raw(b"ctx");
+        let blob = ks.encrypt(&id, b"data", &aad, &ctx).await.unwrap();
+
+        ks.compromise(&id, "found in public repo").await.unwrap();
+        let meta = ks.get(&id).await.unwrap();
+        assert_eq!(meta.state, KeyState::Compromised);
+        assert!(meta.compromised_at.is_some() && meta.revoked_at.is_none());
+        assert!(ks.threat_level() >= ThreatLevel::Guarded);
+
+        assert!(ks.encrypt(&id, b"more", &aad, &ctx).await.is_err());
+        assert!(ks.decrypt(&blob, &aad, &ctx).await.is_err());
+        assert!(ks.compromise(&id, "again").await.is_err());
+        assert!(ks.revoke(&id, "too late").await.is_err());
+
+        ks.destroy(&id).await.unwrap();
+        assert_eq!(ks.get(&id).await.unwrap().state, KeyState::Destroyed);
+    }
+
     // === Destruction ===
 
     #[tokio::test]
@@ -213,6 +515,109 @@ mod tests {
         assert_eq!(meta.versions[0].public_key_hex, "DESTROYED");
     }
 
+    #[tokio::test]
+    async fn test_decrypt_try_all_versions_recovers_misnumbered_blob() {
+        let ks = test_keystore();
+        let (aad, ctx) = (Aad::raw(b"aad"), Context::raw(b"ctx"));
+        let k1 = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&k1).await.unwrap();
+        let old = ks.encrypt(&k1, b"k1 data", &aad, &ctx).await.unwrap();
+        let k2 = ks.rotate(&k1).await.unwrap();
+        let mid = ks.encrypt(&k2, b"k2 data", &aad, &ctx).await.unwrap();
+        let k3 = ks.rotate(&k2).await.unwrap();
+
+        // Relabeled with the newest key: every record holds only version 1,
+        // so `decrypt` tries k3's material and fails.
+        let mut relabeled = old.clone();
+        relabeled.key_id = k3.to_string();
+        assert!(ks.decrypt(&relabeled, &aad, &ctx).await.is_err());
+        assert_eq!(
+            ks.decrypt_try_all_versions(&relabeled, &aad, &ctx).await.unwrap(),
+            (k1.clone(), 1, b"k1 data".to_vec())
+        );
+
+        // Named by an older key, found in a newer one.
+        let mut backwards = mid.clone();
+        backwards.key_id = k1.to_string();
+        backwards.key_version = 9;
+        assert_eq!(
+            ks.decrypt_try_all_versions(&backwards, &aad, &ctx).await.unwrap(),
+            (k2.clone(), 1, b"k2 data".to_vec())
+        );
+
+        assert!(ks.decrypt_try_all_versions(&relabeled, &Aad::raw(b"wrong"), &ctx).await.is_err());
+
+        // A key outside the lineage is not tried.
+        let other = ks.generate("other", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&other).await.unwrap();
+        let mut foreign = old;
+        foreign.key_id = other.to_string();
+        assert!(ks.decrypt_try_all_versions(&foreign, &aad, &ctx).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bulk_lifecycle_reports_per_key() {
+        let ks = test_keystore();
+        let a = ks.generate("a", KeyType::DataEncrypting, None, None).await.unwrap();
+        let b = ks.generate("b", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&b).await.unwrap();
+        let missing = KeyId::new("missing");
+
+        let results = ks.activate_many(&[a.clone(), b.clone(), missing.clone()]).await;
+        assert_eq!(results.iter().map(|(id, _)| id.clone()).collect::<Vec<_>>(), vec![a.clone(), b.clone(), missing.clone()]);
+        assert!(results[0].1.is_ok());
+        assert!(results[1].1.is_err(), "already active");
+        assert!(results[2].1.is_err());
+        assert_eq!(ks.get(&a).await.unwrap().state, KeyState::Active);
+
+        let revoked = ks.revoke_many(&[a.clone(), b.clone()], "bulk").await;
+        assert!(revoked.iter().all(|(_, r)| r.is_ok()));
+        let destroyed = ks.destroy_many(&[missing, a.clone(), b.clone()]).await;
+        assert!(destroyed[0].1.is_err() && destroyed[1].1.is_ok() && destroyed[2].1.is_ok());
+        assert_eq!(ks.get(&b).await.unwrap().state, KeyState::Destroyed);
+    }
+
+    #[tokio::test]
+    async fn test_key_versions_marks_current_and_decryptable() {
+        let ks = test_keystore();
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        let pending = ks.key_versions(&id).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert!(!pending[0].is_current && !pending[0].decryptable);
+
+        ks.activate(&id).await.unwrap();
+        let mut meta = ks.get(&id).await.unwrap();
+        let active = ks.key_versions(&id).await.unwrap();
+        assert!(active[0].is_current && active[0].decryptable);
+        assert_eq!(active[0].key_id, id);
+
+        let mut v2 = meta.versions[0].clone();
+        v2.version = 2;
+        meta.versions.push(v2);
+        meta.current_version = 2;
+        meta.versions[0].secret_key_hex = "DESTROYED".into();
+        let history = meta.version_history();
+        assert_eq!(history.iter().map(|v| v.version).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(!history[0].is_current && !history[0].decryptable);
+        assert!(history[1].is_current && history[1].decryptable);
+
+        // The whole lineage is listed from either end, oldest first, and
+        // only the ACTIVE head is current.
+        let new_id = ks.rotate(&id).await.unwrap();
+        let newest = ks.rotate(&new_id).await.unwrap();
+        for from in [&id, &new_id, &newest] {
+            let rows = ks.key_versions(from).await.unwrap();
+            assert_eq!(rows.iter().map(|v| &v.key_id).collect::<Vec<_>>(), vec![&id, &new_id, &newest]);
+            assert_eq!(rows.iter().map(|v| v.is_current).collect::<Vec<_>>(), vec![false, false, true]);
+            assert!(rows.iter().all(|v| v.decryptable));
+        }
+
+        ks.revoke(&newest, "test").await.unwrap();
+        let rows = ks.key_versions(&id).await.unwrap();
+        assert!(!rows[2].decryptable && !rows.iter().any(|v| v.is_current));
+        assert!(ks.key_versions(&KeyId::new("missing")).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_destroy_active_key_fails() {
         let ks = test_keystore();
@@ -222,6 +627,126 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_destroy_eligible_respects_grace_and_retention() {
+        let clock = Arc::new(MockClock::default());
+        let ks = test_keystore().with_clock(clock.clone());
+        ks.register_policy(KeyPolicy {
+            rotation_grace_period: Duration::from_secs(86400),
+            min_versions_retained: 1,
+            ..KeyPolicy::default_dek()
+        });
+
+        let ids = expired_lineage(&ks, &clock, "k", 3).await;
+        let pending = ks.generate("pending", KeyType::DataEncrypting, None, None).await.unwrap();
+
+        assert!(!ks.should_destroy(&pending).await.unwrap().is_safe());
+        assert!(!ks.should_destroy(&ids[0]).await.unwrap().is_safe());
+
+        clock.advance(Duration::from_secs(2 * 86400));
+        let report = ks.destroy_eligible().await.unwrap();
+        assert_eq!(report.destroyed, vec![ids[0].clone(), ids[1].clone()]);
+        assert_eq!(report.blocked.len(), 1);
+        assert_eq!(report.blocked[0].0, ids[2]);
+        assert_eq!(ks.get(&ids[2]).await.unwrap().state, KeyState::Expired);
+    }
+
+    #[tokio::test]
+    async fn test_retention_is_counted_per_lineage() {
+        let clock = Arc::new(MockClock::default());
+        let ks = test_keystore().with_clock(clock.clone());
+        ks.register_policy(KeyPolicy {
+            rotation_grace_period: Duration::from_secs(86400),
+            min_versions_retained: 1,
+            ..KeyPolicy::default_dek()
+        });
+
+        // Two independent keys under the same policy.
+        let a = expired_lineage(&ks, &clock, "a", 1).await;
+        let b = expired_lineage(&ks, &clock, "b", 3).await;
+
+        clock.advance(Duration::from_secs(2 * 86400));
+        // B's retired keys don't count towards A's floor.
+        assert!(!ks.should_destroy(&a[0]).await.unwrap().is_safe());
+
+        let report = ks.destroy_eligible().await.unwrap();
+        assert_eq!(report.destroyed, vec![b[0].clone(), b[1].clone()]);
+        let mut blocked: Vec<KeyId> = report.blocked.into_iter().map(|(id, _)| id).collect();
+        blocked.sort_by(|x, y| x.as_str().cmp(y.as_str()));
+        let mut expected = vec![a[0].clone(), b[2].clone()];
+        expected.sort_by(|x, y| x.as_str().cmp(y.as_str()));
+        assert_eq!(blocked, expected);
+        assert_eq!(ks.get(&a[0]).await.unwrap().state, KeyState::Expired);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_destroyable_waits_for_retention_window() {
+        let clock = Arc::new(MockClock::default());
+        let ks = test_keystore().with_clock(clock.clone());
+        ks.register_policy(KeyPolicy {
+            rotation_grace_period: Duration::from_secs(86400),
+            min_versions_retained: 1,
+            destroy_after: Some(Duration::from_secs(30 * 86400)),
+            ..KeyPolicy::default_dek()
+        });
+
+        let ids = expired_lineage(&ks, &clock, "k", 3).await;
+        // No policy, so no retention window: never swept.
+        let bare = ks.generate("bare", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&bare).await.unwrap();
+        ks.revoke(&bare, "retired").await.unwrap();
+
+        // Past the grace period but inside the window: nothing goes.
+        clock.advance(Duration::from_secs(2 * 86400));
+        let report = ks.sweep_destroyable().await.unwrap();
+        assert!(report.destroyed.is_empty());
+        assert_eq!(report.blocked.len(), 3);
+
+        // Past the window, the retention floor still keeps the newest.
+        clock.advance(Duration::from_secs(30 * 86400));
+        let report = ks.sweep_destroyable().await.unwrap();
+        assert_eq!(report.destroyed, vec![ids[0].clone(), ids[1].clone()]);
+        assert_eq!(report.blocked.len(), 1);
+        assert_eq!(report.blocked[0].0, ids[2]);
+        assert_eq!(ks.get(&ids[0]).await.unwrap().state, KeyState::Destroyed);
+        assert_eq!(ks.get(&bare).await.unwrap().state, KeyState::Revoked);
+    }
+
+    #[tokio::test]
+    async fn test_retention_window_runs_from_expiry_not_last_write() {
+        let clock = Arc::new(MockClock::default());
+        let ks = test_keystore().with_clock(clock.clone());
+        ks.register_policy(KeyPolicy {
+            rotation_triggers: vec![],
+            rotation_grace_period: Duration::from_secs(86400),
+            max_lifetime: Some(Duration::from_secs(100 * 86400)),
+            min_versions_retained: 0,
+            destroy_after: Some(Duration::from_secs(30 * 86400)),
+            ..KeyPolicy::default_dek()
+        });
+        let id = ks.generate("key", KeyType::DataEncrypting, Some(PolicyId::new("default-dek")), None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+
+        clock.advance(Duration::from_secs(101 * 86400));
+        ks.expire_due_keys().await.unwrap();
+        let expired_at = ks.get(&id).await.unwrap().expired_at;
+        assert_eq!(expired_at, Some(clock.now()));
+
+        // A later metadata write must not restart the window.
+        clock.advance(Duration::from_secs(29 * 86400));
+        ks.set_tag(&id, "note", "archived").await.unwrap();
+        clock.advance(Duration::from_secs(2 * 86400));
+        let report = ks.sweep_destroyable().await.unwrap();
+        assert_eq!(report.destroyed, vec![id.clone()]);
+        assert_eq!(ks.get(&id).await.unwrap().expired_at, expired_at);
+    }
+
+    #[tokio::test]
+    async fn test_policy_rejects_zero_destroy_after() {
+        let p = KeyPolicy { destroy_after: Some(Duration::ZERO), ..KeyPolicy::default_dek() };
+        assert!(p.validate().is_err());
+    }
+
     // === State Machine ===
 
     #[tokio::test]
@@ -234,6 +759,10 @@ mod tests {
         assert!(KeyState::Rotated.can_transition_to(KeyState::Expired));
         assert!(KeyState::Expired.can_transition_to(KeyState::Destroyed));
         assert!(KeyState::Revoked.can_transition_to(KeyState::Destroyed));
+        assert!(KeyState::Active.can_transition_to(KeyState::Compromised));
+        assert!(KeyState::Compromised.can_transition_to(KeyState::Destroyed));
+        assert!(!KeyState::Compromised.can_encrypt());
+        assert!(!KeyState::Compromised.can_decrypt());
     }
 
     #[tokio::test]
@@ -243,6 +772,10 @@ mod tests {
         assert!(!KeyState::Rotated.can_transition_to(KeyState::Active));
         assert!(!KeyState::Expired.can_transition_to(KeyState::Active));
         assert!(!KeyState::Destroyed.can_transition_to(KeyState::Active));
+        assert!(!KeyState::Pending.can_transition_to(KeyState::Compromised));
+        assert!(!KeyState::Revoked.can_transition_to(KeyState::Compromised));
+        assert!(!KeyState::Compromised.can_transition_to(KeyState::Active));
+        assert!(!KeyState::Compromised.can_transition_to(KeyState::Revoked));
     }
 
     // === Encrypt / Decrypt ===
@@ -281,104 +814,881 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_encrypt_with_pending_key_fails() {
-        let ks = test_keystore();
+    async fn test_encrypt_with_pending_key_fails() {
+        let ks = test_keystore();
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+
+        let aad = Aad::raw(b"aad");
+        let ctx = Context::raw(b"ctx");
+        let result = ks.encrypt(&id, b"data", &aad, &ctx).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_with_wrong_aad_fails() {
+        let ks = test_keystore();
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+
+        let aad = Aad::raw(b"correct-aad");
+        let ctx = Context::raw(b"ctx");
+        let blob = ks.encrypt(&id, b"data", &aad, &ctx).await.unwrap();
+
+        let wrong_aad = Aad::raw(b"wrong-aad");
+        let result = ks.decrypt(&blob, &wrong_aad, &ctx).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_batch_is_per_item() {
+        let ks = test_keystore();
+        let a = ks.generate("a", KeyType::DataEncrypting, None, None).await.unwrap();
+        let b = ks.generate("b", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&a).await.unwrap();
+        ks.activate(&b).await.unwrap();
+
+        let ctx = Context::raw(b"ctx");
+        let blob_a = ks.encrypt(&a, b"one", &Aad::raw(b"x"), &ctx).await.unwrap();
+        let blob_b = ks.encrypt(&b, b"two", &Aad::raw(b"y"), &ctx).await.unwrap();
+        let mut missing = blob_a.clone();
+        missing.key_id = "nope".into();
+
+        let results = ks.decrypt_batch(&[
+            (blob_a.clone(), Aad::raw(b"x"), ctx.clone()),
+            (blob_b, Aad::raw(b"y"), ctx.clone()),
+            (blob_a, Aad::raw(b"wrong"), ctx.clone()),
+            (missing, Aad::raw(b"x"), ctx),
+        ]).await;
+
+        assert_eq!(results[0].as_deref().unwrap(), b"one");
+        assert_eq!(results[1].as_deref().unwrap(), b"two");
+        assert!(results[2].is_err());
+        assert!(results[3].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_secret_key_cache_fills_and_invalidates_on_destroy() {
+        let ks = test_keystore().with_secret_key_cache(1);
+        let a = ks.generate("a", KeyType::DataEncrypting, None, None).await.unwrap();
+        let b = ks.generate("b", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&a).await.unwrap();
+        ks.activate(&b).await.unwrap();
+
+        let aad = Aad::raw(b"aad");
+        let ctx = Context::raw(b"ctx");
+        let blob_a = ks.encrypt(&a, b"one", &aad, &ctx).await.unwrap();
+        let blob_b = ks.encrypt(&b, b"two", &aad, &ctx).await.unwrap();
+        assert_eq!(ks.cached_secret_keys(), 0);
+
+        assert_eq!(ks.decrypt(&blob_a, &aad, &ctx).await.unwrap(), b"one");
+        assert_eq!(ks.decrypt(&blob_a, &aad, &ctx).await.unwrap(), b"one");
+        assert_eq!(ks.cached_secret_keys(), 1);
+
+        // Capacity 1: b evicts a.
+        assert_eq!(ks.decrypt(&blob_b, &aad, &ctx).await.unwrap(), b"two");
+        assert_eq!(ks.cached_secret_keys(), 1);
+
+        ks.revoke(&b, "done").await.unwrap();
+        ks.destroy(&b).await.unwrap();
+        assert_eq!(ks.cached_secret_keys(), 0);
+        assert!(ks.decrypt(&blob_b, &aad, &ctx).await.is_err());
+
+        let uncached = test_keystore().with_secret_key_cache(0);
+        let c = uncached.generate("c", KeyType::DataEncrypting, None, None).await.unwrap();
+        uncached.activate(&c).await.unwrap();
+        let blob_c = uncached.encrypt(&c, b"three", &aad, &ctx).await.unwrap();
+        assert_eq!(uncached.decrypt(&blob_c, &aad, &ctx).await.unwrap(), b"three");
+        assert_eq!(uncached.cached_secret_keys(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_operation_metrics_record_successful_ops_only() {
+        let ks = test_keystore();
+        assert_eq!(ks.operation_metrics().encrypt_count, 0);
+        assert_eq!(ks.operation_metrics().decrypt_p99, std::time::Duration::ZERO);
+
+        let id = ks.generate("m", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+        let aad = Aad::raw(b"aad");
+        let ctx = Context::raw(b"ctx");
+        for _ in 0..3 {
+            let blob = ks.encrypt(&id, b"x", &aad, &ctx).await.unwrap();
+            ks.decrypt(&blob, &aad, &ctx).await.unwrap();
+        }
+        let blob = ks.encrypt(&id, b"x", &aad, &ctx).await.unwrap();
+        assert!(ks.decrypt(&blob, &Aad::raw(b"wrong"), &ctx).await.is_err());
+
+        let m = ks.operation_metrics();
+        assert_eq!(m.encrypt_count, 4);
+        assert_eq!(m.decrypt_count, 3);
+        assert!(m.decrypt_p50 > std::time::Duration::ZERO);
+        assert!(m.decrypt_p50 <= m.decrypt_p99 && m.decrypt_p99 <= m.decrypt_max);
+        assert!(m.encrypt_p50 <= m.encrypt_p99 && m.encrypt_p99 <= m.encrypt_max);
+    }
+
+    #[tokio::test]
+    async fn test_binding_length_cap() {
+        let ks = test_keystore().with_max_binding_bytes(8);
+        let id = ks.generate("cap", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+        let ctx = Context::raw(b"ctx");
+
+        let blob = ks.encrypt(&id, b"x", &Aad::raw(&[0; 8]), &ctx).await.unwrap();
+        let err = ks.encrypt(&id, b"x", &Aad::raw(&[0; 9]), &ctx).await.unwrap_err();
+        assert!(err.to_string().contains("aad is 9 bytes (max 8)"));
+        let err = ks.encrypt(&id, b"x", &Aad::raw(b""), &Context::raw(&[0; 9])).await.unwrap_err();
+        assert!(err.to_string().contains("context is 9 bytes"));
+
+        assert!(ks.decrypt(&blob, &Aad::raw(&[0; 8]), &ctx).await.is_ok());
+        // Rejected at the boundary: no usage counted.
+        assert_eq!(ks.get(&id).await.unwrap().usage_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_binding_cap_does_not_block_decrypt() {
+        let storage = Arc::new(InMemoryBackend::new());
+        let audit = Arc::new(InMemoryAuditSink::new());
+        let before = Keystore::new(storage.clone(), audit.clone()).with_max_binding_bytes(1 << 20);
+        let id = before.generate("legacy", KeyType::DataEncrypting, None, None).await.unwrap();
+        before.activate(&id).await.unwrap();
+        let (aad, ctx) = (Aad::raw(&[7; 8192]), Context::raw(b"ctx"));
+        let blob = before.encrypt(&id, b"sealed before the cap", &aad, &ctx).await.unwrap();
+
+        // Same store under the default cap: still opens.
+        let after = Keystore::new(storage, audit);
+        assert!(after.encrypt(&id, b"x", &aad, &ctx).await.is_err());
+        assert_eq!(after.decrypt(&blob, &aad, &ctx).await.unwrap(), b"sealed before the cap");
+        assert_eq!(after.decrypt_try_all_versions(&blob, &aad, &ctx).await.unwrap().2, b"sealed before the cap");
+    }
+
+    #[tokio::test]
+    async fn test_allowed_contexts_gate_encrypt() {
+        let ks = test_keystore();
+        let id = ks.generate("billing-dek", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+
+        let aad = Aad::raw(b"aad");
+        let billing = Context::raw(b"app|billing|prod");
+        let analytics = Context::raw(b"app|analytics|prod");
+
+        // Unrestricted by default
+        ks.encrypt(&id, b"data", &aad, &analytics).await.unwrap();
+
+        ks.set_allowed_contexts(&id, vec![b"app|billing|prod".to_vec()]).await.unwrap();
+        ks.encrypt(&id, b"data", &aad, &billing).await.unwrap();
+        let err = ks.encrypt(&id, b"data", &aad, &analytics).await.unwrap_err();
+        assert!(err.to_string().contains("context not allowed"));
+
+        ks.set_allowed_contexts(&id, Vec::new()).await.unwrap();
+        ks.encrypt(&id, b"data", &aad, &analytics).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_failure_stage_is_audited_not_returned() {
+        let (ks, audit) = test_keystore_with_audit();
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+
+        let aad = Aad::raw(b"aad");
+        let ctx = Context::raw(b"ctx");
+        let mut blob = ks.encrypt(&id, b"data", &aad, &ctx).await.unwrap();
+        blob.ciphertext_hex.truncate(20);
+
+        let err = ks.decrypt(&blob, &aad, &ctx).await.unwrap_err();
+        assert_eq!(err.to_string(), "decrypt: decryption failed");
+
+        let events = audit.events_for_key(&id).await;
+        let failed = events.iter()
+            .find(|e| matches!(e.action, crate::audit::AuditAction::DecryptionFailed { .. }))
+            .unwrap();
+        assert_eq!(failed.detail.as_deref(), Some("stage=decode"));
+
+        let threat = ks.recent_threat_events(1).pop().unwrap();
+        assert_eq!(threat.kind, ThreatEventKind::DecryptionFailure);
+        assert!(!threat.detail.unwrap().contains("stage"));
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_after_rotation_uses_correct_version() {
+        let ks = test_keystore();
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+
+        let aad = Aad::raw(b"aad");
+        let ctx = Context::raw(b"ctx");
+
+        let blob_old = ks.encrypt(&id, b"old key", &aad, &ctx).await.unwrap();
+
+        let new_id = ks.rotate(&id).await.unwrap();
+
+        // The rotated key is decrypt-only.
+        assert!(ks.encrypt(&id, b"nope", &aad, &ctx).await.is_err());
+        let blob_new = ks.encrypt(&new_id, b"new key", &aad, &ctx).await.unwrap();
+        assert_eq!(blob_new.key_id, new_id.to_string());
+        assert_eq!(blob_new.key_version, 1);
+
+        // Both should decrypt correctly
+        let pt1 = ks.decrypt(&blob_old, &aad, &ctx).await.unwrap();
+        let pt2 = ks.decrypt(&blob_new, &aad, &ctx).await.unwrap();
+        assert_eq!(pt1, b"old key");
+        assert_eq!(pt2, b"new key");
+    }
+
+    // === Policy Evaluation ===
+
+    #[tokio::test]
+    async fn test_policy_compliant() {
+        let ks = test_keystore();
+        let policy = KeyPolicy::default_dek();
+        let pid = policy.id.clone();
+        ks.register_policy(policy);
+
+        let id = ks.generate("key", KeyType::DataEncrypting, Some(pid), None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+
+        let verdict = ks.evaluate_policy(&id).await.unwrap();
+        assert!(matches!(verdict, PolicyVerdict::Compliant));
+    }
+
+    #[tokio::test]
+    async fn test_default_policy_assigned_by_key_type() {
+        let ks = test_keystore();
+        let missing = ks.set_default_policy(KeyType::DataEncrypting, PolicyId::new("default-dek"));
+        assert!(matches!(missing, Err(KeystoreError::PolicyNotFound(_))));
+
+        ks.register_policy(KeyPolicy::default_dek());
+        ks.register_policy(KeyPolicy::default_kek());
+        ks.set_default_policy(KeyType::DataEncrypting, PolicyId::new("default-dek")).unwrap();
+
+        let dek = ks.generate("dek", KeyType::DataEncrypting, None, None).await.unwrap();
+        assert_eq!(ks.get(&dek).await.unwrap().policy_id, Some(PolicyId::new("default-dek")));
+
+        // An explicit policy wins; other key types are unaffected.
+        let explicit = ks.generate("explicit", KeyType::DataEncrypting, Some(PolicyId::new("default-kek")), None).await.unwrap();
+        assert_eq!(ks.get(&explicit).await.unwrap().policy_id, Some(PolicyId::new("default-kek")));
+        let kek = ks.generate("kek", KeyType::KeyEncrypting, None, None).await.unwrap();
+        assert_eq!(ks.get(&kek).await.unwrap().policy_id, None);
+
+        // Rotation carries the inherited policy forward.
+        ks.activate(&dek).await.unwrap();
+        let successor = ks.rotate(&dek).await.unwrap();
+        assert_eq!(ks.get(&successor).await.unwrap().policy_id, Some(PolicyId::new("default-dek")));
+
+        ks.clear_default_policy(KeyType::DataEncrypting);
+        assert_eq!(ks.default_policy(KeyType::DataEncrypting), None);
+        let bare = ks.generate("bare", KeyType::DataEncrypting, None, None).await.unwrap();
+        assert_eq!(ks.get(&bare).await.unwrap().policy_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_policy_usage_limit() {
+        let ks = test_keystore();
+        let policy = KeyPolicy {
+            id: PolicyId::new("limited"),
+            name: "Limited".into(),
+            applies_to: vec![KeyType::DataEncrypting],
+            rotation_triggers: vec![],
+            rotation_grace_period: Duration::from_secs(86400),
+            max_lifetime: None,
+            max_usage_count: Some(10),
+            auto_rotate: false,
+            min_versions_retained: 1,
+            destroy_after: None,
+            warn_fraction: 0.9,
+        };
+        let pid = policy.id.clone();
+        ks.register_policy(policy);
+
+        let id = ks.generate("key", KeyType::DataEncrypting, Some(pid), None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+
+        let aad = Aad::raw(b"aad");
+        let ctx = Context::raw(b"ctx");
+
+        // Use it 10 times
+        for _ in 0..10 {
+            ks.encrypt(&id, b"data", &aad, &ctx).await.unwrap();
+        }
+
+        let verdict = ks.evaluate_policy(&id).await.unwrap();
+        assert!(verdict.needs_rotation());
+    }
+
+    #[tokio::test]
+    async fn test_policy_warn_fraction() {
+        let ks = test_keystore();
+        let policy = KeyPolicy {
+            id: PolicyId::new("early-warning"),
+            max_usage_count: Some(10),
+            warn_fraction: 0.5,
+            ..KeyPolicy::default_dek()
+        };
+        policy.validate().unwrap();
+        ks.register_policy(policy.clone());
+
+        let id = ks.generate("key", KeyType::DataEncrypting, Some(policy.id.clone()), None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+        let (aad, ctx) = (Aad::raw(b"aad"), Context::raw(b"ctx"));
+        for _ in 0..4 {
+            ks.encrypt(&id, b"data", &aad, &ctx).await.unwrap();
+        }
+        assert!(matches!(ks.evaluate_policy(&id).await.unwrap(), PolicyVerdict::Compliant));
+        ks.encrypt(&id, b"data", &aad, &ctx).await.unwrap();
+        assert!(matches!(ks.evaluate_policy(&id).await.unwrap(), PolicyVerdict::Warning { .. }));
+
+        for bad in [0.0, 1.0, f64::NAN] {
+            let p = KeyPolicy { warn_fraction: bad, ..KeyPolicy::default_dek() };
+            assert!(matches!(p.validate(), Err(KeystoreError::PolicyViolation(_))));
+        }
+
+        // Older stored policies without the field get the default.
+        let mut json = serde_json::to_value(KeyPolicy::default_dek()).unwrap();
+        json.as_object_mut().unwrap().remove("warn_fraction");
+        let parsed: KeyPolicy = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.warn_fraction, DEFAULT_WARN_FRACTION);
+    }
+
+    #[tokio::test]
+    async fn test_update_policy() {
+        let ks = test_keystore();
+        ks.register_policy(KeyPolicy::default_dek());
+
+        let mut updated = KeyPolicy::default_dek();
+        updated.max_usage_count = Some(500);
+        ks.update_policy(updated).unwrap();
+        assert_eq!(ks.get_policy(&PolicyId::new("default-dek")).unwrap().max_usage_count, Some(500));
+
+        let mut zero_grace = KeyPolicy::default_dek();
+        zero_grace.rotation_grace_period = Duration::ZERO;
+        assert!(matches!(ks.update_policy(zero_grace), Err(KeystoreError::PolicyViolation(_))));
+
+        let unknown = KeyPolicy { id: PolicyId::new("nope"), ..KeyPolicy::default_dek() };
+        assert!(matches!(ks.update_policy(unknown), Err(KeystoreError::PolicyNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_register_policy_through_shared_keystore() {
+        let ks = Arc::new(test_keystore());
+        let id = ks.generate("key", KeyType::DataEncrypting, Some(PolicyId::new("late")), None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+
+        // Policy referenced before it exists
+        assert!(matches!(ks.evaluate_policy(&id).await, Err(KeystoreError::PolicyNotFound(_))));
+
+        let shared = ks.clone();
+        tokio::spawn(async move {
+            shared.register_policy(KeyPolicy { id: PolicyId::new("late"), ..KeyPolicy::default_dek() });
+        }).await.unwrap();
+
+        assert!(ks.get_policy(&PolicyId::new("late")).is_some());
+        ks.register_policy(KeyPolicy { id: PolicyId::new("early"), ..KeyPolicy::default_dek() });
+        assert_eq!(ks.policy_ids(), vec![PolicyId::new("early"), PolicyId::new("late")]);
+        assert!(matches!(ks.evaluate_policy(&id).await.unwrap(), PolicyVerdict::Compliant));
+    }
+
+    // === Expiration ===
+
+    #[tokio::test]
+    async fn test_expiration_warning_hook_fires() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let storage = Arc::new(InMemoryBackend::new());
+        let ks = Keystore::new(storage.clone(), Arc::new(InMemoryAuditSink::new()));
+        ks.register_policy(KeyPolicy {
+            rotation_triggers: vec![],
+            max_lifetime: Some(Duration::from_secs(100 * 86400)),
+            ..KeyPolicy::default_dek()
+        });
+
+        let id = ks.generate("key", KeyType::DataEncrypting, Some(PolicyId::new("default-dek")), None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+
+        // Backdate activation to 95% of max lifetime
+        let mut meta = ks.get(&id).await.unwrap();
+        meta.activated_at = Some(chrono::Utc::now() - chrono::Duration::days(95));
+        storage.put(&meta).unwrap();
+
+        let fired = Arc::new(AtomicUsize::new(0));
+        let counter = fired.clone();
+        let expected = id.clone();
+        ks.on_expiration_warning(Box::new(move |key_id, _reason, remaining| {
+            assert_eq!(key_id, &expected);
+            assert!(remaining > Duration::ZERO);
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let report = ks.expire_due_keys().await.unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(fired.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mock_clock_drives_max_lifetime_expiry() {
+        let clock = Arc::new(MockClock::default());
+        let ks = test_keystore().with_clock(clock.clone());
+        ks.register_policy(KeyPolicy {
+            rotation_triggers: vec![],
+            max_lifetime: Some(Duration::from_secs(100 * 86400)),
+            ..KeyPolicy::default_dek()
+        });
+        let id = ks.generate("key", KeyType::DataEncrypting, Some(PolicyId::new("default-dek")), None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+        assert!(matches!(ks.should_expire(&id).await.unwrap(), ExpirationDecision::NotNeeded));
+
+        clock.advance(Duration::from_secs(95 * 86400));
+        assert!(matches!(ks.should_expire(&id).await.unwrap(), ExpirationDecision::Warning { .. }));
+
+        clock.advance(Duration::from_secs(6 * 86400));
+        let report = ks.expire_due_keys().await.unwrap();
+        assert_eq!(report.expired.len(), 1);
+        assert_eq!(ks.get(&id).await.unwrap().state, KeyState::Expired);
+    }
+
+    #[tokio::test]
+    async fn test_time_until_expiry() {
+        let clock = Arc::new(MockClock::default());
+        let ks = test_keystore().with_clock(clock.clone());
+        let dek = KeyPolicy::default_dek();
+        let kek = KeyPolicy::default_kek();
+        let day = Duration::from_secs(86400);
+
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        assert_eq!(ks.get(&id).await.unwrap().time_until_expiry_at(&dek, clock.now()), None); // PENDING
+
+        ks.activate(&id).await.unwrap();
+        clock.advance(10 * day);
+        let active = ks.get(&id).await.unwrap();
+        assert_eq!(active.time_until_expiry_at(&dek, clock.now()), Some(355 * day));
+        assert_eq!(active.time_until_expiry_at(&kek, clock.now()), None); // no max_lifetime
+
+        ks.rotate(&id).await.unwrap();
+        clock.advance(2 * day);
+        let rotated = ks.get(&id).await.unwrap();
+        assert_eq!(rotated.time_until_expiry_at(&dek, clock.now()), Some(5 * day));
+        assert_eq!(rotated.time_until_expiry_at(&kek, clock.now()), Some(28 * day));
+
+        clock.advance(30 * day);
+        assert_eq!(rotated.time_until_expiry_at(&dek, clock.now()), Some(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_expiration_preview_changes_nothing() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let clock = Arc::new(MockClock::default());
+        let (ks, audit) = test_keystore_with_audit();
+        let ks = ks.with_clock(clock.clone());
+        ks.register_policy(KeyPolicy {
+            rotation_triggers: vec![],
+            max_lifetime: Some(Duration::from_secs(100 * 86400)),
+            ..KeyPolicy::default_dek()
+        });
+        let pid = Some(PolicyId::new("default-dek"));
+        let due = ks.generate("due", KeyType::DataEncrypting, pid.clone(), None).await.unwrap();
+        ks.activate(&due).await.unwrap();
+        clock.advance(Duration::from_secs(5 * 86400));
+        let soon = ks.generate("soon", KeyType::DataEncrypting, pid.clone(), None).await.unwrap();
+        ks.activate(&soon).await.unwrap();
+        clock.advance(Duration::from_secs(15 * 86400));
+        let fresh = ks.generate("fresh", KeyType::DataEncrypting, pid, None).await.unwrap();
+        ks.activate(&fresh).await.unwrap();
+        clock.advance(Duration::from_secs(82 * 86400));
+
+        let fired = Arc::new(AtomicUsize::new(0));
+        let counter = fired.clone();
+        ks.on_expiration_warning(Box::new(move |_, _, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+        let events_before = audit.len().await;
+
+        let preview = ks.expiration_preview().await.unwrap();
+        assert_eq!(preview.expired, vec![(due.clone(), ExpirationSource::MaxLifetimeExceeded)]);
+        assert_eq!(preview.warnings.len(), 1);
+        assert_eq!(preview.warnings[0].0, soon);
+        assert_eq!(preview.skipped, 1);
+        assert!(preview.failed.is_empty());
+
+        assert_eq!(ks.get(&due).await.unwrap().state, KeyState::Active);
+        assert_eq!(fired.load(Ordering::SeqCst), 0);
+        assert_eq!(audit.len().await, events_before);
+
+        let report = ks.expire_due_keys().await.unwrap();
+        assert_eq!(report.expired, preview.expired);
+    }
+
+    #[tokio::test]
+    async fn test_policy_blocked_encrypt_records_threat_event() {
+        let clock = Arc::new(MockClock::default());
+        let ks = test_keystore().with_clock(clock.clone());
+        ks.register_policy(KeyPolicy::default_dek());
+        let id = ks.generate("key", KeyType::DataEncrypting, Some(PolicyId::new("default-dek")), None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+        let aad = Aad::raw(b"aad");
+        let ctx = Context::raw(b"ctx");
+
+        ks.encrypt(&id, b"fresh", &aad, &ctx).await.unwrap();
+        assert_eq!(ks.threat_score(), 0.0);
+
+        clock.advance(Duration::from_secs(91 * 86400));
+        assert!(ks.encrypt(&id, b"stale", &aad, &ctx).await.is_err());
+        assert!(ks.threat_score() > 0.0);
+        let snapshot = ks.threat_snapshot();
+        assert_eq!(snapshot.events.len(), 1);
+        assert_eq!(snapshot.events[0].kind, ThreatEventKind::PolicyViolation);
+    }
+
+    #[test]
+    fn test_set_threat_config_validates_and_reassesses() {
+        let ks = test_keystore();
+        ks.record_threat_event(ThreatEvent::new(ThreatEventKind::AuthFailure, 3.0));
+        assert_eq!(ks.threat_level(), ThreatLevel::Low);
+
+        let bad = [
+            ThreatConfig { decay_rate: 0.0, ..ThreatConfig::default() },
+            ThreatConfig { thresholds: [1.0, 1.0, 2.0, 3.0], ..ThreatConfig::default() },
+            ThreatConfig { hysteresis: 1.0, ..ThreatConfig::default() },
+            ThreatConfig { max_events: 0, ..ThreatConfig::default() },
+        ];
+        for config in bad {
+            assert!(matches!(ks.set_threat_config(config), Err(KeystoreError::InvalidInput(_))));
+        }
+        assert_eq!(ks.threat_config(), ThreatConfig::default());
+
+        let tight = ThreatConfig { thresholds: [0.5, 1.0, 1.5, 2.0], ..ThreatConfig::default() };
+        ks.set_threat_config(tight.clone()).unwrap();
+        assert_eq!(ks.threat_config(), tight);
+        assert_eq!(ks.threat_level(), ThreatLevel::Critical);
+    }
+
+    // === Tags ===
+
+    #[tokio::test]
+    async fn test_set_and_remove_tag() {
+        let ks = test_keystore();
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+
+        ks.set_tag(&id, "owner", "payments").await.unwrap();
+        ks.set_tag(&id, "cost-center", "cc-42").await.unwrap();
+        let meta = ks.get(&id).await.unwrap();
+        assert_eq!(meta.tags.get("owner").map(String::as_str), Some("payments"));
+
+        assert_eq!(ks.list_by_tag("owner", Some("payments")).await.unwrap().len(), 1);
+        assert_eq!(ks.list_by_tag("owner", Some("billing")).await.unwrap().len(), 0);
+
+        assert_eq!(ks.remove_tag(&id, "owner").await.unwrap(), Some("payments".into()));
+        assert_eq!(ks.remove_tag(&id, "owner").await.unwrap(), None);
+        assert!(ks.set_tag(&id, "", "x").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tag_survives_file_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(FileBackend::new(dir.path()).unwrap());
+        let ks = Keystore::new(storage, Arc::new(InMemoryAuditSink::new()));
         let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.set_tag(&id, "compliance-scope", "pci").await.unwrap();
 
-        let aad = Aad::raw(b"aad");
-        let ctx = Context::raw(b"ctx");
-        let result = ks.encrypt(&id, b"data", &aad, &ctx).await;
-        assert!(result.is_err());
+        let reopened = FileBackend::new(dir.path()).unwrap();
+        let meta = reopened.get(&id).unwrap().unwrap();
+        assert_eq!(meta.tags.get("compliance-scope").map(String::as_str), Some("pci"));
     }
 
     #[tokio::test]
-    async fn test_decrypt_with_wrong_aad_fails() {
-        let ks = test_keystore();
+    async fn test_file_backend_get_rejects_renamed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileBackend::new(dir.path()).unwrap();
+        let ks = Keystore::new(Arc::new(FileBackend::new(dir.path()).unwrap()), Arc::new(InMemoryAuditSink::new()));
         let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
-        ks.activate(&id).await.unwrap();
-
-        let aad = Aad::raw(b"correct-aad");
-        let ctx = Context::raw(b"ctx");
-        let blob = ks.encrypt(&id, b"data", &aad, &ctx).await.unwrap();
+        let other = KeyId::generate();
+        std::fs::rename(
+            dir.path().join(format!("{}.json", id)),
+            dir.path().join(format!("{}.json", other)),
+        ).unwrap();
+
+        assert!(matches!(storage.get(&other), Err(KeystoreError::StorageError(_))));
+        assert!(storage.get(&id).unwrap().is_none());
+    }
 
-        let wrong_aad = Aad::raw(b"wrong-aad");
-        let result = ks.decrypt(&blob, &wrong_aad, &ctx).await;
-        assert!(result.is_err());
+    #[tokio::test]
+    async fn test_file_backend_list_skips_corrupt_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(FileBackend::new(dir.path()).unwrap());
+        let ks = Keystore::new(storage.clone(), Arc::new(InMemoryAuditSink::new()));
+        let id = ks.generate("survivor", KeyType::DataEncrypting, None, None).await.unwrap();
+        std::fs::write(dir.path().join("half-written.json"), "{\"id\": \"trunc").unwrap();
+
+        let keys = storage.list().unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].id, id);
+        assert_eq!(ks.list_keys().await.unwrap().len(), 1);
+
+        let err = storage.list_strict().unwrap_err().to_string();
+        assert!(err.contains("half-written.json"), "{}", err);
     }
 
     #[tokio::test]
-    async fn test_decrypt_after_rotation_uses_correct_version() {
-        let ks = test_keystore();
-        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
-        ks.activate(&id).await.unwrap();
+    async fn test_file_backend_concurrent_writers() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(FileBackend::new(dir.path()).unwrap());
+        let ks = Keystore::new(storage, Arc::new(InMemoryAuditSink::new()));
+        let id = ks.generate("contended", KeyType::DataEncrypting, None, None).await.unwrap();
+        let base = ks.get(&id).await.unwrap();
+
+        let writers: Vec<_> = (0..4u64)
+            .map(|w| {
+                let backend = FileBackend::new(dir.path()).unwrap();
+                let (id, mut meta) = (id.clone(), base.clone());
+                std::thread::spawn(move || {
+                    for i in 0..50 {
+                        meta.usage_count = w * 1000 + i;
+                        backend.put(&meta).unwrap();
+                        backend.get(&id).unwrap().unwrap();
+                    }
+                })
+            })
+            .collect();
+        for w in writers {
+            w.join().unwrap();
+        }
+
+        let last = FileBackend::new(dir.path()).unwrap().list_strict().unwrap();
+        assert_eq!(last.len(), 1);
+        assert_eq!(last[0].usage_count % 1000, 49);
+    }
+
+    #[test]
+    fn test_v0_metadata_migrates_on_read() {
+        const V0_FIXTURE: &str = r#"{
+            "id": "0123456789abcdef0123456789abcdef",
+            "name": "legacy",
+            "key_type": "DataEncrypting",
+            "state": "Active",
+            "policy_id": null,
+            "parent_id": null,
+            "created_at": "2025-01-01T00:00:00Z",
+            "updated_at": "2025-01-02T00:00:00Z",
+            "activated_at": "2025-01-02T00:00:00Z",
+            "rotated_at": null,
+            "revoked_at": null,
+            "destroyed_at": null,
+            "versions": [
+                {"version": 1, "created_at": "2025-01-01T00:00:00Z", "public_key_hex": "00", "secret_key_hex": "00"}
+            ],
+            "current_version": 1,
+            "usage_count": 7,
+            "tags": {}
+        }"#;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("0123456789abcdef0123456789abcdef.json"), V0_FIXTURE).unwrap();
+        let backend = FileBackend::new(dir.path()).unwrap();
+        let id = KeyId::new("0123456789abcdef0123456789abcdef");
+
+        let meta = backend.get(&id).unwrap().unwrap();
+        assert_eq!(meta.schema_version, KEY_METADATA_SCHEMA_VERSION);
+        assert_eq!(meta.versions[0].usage_count, Some(7));
+        assert_eq!(meta.current_usage(), 7);
+
+        let mut future = meta.clone();
+        future.schema_version = KEY_METADATA_SCHEMA_VERSION + 1;
+        backend.put(&future).unwrap();
+        assert!(backend.get(&id).is_err());
+    }
 
+    // === Export / Import ===
+
+    #[tokio::test]
+    async fn test_export_import_roundtrip_through_json() {
+        let src = test_keystore();
+        let id = src.generate("exported", KeyType::DataEncrypting, None, None).await.unwrap();
+        src.activate(&id).await.unwrap();
         let aad = Aad::raw(b"aad");
         let ctx = Context::raw(b"ctx");
+        let blob = src.encrypt(&id, b"migrate me", &aad, &ctx).await.unwrap();
 
-        // Encrypt with version 1
-        let blob_v1 = ks.encrypt(&id, b"version one", &aad, &ctx).await.unwrap();
-        assert_eq!(blob_v1.key_version, 1);
+        let json = serde_json::to_string(&src.export_key(&id, Some("correct horse")).await.unwrap()).unwrap();
+        assert!(!json.contains(&src.get(&id).await.unwrap().versions[0].secret_key_hex));
 
-        // Rotate to version 2
-        ks.rotate(&id).await.unwrap();
+        let dst = test_keystore();
+        let export: KeyExport = serde_json::from_str(&json).unwrap();
+        assert!(dst.import_key(export.clone(), Some("wrong")).await.is_err());
+        assert!(dst.import_key(export.clone(), None).await.is_err());
 
-        // Encrypt with version 2
-        let blob_v2 = ks.encrypt(&id, b"version two", &aad, &ctx).await.unwrap();
-        assert_eq!(blob_v2.key_version, 2);
+        let imported = dst.import_key(export.clone(), Some("correct horse")).await.unwrap();
+        assert_eq!(imported, id);
+        assert_eq!(dst.get(&id).await.unwrap().state, KeyState::Active);
+        assert_eq!(dst.decrypt(&blob, &aad, &ctx).await.unwrap(), b"migrate me");
 
-        // Both should decrypt correctly
-        let pt1 = ks.decrypt(&blob_v1, &aad, &ctx).await.unwrap();
-        let pt2 = ks.decrypt(&blob_v2, &aad, &ctx).await.unwrap();
-        assert_eq!(pt1, b"version one");
-        assert_eq!(pt2, b"version two");
+        assert!(matches!(
+            dst.import_key(export, Some("correct horse")).await,
+            Err(KeystoreError::DuplicateKey(_))
+        ));
     }
 
-    // === Policy Evaluation ===
-
     #[tokio::test]
-    async fn test_policy_compliant() {
-        let mut ks = test_keystore();
-        let policy = KeyPolicy::default_dek();
-        let pid = policy.id.clone();
-        ks.register_policy(policy);
+    async fn test_import_rejects_tampered_export_metadata() {
+        let src = test_keystore();
+        let id = src.generate("victim", KeyType::DataEncrypting, None, None).await.unwrap();
+        src.activate(&id).await.unwrap();
+        let attacker = citadel_envelope::Citadel::new().generate_keypair().0;
+        let attacker_hex = hex::encode(attacker.to_bytes());
+
+        // Wrapped: any metadata edit breaks the unwrap.
+        let mut wrapped = src.export_key(&id, Some("pw")).await.unwrap();
+        wrapped.metadata.versions[0].public_key_hex = attacker_hex.clone();
+        let dst = test_keystore();
+        assert!(matches!(dst.import_key(wrapped, Some("pw")).await, Err(KeystoreError::InvalidInput(_))));
+        let mut wrapped = src.export_key(&id, Some("pw")).await.unwrap();
+        wrapped.metadata.name = "renamed".into();
+        assert!(dst.import_key(wrapped, Some("pw")).await.is_err());
+
+        // Plaintext: the public key must match the secret key.
+        let mut plain = src.export_key(&id, None).await.unwrap();
+        plain.metadata.versions[0].public_key_hex = attacker_hex;
+        let err = dst.import_key(plain, None).await.unwrap_err();
+        assert!(err.to_string().contains("does not match"), "{}", err);
+        assert!(dst.get(&id).await.is_err());
+    }
 
-        let id = ks.generate("key", KeyType::DataEncrypting, Some(pid), None).await.unwrap();
-        ks.activate(&id).await.unwrap();
+    #[tokio::test]
+    async fn test_import_rejects_invalid_key_id() {
+        let src = test_keystore();
+        let id = src.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        let dst = test_keystore();
+        for bad in ["../../etc/passwd".to_string(), "Upper".to_string(), "a".repeat(MAX_KEY_ID_LEN + 1), String::new()] {
+            let mut export = src.export_key(&id, None).await.unwrap();
+            export.metadata.id = KeyId::new(&bad);
+            assert!(matches!(dst.import_key(export, None).await, Err(KeystoreError::InvalidInput(_))), "{:?}", bad);
+        }
+        assert!(dst.list_keys().await.unwrap().is_empty());
+    }
 
-        let verdict = ks.evaluate_policy(&id).await.unwrap();
-        assert!(matches!(verdict, PolicyVerdict::Compliant));
+    #[tokio::test]
+    async fn test_derived_keys_regenerate_from_root_backup() {
+        let src = test_keystore();
+        let root = src.generate("root", KeyType::Root, None, None).await.unwrap();
+        assert!(matches!(
+            src.generate_derived("early", KeyType::Domain, None, root.clone()).await,
+            Err(GenerateError(KeystoreError::NotActive(_)))
+        ));
+        src.activate(&root).await.unwrap();
+        let domain = src.generate_derived("payments", KeyType::Domain, None, root.clone()).await.unwrap();
+        let other = src.generate_derived("billing", KeyType::Domain, None, root.clone()).await.unwrap();
+        let domain_meta = src.get(&domain).await.unwrap();
+        assert_eq!(domain_meta.derived_from_version, Some(1));
+        assert_eq!(domain_meta.parent_id, Some(root.clone()));
+        assert_ne!(domain_meta.versions[0].public_key_hex, src.get(&other).await.unwrap().versions[0].public_key_hex);
+
+        // Same name + type under the same parent would recreate the same key.
+        assert!(matches!(
+            src.generate_derived("payments", KeyType::Domain, None, root.clone()).await,
+            Err(GenerateError(KeystoreError::DuplicateKey(id))) if id == domain
+        ));
+
+        // Restore only the root elsewhere and re-derive.
+        let dst = test_keystore();
+        dst.import_key(src.export_key(&root, None).await.unwrap(), None).await.unwrap();
+        let rebuilt = dst.generate_derived("payments", KeyType::Domain, None, root.clone()).await.unwrap();
+        let rebuilt_meta = dst.get(&rebuilt).await.unwrap();
+        assert_eq!(rebuilt_meta.versions[0].public_key_hex, domain_meta.versions[0].public_key_hex);
+        assert_eq!(rebuilt_meta.versions[0].secret_key_hex, domain_meta.versions[0].secret_key_hex);
+
+        // A compromised or destroyed parent no longer seeds children.
+        src.compromise(&root, "leaked").await.unwrap();
+        assert!(matches!(
+            src.generate_derived("late", KeyType::Domain, None, root.clone()).await,
+            Err(GenerateError(KeystoreError::NotActive(_)))
+        ));
+        src.destroy(&root).await.unwrap();
+        assert!(matches!(
+            src.generate_derived("late", KeyType::Domain, None, root).await,
+            Err(GenerateError(KeystoreError::KeyDestroyed(_)))
+        ));
     }
 
     #[tokio::test]
-    async fn test_policy_usage_limit() {
-        let mut ks = test_keystore();
-        let policy = KeyPolicy {
-            id: PolicyId::new("limited"),
-            name: "Limited".into(),
-            applies_to: vec![KeyType::DataEncrypting],
-            rotation_triggers: vec![],
-            rotation_grace_period: Duration::from_secs(86400),
-            max_lifetime: None,
-            max_usage_count: Some(10),
-            auto_rotate: false,
-            min_versions_retained: 1,
-        };
-        let pid = policy.id.clone();
-        ks.register_policy(policy);
+    async fn test_import_rejects_inconsistent_state() {
+        let src = test_keystore();
+        let id = src.generate("pending", KeyType::DataEncrypting, None, None).await.unwrap();
+        let mut export = src.export_key(&id, None).await.unwrap();
+        export.metadata.state = KeyState::Active; // no activated_at
+
+        let dst = test_keystore();
+        assert!(matches!(dst.import_key(export, None).await, Err(KeystoreError::InvalidInput(_))));
+    }
 
-        let id = ks.generate("key", KeyType::DataEncrypting, Some(pid), None).await.unwrap();
-        ks.activate(&id).await.unwrap();
+    #[tokio::test]
+    async fn test_archive_roundtrip_through_buffer() {
+        let src = test_keystore();
+        src.register_policy(KeyPolicy::default_dek());
+        let active = src.generate("active", KeyType::DataEncrypting, Some(PolicyId::new("default-dek")), None).await.unwrap();
+        src.activate(&active).await.unwrap();
+        let pending = src.generate("pending", KeyType::DataEncrypting, None, None).await.unwrap();
+        let gone = src.generate("gone", KeyType::DataEncrypting, None, None).await.unwrap();
+        src.destroy(&gone).await.unwrap();
+        src.record_threat_event(ThreatEvent::new(ThreatEventKind::AuthFailure, 2.0));
 
         let aad = Aad::raw(b"aad");
         let ctx = Context::raw(b"ctx");
+        let blob = src.encrypt(&active, b"backed up", &aad, &ctx).await.unwrap();
+
+        let mut archive = Vec::new();
+        let written = src.export_archive(&mut archive).await.unwrap();
+        assert_eq!(written, ArchiveSummary { keys: 2, policies: 1, threat_events: 1 });
+
+        let dst = test_keystore();
+        let restored = dst.import_archive(archive.as_slice()).await.unwrap();
+        assert_eq!(restored, written);
+        assert!(dst.get_policy(&PolicyId::new("default-dek")).is_some());
+        assert_eq!(dst.get(&pending).await.unwrap().state, KeyState::Pending);
+        assert!(matches!(dst.get(&gone).await, Err(KeystoreError::KeyNotFound(_))));
+        assert_eq!(dst.threat_snapshot().events.len(), 1);
+        assert_eq!(dst.decrypt(&blob, &aad, &ctx).await.unwrap(), b"backed up");
+
+        // Restoring over existing keys is refused without touching anything.
+        assert!(matches!(dst.import_archive(archive.as_slice()).await, Err(KeystoreError::DuplicateKey(_))));
+        // A truncated archive is rejected before any key is written.
+        let fresh = test_keystore();
+        assert!(fresh.import_archive(&archive[..archive.len() - 5]).await.is_err());
+        assert!(fresh.list_keys().await.unwrap().is_empty());
+    }
 
-        // Use it 10 times
-        for _ in 0..10 {
-            ks.encrypt(&id, b"data", &aad, &ctx).await.unwrap();
-        }
+    #[tokio::test]
+    async fn test_rejected_archive_writes_nothing() {
+        let src = test_keystore();
+        let id = src.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        let key = src.export_key(&id, None).await.unwrap();
+
+        let archive = |policy: &KeyPolicy, keys: &[&KeyExport]| {
+            let mut out = Vec::new();
+            export::write_archive_header(&mut out).unwrap();
+            export::write_frame(&mut out, export::FRAME_POLICY, policy).unwrap();
+            for key in keys {
+                export::write_frame(&mut out, export::FRAME_KEY, key).unwrap();
+            }
+            export::write_end_frame(&mut out).unwrap();
+            out
+        };
 
-        let verdict = ks.evaluate_policy(&id).await.unwrap();
-        assert!(verdict.needs_rotation());
+        // The same key twice: the second copy must not fail after the first lands.
+        let dst = test_keystore();
+        let twice = archive(&KeyPolicy::default_dek(), &[&key, &key]);
+        assert!(matches!(dst.import_archive(twice.as_slice()).await, Err(KeystoreError::DuplicateKey(d)) if d == id));
+        assert!(dst.list_keys().await.unwrap().is_empty());
+        assert!(dst.get_policy(&PolicyId::new("default-dek")).is_none());
+
+        // An invalid policy is refused before any key is written.
+        let mut bad = KeyPolicy::default_dek();
+        bad.warn_fraction = 2.0;
+        let invalid = archive(&bad, &[&key]);
+        assert!(matches!(dst.import_archive(invalid.as_slice()).await, Err(KeystoreError::PolicyViolation(_))));
+        assert!(dst.list_keys().await.unwrap().is_empty());
+        assert!(dst.get_policy(&PolicyId::new("default-dek")).is_none());
+
+        let good = archive(&KeyPolicy::default_dek(), &[&key]);
+        dst.import_archive(good.as_slice()).await.unwrap();
+        assert!(dst.get_policy(&PolicyId::new("default-dek")).is_some());
+        assert_eq!(dst.get(&id).await.unwrap().id, id);
     }
 
     // === Audit ===
@@ -408,6 +1718,120 @@ mod tests {
         assert!(has_encrypt);
     }
 
+    #[tokio::test]
+    async fn test_with_actor_attributes_audit_events() {
+        let (ks, audit) = test_keystore_with_audit();
+        let id = ks.with_actor("key-ops").generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.with_actor("key-ops").activate(&id).await.unwrap();
+        ks.rotate(&id).await.unwrap();
+
+        let actors: Vec<String> = audit.events_for_key(&id).await.into_iter().map(|e| e.actor).collect();
+        assert_eq!(actors, vec!["key-ops", "key-ops", "system"]);
+    }
+
+    #[tokio::test]
+    async fn test_integrity_chain_verifies_and_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = Arc::new(FileAuditSink::new(dir.path().join("audit.jsonl")));
+        let ks = Keystore::new(
+            Arc::new(InMemoryBackend::new()),
+            Arc::new(IntegrityChainSink::new(log.clone())),
+        );
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+        ks.rotate(&id).await.unwrap();
+
+        let mut events = log.read_events().unwrap();
+        assert!(events.len() >= 3);
+        assert_eq!(verify_chain(&events), Ok(events.len()));
+
+        events[1].actor = "mallory".into();
+        let brk = verify_chain(&events).unwrap_err();
+        assert_eq!(brk.index, 2);
+
+        events.remove(1);
+        assert!(verify_chain(&events).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_integrity_chain_resumes_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = Arc::new(FileAuditSink::new(dir.path().join("audit.jsonl")));
+        let first = Keystore::new(Arc::new(InMemoryBackend::new()), Arc::new(IntegrityChainSink::new(log.clone())));
+        let id = first.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        first.activate(&id).await.unwrap();
+        first.flush_audit();
+
+        // A restart that resumes from the log keeps one unbroken chain.
+        let last = log.read_events().unwrap().pop().unwrap();
+        let resumed = Keystore::new(
+            Arc::new(InMemoryBackend::new()),
+            Arc::new(IntegrityChainSink::new(log.clone()).resume_after(&last)),
+        );
+        resumed.generate("other", KeyType::DataEncrypting, None, None).await.unwrap();
+        resumed.flush_audit();
+        let events = log.read_events().unwrap();
+        assert_eq!(verify_chain(&events), Ok(3));
+
+        // A second genesis, as left by cutting a segment's tail and
+        // splicing on another log, is rejected.
+        let mut spliced = events[..1].to_vec();
+        spliced.extend(events[..2].iter().cloned());
+        let brk = verify_chain(&spliced).unwrap_err();
+        assert_eq!(brk.index, 1);
+    }
+
+    #[tokio::test]
+    async fn test_ring_sink_drops_oldest_past_capacity() {
+        let ring = Arc::new(RingAuditSink::new(3));
+        let ks = Keystore::new(Arc::new(InMemoryBackend::new()), ring.clone());
+        let a = ks.generate("a", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&a).await.unwrap();
+        let b = ks.generate("b", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&b).await.unwrap();
+
+        // Four events recorded, three kept: a's generation was evicted.
+        assert_eq!(ring.len().await, 3);
+        let events = ring.events().await;
+        assert_eq!(events[0].key_id.as_ref(), Some(&a));
+        assert!(matches!(events[0].action, crate::audit::AuditAction::KeyActivated));
+        assert_eq!(ring.events_for_key(&a).await.len(), 1);
+        assert_eq!(ring.events_for_key(&b).await.len(), 2);
+
+        let empty = RingAuditSink::new(0);
+        empty.record(events[0].clone());
+        assert!(empty.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_composite_sink_fans_out_past_a_failing_sink() {
+        struct PanickingSink;
+        impl AuditSinkSync for PanickingSink {
+            fn record(&self, _: AuditEvent) {
+                panic!("siem unreachable");
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = Arc::new(FileAuditSink::new(dir.path().join("audit.jsonl")));
+        let memory = Arc::new(InMemoryAuditSink::new());
+        let composite = CompositeAuditSink::new(vec![file.clone(), Arc::new(PanickingSink)])
+            .with_sink(memory.clone());
+        assert_eq!(composite.len(), 3);
+
+        let ks = Keystore::new(Arc::new(InMemoryBackend::new()), Arc::new(IntegrityChainSink::new(Arc::new(composite))));
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+        ks.flush_audit();
+
+        let written = file.read_events().unwrap();
+        assert_eq!(written.len(), 2);
+        assert_eq!(verify_chain(&written), Ok(2));
+        let kept = memory.events().await;
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[1].sequence, written[1].sequence);
+    }
+
     // === List Operations ===
 
     #[tokio::test]
@@ -436,6 +1860,66 @@ mod tests {
         assert_eq!(pending.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_key_counts_match_listing() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_ks = Keystore::new(Arc::new(FileBackend::new(dir.path()).unwrap()), Arc::new(InMemoryAuditSink::new()));
+        for ks in [test_keystore(), file_ks] {
+            let id1 = ks.generate("key1", KeyType::DataEncrypting, None, None).await.unwrap();
+            let id2 = ks.generate("key2", KeyType::DataEncrypting, None, None).await.unwrap();
+            ks.generate("key3", KeyType::DataEncrypting, None, None).await.unwrap();
+            ks.activate(&id1).await.unwrap();
+            ks.activate(&id2).await.unwrap();
+            ks.revoke(&id2, "test").await.unwrap();
+
+            let counts = ks.key_counts().await.unwrap();
+            assert_eq!(counts.len(), 3);
+            assert_eq!(counts[&KeyState::Active], 1);
+            assert_eq!(counts[&KeyState::Pending], 1);
+            assert_eq!(counts[&KeyState::Revoked], 1);
+            assert!(!counts.contains_key(&KeyState::Destroyed));
+        }
+    }
+
+    // === Stored blobs ===
+
+    #[tokio::test]
+    async fn test_seal_and_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let stores: [Arc<dyn BlobStorageBackend>; 2] = [
+            Arc::new(InMemoryBlobBackend::new()),
+            Arc::new(FileBlobBackend::new(dir.path()).unwrap()),
+        ];
+        let aad = Aad::raw(b"aad");
+        let ctx = Context::raw(b"ctx");
+
+        for store in stores {
+            let ks = test_keystore().with_blob_store(store.clone());
+            let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+            ks.activate(&id).await.unwrap();
+
+            let blob_id = ks.seal_and_store(&id, b"stored secret", &aad, &ctx).await.unwrap();
+            assert_eq!(store.get_blob(&blob_id).unwrap().unwrap().key_id, id.as_str());
+            assert_eq!(ks.open_stored(&blob_id, &aad, &ctx).await.unwrap(), b"stored secret");
+            assert!(ks.open_stored(&blob_id, &Aad::raw(b"other"), &ctx).await.is_err());
+
+            ks.delete_stored(&blob_id).unwrap();
+            assert!(ks.open_stored(&blob_id, &aad, &ctx).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seal_and_store_without_store_fails() {
+        let ks = test_keystore();
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+
+        let err = ks.seal_and_store(&id, b"x", &Aad::raw(b""), &Context::raw(b"")).await.unwrap_err();
+        assert!(matches!(err, EncryptError::Keystore(KeystoreError::InvalidInput(_))));
+        // Refused before encrypting, so no usage was counted.
+        assert_eq!(ks.get(&id).await.unwrap().usage_count, 0);
+    }
+
     // === Encrypted Blob Serialization ===
 
     #[tokio::test]
@@ -472,22 +1956,22 @@ mod tests {
         let ctx = Context::raw(b"ctx");
         let blob = ks.encrypt(&id, b"important data", &aad, &ctx).await.unwrap();
 
-        // ACTIVE â†’ ROTATED â†’ ACTIVE (via rotate)
-        ks.rotate(&id).await.unwrap();
-        assert_eq!(ks.get(&id).await.unwrap().state, KeyState::Active);
-        assert_eq!(ks.get(&id).await.unwrap().current_version, 2);
+        // ACTIVE â†’ ROTATED, successor ACTIVE
+        let successor = ks.rotate(&id).await.unwrap();
+        assert_eq!(ks.get(&id).await.unwrap().state, KeyState::Rotated);
+        assert_eq!(ks.get(&successor).await.unwrap().state, KeyState::Active);
 
         // Old blob still decrypts
         let pt = ks.decrypt(&blob, &aad, &ctx).await.unwrap();
         assert_eq!(pt, b"important data");
 
         // ACTIVE â†’ REVOKED
-        ks.revoke(&id, "end of life").await.unwrap();
-        assert_eq!(ks.get(&id).await.unwrap().state, KeyState::Revoked);
+        ks.revoke(&successor, "end of life").await.unwrap();
+        assert_eq!(ks.get(&successor).await.unwrap().state, KeyState::Revoked);
 
         // REVOKED â†’ DESTROYED
-        ks.destroy(&id).await.unwrap();
-        assert_eq!(ks.get(&id).await.unwrap().state, KeyState::Destroyed);
+        ks.destroy(&successor).await.unwrap();
+        assert_eq!(ks.get(&successor).await.unwrap().state, KeyState::Destroyed);
     }
 
     // === Key Not Found ===
@@ -531,6 +2015,68 @@ mod tests {
         assert!(assessor.current_level() >= ThreatLevel::Elevated);
     }
 
+    #[test]
+    fn test_threat_event_log_outlives_the_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("threat.jsonl");
+        let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+        let mut assessor = ThreatAssessor::new(ThreatConfig {
+            thresholds: [5.0, 15.0, 30.0, 50.0],
+            ..Default::default()
+        })
+        .with_clock(clock.clone())
+        .with_event_log(ThreatEventLog::new(&path));
+
+        let failure = |n: u32| ThreatEvent::new(ThreatEventKind::DecryptionFailure, 3.0).with_detail(format!("attempt {}", n));
+        assessor.record_event(failure(1));
+        assessor.record_event(failure(1)); // duplicate: not logged
+        assessor.record_event(ThreatEvent::new(ThreatEventKind::Heartbeat, 0.0));
+        assert_eq!(assessor.record_events(vec![failure(2), failure(3)]), 0);
+
+        clock.advance(Duration::from_secs(2 * 3600));
+        assessor.tick();
+        assert_eq!(assessor.event_count(), 0);
+
+        let entries = ThreatEventLog::new(&path).read_entries().unwrap();
+        let details: Vec<_> = entries.iter().map(|e| e.event.detail.as_deref().unwrap()).collect();
+        assert_eq!(details, ["attempt 1", "attempt 2", "attempt 3"]);
+        assert_eq!(entries[0].score, 3.0);
+        assert_eq!(entries[0].level, ThreatLevel::Low);
+        assert_eq!(entries[2].score, 9.0);
+        assert_eq!(entries[2].level, ThreatLevel::Guarded);
+    }
+
+    #[test]
+    fn test_threat_change_hook_fires_once_per_change() {
+        let ks = Arc::new(test_keystore());
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (weak, log) = (Arc::downgrade(&ks), seen.clone());
+        ks.on_threat_change(Box::new(move |old, new| {
+            // Reading the level back would deadlock if the threat lock were held.
+            let now = weak.upgrade().map(|ks| ks.threat_level());
+            log.lock().unwrap().push((old, new, now));
+        }));
+
+        ks.record_threat_event(ThreatEvent::new(ThreatEventKind::ManualEscalation, 0.0));
+        ks.record_threat_event(ThreatEvent::new(ThreatEventKind::AnomalousAccess, 0.1));
+        ks.recompute_threat();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![(ThreatLevel::Low, ThreatLevel::Guarded, Some(ThreatLevel::Guarded))]
+        );
+
+        // Standalone assessors deliver queued changes on request.
+        let mut assessor = ThreatAssessor::new(ThreatConfig::default());
+        assessor.record_event(ThreatEvent::new(ThreatEventKind::ManualEscalation, 0.0));
+        assert!(assessor.take_level_changes().changes().is_empty(), "nothing queued without a hook");
+        assessor.on_level_change(Box::new(|_, _| {}));
+        assessor.record_event(ThreatEvent::new(ThreatEventKind::ManualEscalation, 0.0));
+        let changes = assessor.take_level_changes();
+        assert_eq!(changes.changes(), &[(ThreatLevel::Guarded, ThreatLevel::Elevated)]);
+        changes.fire();
+        assert!(assessor.take_level_changes().changes().is_empty());
+    }
+
     #[test]
     fn test_threat_manual_escalation() {
         let mut assessor = ThreatAssessor::new(ThreatConfig::default());
@@ -548,6 +2094,175 @@ mod tests {
         assert_eq!(assessor.current_level(), ThreatLevel::Low);
     }
 
+    #[test]
+    fn test_threat_manual_escalation_in_batch() {
+        let manual = |kind| ThreatEvent::new(kind, 0.0);
+        let mut assessor = ThreatAssessor::new(ThreatConfig::default());
+
+        // Each escalation steps from the level the previous one set, as
+        // with `record_event`.
+        let batch = vec![manual(ThreatEventKind::ManualEscalation), manual(ThreatEventKind::ManualEscalation)];
+        assert_eq!(assessor.record_events(batch), 0);
+        assert_eq!(assessor.current_level(), ThreatLevel::Elevated);
+        assert_eq!(assessor.snapshot().manual_override, Some(ThreatLevel::Elevated));
+
+        assert_eq!(assessor.record_events(vec![manual(ThreatEventKind::ManualDeescalation)]), 0);
+        assert_eq!(assessor.snapshot().manual_override, None);
+        assert_eq!(assessor.current_level(), ThreatLevel::Low);
+    }
+
+    #[test]
+    fn test_threat_ingestion_limit_and_coalesced_recompute() {
+        let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+        let mut assessor = ThreatAssessor::new(ThreatConfig {
+            thresholds: [5.0, 15.0, 30.0, 50.0],
+            ingest_burst: 3,
+            ingest_rate: 1.0,
+            recompute_interval: Duration::from_secs(60),
+            ..Default::default()
+        })
+        .with_clock(clock.clone());
+        let failure = || ThreatEvent::new(ThreatEventKind::DecryptionFailure, 3.0);
+
+        // The first event recomputes. Within the interval, the second still
+        // recomputes because it escalates; the third can't change the level
+        // and is folded into the next recompute.
+        assert!(assessor.record_event(failure()));
+        assert_eq!(assessor.current_level(), ThreatLevel::Low);
+        assert!(assessor.record_event(failure()));
+        assert_eq!(assessor.current_level(), ThreatLevel::Guarded);
+        assert_eq!(assessor.level_history().last().unwrap().1, ThreatLevel::Guarded);
+        assert!(assessor.record_event(failure()));
+        assert_eq!(assessor.current_level(), ThreatLevel::Guarded);
+
+        // Bucket is empty: events are refused until it refills; manual
+        // escalation is never limited.
+        assert!(!assessor.record_event(failure()));
+        assert_eq!(assessor.record_events(vec![failure(), failure()]), 2);
+        assert!(assessor.record_event(ThreatEvent::new(ThreatEventKind::ManualEscalation, 0.0)));
+        assert_eq!(
+            assessor.record_events(vec![ThreatEvent::new(ThreatEventKind::ManualDeescalation, 0.0), failure()]),
+            1
+        );
+        assert_eq!(assessor.dropped_events(), 4);
+        assert_eq!(assessor.event_count(), 5);
+
+        clock.advance(Duration::from_secs(2));
+        assert!(assessor.record_event(failure()));
+        assert!(assessor.record_event(failure()));
+        assert!(!assessor.record_event(failure()));
+
+        assert!(ThreatConfig { ingest_rate: 0.0, ..Default::default() }.validate().is_err());
+        assert!(ThreatConfig { ingest_burst: 0, ingest_rate: 0.0, ..Default::default() }.validate().is_ok());
+    }
+
+    #[test]
+    fn test_threat_heartbeat_recomputes_without_adding_events() {
+        let mut assessor = ThreatAssessor::new(ThreatConfig::default());
+        assessor.record_event(ThreatEvent::new(ThreatEventKind::DecryptionFailure, 3.0));
+        let score = assessor.raw_score();
+
+        assessor.record_event(ThreatEvent::new(ThreatEventKind::Heartbeat, 10.0));
+        assessor.record_events(vec![ThreatEvent::new(ThreatEventKind::Heartbeat, 10.0)]);
+        assert_eq!(assessor.event_count(), 1);
+        assert!(assessor.raw_score() <= score);
+    }
+
+    #[test]
+    fn test_threat_history_starts_at_injected_clock() {
+        let start = chrono::Utc::now() - chrono::Duration::days(30);
+        let clock = Arc::new(MockClock::new(start));
+        let mut assessor = ThreatAssessor::new(ThreatConfig::default()).with_clock(clock.clone());
+        assert_eq!(assessor.level_history()[0].0, start);
+
+        clock.advance(Duration::from_secs(60));
+        for _ in 0..3 {
+            let mut event = ThreatEvent::new(ThreatEventKind::ExternalAdvisory, 5.0);
+            event.timestamp = clock.now();
+            assessor.record_event(event);
+        }
+        let history = assessor.level_history();
+        assert!(history.len() > 1);
+        assert!(history.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+
+    #[test]
+    fn test_threat_tick_decays_elevated_to_low() {
+        let clock = Arc::new(MockClock::default());
+        let mut assessor = ThreatAssessor::new(ThreatConfig::default()).with_clock(clock.clone());
+
+        for _ in 0..3 {
+            let mut event = ThreatEvent::new(ThreatEventKind::ExternalAdvisory, 5.0);
+            event.timestamp = clock.now();
+            assessor.record_event(event);
+        }
+        assert_eq!(assessor.current_level(), ThreatLevel::Elevated);
+
+        // Without a tick the cached level stays put
+        clock.advance(ThreatConfig::default().window + Duration::from_secs(60));
+        assert_eq!(assessor.current_level(), ThreatLevel::Elevated);
+
+        assessor.tick();
+        assert_eq!(assessor.current_level(), ThreatLevel::Low);
+        assert_eq!(assessor.event_count(), 0);
+    }
+
+    #[test]
+    fn test_threat_dedups_repeated_detail_within_window() {
+        let clock = Arc::new(MockClock::default());
+        let mut assessor = ThreatAssessor::new(ThreatConfig::default()).with_clock(clock.clone());
+        let event = |detail: Option<&str>| {
+            let mut e = ThreatEvent::new(ThreatEventKind::AuthFailure, 1.0);
+            e.timestamp = clock.now();
+            e.detail = detail.map(String::from);
+            e
+        };
+
+        assessor.record_events(vec![event(Some("bad key from 10.0.0.5")); 50]);
+        assessor.record_event(event(Some("bad key from 10.0.0.5")));
+        assert_eq!(assessor.event_count(), 1);
+
+        // Distinct details and detail-less events all count.
+        assessor.record_event(event(Some("bad key from 10.0.0.6")));
+        assessor.record_event(event(None));
+        assessor.record_event(event(None));
+        assert_eq!(assessor.event_count(), 4);
+
+        clock.advance(ThreatConfig::default().dedup_window + Duration::from_secs(1));
+        assessor.record_event(event(Some("bad key from 10.0.0.5")));
+        assert_eq!(assessor.event_count(), 5);
+
+        // Events the keystore observed itself are never deduplicated.
+        assessor.record_observed(event(Some("bad key from 10.0.0.5")));
+        assessor.record_observed(event(Some("bad key from 10.0.0.5")));
+        assert_eq!(assessor.event_count(), 7);
+    }
+
+    #[test]
+    fn test_threat_duplicates_do_not_spend_ingestion_tokens() {
+        let clock = Arc::new(MockClock::default());
+        let mut assessor = ThreatAssessor::new(ThreatConfig {
+            ingest_burst: 2,
+            ingest_rate: 0.001,
+            ..Default::default()
+        })
+        .with_clock(clock.clone());
+        let event = |detail: &str| {
+            let mut e = ThreatEvent::new(ThreatEventKind::AuthFailure, 1.0).with_detail(detail);
+            e.timestamp = clock.now();
+            e
+        };
+
+        assert_eq!(assessor.record_events(vec![event("a"); 20]), 0);
+        assert!(assessor.record_event(event("b")));
+        assert_eq!(assessor.dropped_events(), 0);
+        assert!(!assessor.record_event(event("c")));
+
+        // A drained bucket doesn't hold back observed events.
+        assessor.record_observed(ThreatEvent::new(ThreatEventKind::KeyCompromise, 10.0));
+        assert_eq!(assessor.event_count(), 3);
+    }
+
     #[test]
     fn test_threat_level_display() {
         assert_eq!(ThreatLevel::Low.label(), "LOW");
@@ -645,6 +2360,29 @@ mod tests {
         assert!(summary.effective_grace_period < summary.base_grace_period);
     }
 
+    #[tokio::test]
+    async fn test_effective_policy_summary_for_key() {
+        let ks = test_keystore();
+        ks.register_policy(KeyPolicy::default_dek());
+        let with = ks.generate("dek", KeyType::DataEncrypting, Some(PolicyId::new("default-dek")), None).await.unwrap();
+        let without = ks.generate("bare", KeyType::DataEncrypting, None, None).await.unwrap();
+        let dangling = ks.generate("dangling", KeyType::DataEncrypting, Some(PolicyId::new("nope")), None).await.unwrap();
+
+        for _ in 0..20 {
+            ks.record_threat_event(ThreatEvent::new(ThreatEventKind::ExternalAdvisory, 8.0));
+        }
+        let summary = ks.effective_policy_summary(&with).await.unwrap().unwrap();
+        assert_eq!(summary.threat_level, ks.threat_level());
+        assert!(summary.effective_grace_period < summary.base_grace_period);
+
+        assert!(ks.effective_policy_summary(&without).await.unwrap().is_none());
+        assert!(ks.effective_policy_summary(&dangling).await.unwrap().is_none());
+        assert!(matches!(
+            ks.effective_policy_summary(&KeyId::new("missing")).await,
+            Err(KeystoreError::KeyNotFound(_))
+        ));
+    }
+
     // === Keystore + Threat Integration Tests ===
 
     #[tokio::test]
@@ -662,9 +2400,31 @@ mod tests {
         assert!(ks.threat_score() > 0.0);
     }
 
+    #[tokio::test]
+    async fn test_drained_ingestion_limit_does_not_suppress_compromise() {
+        let ks = Keystore::with_threat_config(
+            Arc::new(InMemoryBackend::new()),
+            Arc::new(InMemoryAuditSink::new()),
+            ThreatConfig { ingest_burst: 2, ingest_rate: 0.001, ..ThreatConfig::default() },
+        );
+        let id = ks.generate("key", KeyType::DataEncrypting, None, None).await.unwrap();
+        ks.activate(&id).await.unwrap();
+
+        for i in 0..3 {
+            ks.record_threat_event(
+                ThreatEvent::new(ThreatEventKind::RapidAccessPattern, 0.3).with_detail(format!("flood {}", i)),
+            );
+        }
+        assert!(!ks.record_threat_event(ThreatEvent::new(ThreatEventKind::AuthFailure, 0.5)));
+
+        ks.compromise(&id, "leaked").await.unwrap();
+        assert!(ks.recent_threat_events(10).iter().any(|e| e.kind == ThreatEventKind::KeyCompromise));
+        assert!(ks.threat_level() >= ThreatLevel::Guarded);
+    }
+
     #[tokio::test]
     async fn test_keystore_threat_escalation_tightens_policy() {
-        let mut ks = test_keystore();
+        let ks = test_keystore();
         ks.register_policy(KeyPolicy::default_dek());
 
         let id = ks.generate(
@@ -716,9 +2476,44 @@ mod tests {
         assert!(ks.threat_history().len() >= 2);
     }
 
+    #[tokio::test]
+    async fn test_recent_threat_events_newest_first() {
+        let ks = test_keystore();
+        assert!(ks.recent_threat_events(10).is_empty());
+
+        ks.record_threat_event(ThreatEvent::new(ThreatEventKind::AuthFailure, 1.0).with_detail("ip=10.0.0.1"));
+        ks.record_threat_event(ThreatEvent::new(ThreatEventKind::DecryptionFailure, 2.0));
+        ks.record_threat_event(ThreatEvent::new(ThreatEventKind::KeyEnumeration, 3.0));
+
+        let recent = ks.recent_threat_events(2);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].kind, ThreatEventKind::KeyEnumeration);
+        assert_eq!(recent[1].kind, ThreatEventKind::DecryptionFailure);
+
+        let all = ks.recent_threat_events(10);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[2].detail.as_deref(), Some("ip=10.0.0.1"));
+        assert_eq!(all[2].severity, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_threat_snapshot_restore() {
+        let ks = test_keystore();
+        ks.record_threat_event(ThreatEvent::new(ThreatEventKind::ManualEscalation, 0.0));
+        ks.record_threat_event(ThreatEvent::new(ThreatEventKind::DecryptionFailure, 3.0));
+
+        let snapshot = ks.threat_snapshot();
+        let json = serde_json::to_string(&snapshot).unwrap();
+
+        let restored = test_keystore();
+        restored.restore_threat_snapshot(serde_json::from_str(&json).unwrap());
+        assert_eq!(restored.threat_level(), ThreatLevel::Guarded);
+        assert!(restored.threat_score() > 0.0);
+    }
+
     #[tokio::test]
     async fn test_adaptive_policy_evaluation() {
-        let mut ks = test_keystore();
+        let ks = test_keystore();
 
         let mut dek_policy = KeyPolicy::default_dek();
         dek_policy.max_usage_count = Some(1000);
@@ -734,4 +2529,77 @@ mod tests {
         let verdict = ks.evaluate_adaptive_policy(&id).await.unwrap();
         assert!(matches!(verdict, PolicyVerdict::Compliant));
     }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_keystore_without_a_runtime() {
+        let ks = BlockingKeystore::new(test_keystore()).unwrap();
+        ks.keystore().register_policy(KeyPolicy::default_dek());
+
+        let id = ks.generate("cli-dek", KeyType::DataEncrypting, Some(PolicyId::new("default-dek")), None).unwrap();
+        ks.activate(&id).unwrap();
+        let aad = Aad::raw(b"aad");
+        let ctx = Context::raw(b"ctx");
+        let blob = ks.encrypt(&id, b"provisioned", &aad, &ctx).unwrap();
+        assert_eq!(ks.decrypt(&blob, &aad, &ctx).unwrap(), b"provisioned");
+
+        ks.revoke(&id, "done").unwrap();
+        assert_eq!(ks.get(&id).unwrap().state, KeyState::Revoked);
+    }
+
+    #[cfg(feature = "otlp")]
+    #[test]
+    fn test_otlp_sink_posts_log_records() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut req = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = conn.read(&mut buf).unwrap();
+                req.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&req);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let len: usize = text
+                        .lines()
+                        .find_map(|l| l.strip_prefix("Content-Length: "))
+                        .unwrap()
+                        .parse()
+                        .unwrap();
+                    if req.len() >= end + 4 + len {
+                        conn.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+                        return String::from_utf8(req[end + 4..].to_vec()).unwrap();
+                    }
+                }
+            }
+        });
+
+        assert!(OtlpAuditSink::new(OtlpConfig::new("https://collector:4318")).is_err());
+        let sink = OtlpAuditSink::new(OtlpConfig::new(format!("http://127.0.0.1:{}", port))).unwrap();
+        let id = KeyId::new("otlp-key");
+        sink.record(
+            AuditEvent::key_event(&id, KeyType::DataEncrypting, KeyState::Active, audit::AuditAction::KeyRotated { new_version: 2 })
+                .with_actor("alice"),
+        );
+        sink.flush();
+
+        let body: serde_json::Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+        let record = &body["resourceLogs"][0]["scopeLogs"][0]["logRecords"][0];
+        let attr = |k: &str| {
+            record["attributes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|a| a["key"] == k)
+                .map(|a| a["value"].clone())
+                .unwrap()
+        };
+        assert_eq!(attr("citadel.key_id")["stringValue"], "otlp-key");
+        assert_eq!(attr("citadel.action")["stringValue"], "KeyRotated");
+        assert_eq!(attr("citadel.actor")["stringValue"], "alice");
+        assert_eq!(sink.dropped(), 0);
+    }
 }
@@ -0,0 +1,91 @@
+//! Operational latency metrics for `encrypt` / `decrypt`.
+//!
+//! Durations are bucketed log-linearly (four sub-buckets per power of two
+//! microseconds), so recording is O(1), memory is fixed, and reported
+//! percentiles are within ~25% of the true value. Percentiles report the
+//! upper bound of the bucket they fall in.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const BUCKETS: usize = 252;
+
+/// Latency percentiles for keystore crypto operations, since startup.
+///
+/// Only successful operations are recorded; early rejections (wrong state,
+/// policy block, bad input) would otherwise drag the percentiles down.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationMetrics {
+    /// Successful `encrypt` calls recorded.
+    pub encrypt_count: u64,
+    pub encrypt_p50: Duration,
+    pub encrypt_p99: Duration,
+    pub encrypt_max: Duration,
+    /// Successful decrypts recorded (`decrypt` and each `decrypt_batch` item).
+    pub decrypt_count: u64,
+    pub decrypt_p50: Duration,
+    pub decrypt_p99: Duration,
+    pub decrypt_max: Duration,
+}
+
+pub(crate) struct LatencyHistogram {
+    counts: [u64; BUCKETS],
+    total: u64,
+    max: Duration,
+}
+
+impl LatencyHistogram {
+    pub(crate) fn new() -> Self {
+        Self { counts: [0; BUCKETS], total: 0, max: Duration::ZERO }
+    }
+
+    pub(crate) fn record(&mut self, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.counts[bucket_of(micros)] += 1;
+        self.total += 1;
+        self.max = self.max.max(elapsed);
+    }
+
+    pub(crate) fn count(&self) -> u64 {
+        self.total
+    }
+
+    pub(crate) fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Upper bound of the bucket holding the `q`-quantile (0.0–1.0),
+    /// capped at the largest sample seen. Zero when empty.
+    pub(crate) fn percentile(&self, q: f64) -> Duration {
+        if self.total == 0 {
+            return Duration::ZERO;
+        }
+        let rank = ((q.clamp(0.0, 1.0) * self.total as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Duration::from_micros(bucket_upper(bucket)).min(self.max);
+            }
+        }
+        self.max
+    }
+}
+
+fn bucket_of(micros: u64) -> usize {
+    if micros < 4 {
+        return micros as usize;
+    }
+    let exp = 63 - micros.leading_zeros() as usize;
+    let sub = ((micros >> (exp - 2)) & 0b11) as usize;
+    (exp - 1) * 4 + sub
+}
+
+fn bucket_upper(bucket: usize) -> u64 {
+    if bucket < 4 {
+        return bucket as u64;
+    }
+    let exp = bucket / 4 + 1;
+    let sub = (bucket % 4) as u128;
+    u64::try_from(((5 + sub) << (exp - 2)) - 1).unwrap_or(u64::MAX)
+}
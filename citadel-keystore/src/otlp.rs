@@ -0,0 +1,268 @@
+//! OTLP audit sink: ships audit events to an OpenTelemetry collector as log records.
+//!
+//! Enabled with the `otlp` feature. Events go over OTLP/HTTP with JSON
+//! encoding (`POST /v1/logs`), so no gRPC or protobuf stack is pulled in.
+//! Only plain `http://` endpoints are supported; run the collector on
+//! localhost or behind a sidecar if the hop needs TLS.
+
+use crate::audit::{AuditEvent, AuditSinkSync};
+use crate::error::KeystoreError;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Settings for [`OtlpAuditSink`].
+#[derive(Clone, Debug)]
+pub struct OtlpConfig {
+    /// Collector endpoint, e.g. `http://localhost:4318`. A bare host:port
+    /// gets the standard `/v1/logs` path.
+    pub endpoint: String,
+    /// `service.name` resource attribute.
+    pub service_name: String,
+    /// Events buffered between `record` and the exporter thread. When
+    /// full, new events are dropped and counted rather than blocking.
+    pub queue_capacity: usize,
+    /// Maximum log records per export request.
+    pub max_batch: usize,
+    /// How long a partial batch may wait before it is sent.
+    pub flush_interval: Duration,
+    /// Connect / read / write timeout for each export request.
+    pub timeout: Duration,
+}
+
+impl OtlpConfig {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            service_name: "citadel-keystore".into(),
+            queue_capacity: 4096,
+            max_batch: 256,
+            flush_interval: Duration::from_secs(2),
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+enum Message {
+    Event(AuditEvent),
+    Flush(SyncSender<()>),
+}
+
+/// Forwards audit events to an OTLP/HTTP collector.
+///
+/// `record` never blocks: it enqueues into a bounded channel drained by a
+/// background thread, which batches events and posts them as log records.
+/// Each record carries `citadel.key_id`, `citadel.action`, `citadel.actor`
+/// and `citadel.success` attributes; the body is the event's JSON.
+pub struct OtlpAuditSink {
+    tx: SyncSender<Message>,
+    dropped: Arc<AtomicU64>,
+    timeout: Duration,
+}
+
+impl OtlpAuditSink {
+    /// Start the exporter thread for `config`.
+    pub fn new(config: OtlpConfig) -> Result<Self, KeystoreError> {
+        if config.queue_capacity == 0 || config.max_batch == 0 {
+            return Err(KeystoreError::InvalidInput(
+                "otlp queue_capacity and max_batch must be non-zero".into(),
+            ));
+        }
+        let target = Target::parse(&config.endpoint)?;
+        let (tx, rx) = mpsc::sync_channel(config.queue_capacity);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let timeout = config.timeout;
+
+        std::thread::Builder::new()
+            .name("citadel-otlp-audit".into())
+            .spawn(move || run_exporter(rx, target, config))
+            .map_err(|e| KeystoreError::StorageError(format!("cannot start otlp exporter: {}", e)))?;
+
+        Ok(Self { tx, dropped, timeout })
+    }
+
+    /// Events dropped because the queue was full or the exporter had stopped.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl AuditSinkSync for OtlpAuditSink {
+    fn record(&self, event: AuditEvent) {
+        match self.tx.try_send(Message::Event(event)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Wait (up to the request timeout) for queued events to be exported.
+    fn flush(&self) {
+        let (ack_tx, ack_rx) = mpsc::sync_channel(1);
+        if self.tx.send(Message::Flush(ack_tx)).is_ok() {
+            let _ = ack_rx.recv_timeout(self.timeout);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Exporter thread
+// ---------------------------------------------------------------------------
+
+fn run_exporter(rx: Receiver<Message>, target: Target, config: OtlpConfig) {
+    let mut batch = Vec::with_capacity(config.max_batch);
+    loop {
+        match rx.recv_timeout(config.flush_interval) {
+            Ok(Message::Event(event)) => {
+                batch.push(event);
+                if batch.len() >= config.max_batch {
+                    export(&target, &config, &mut batch);
+                }
+            }
+            Ok(Message::Flush(ack)) => {
+                export(&target, &config, &mut batch);
+                let _ = ack.send(());
+            }
+            Err(RecvTimeoutError::Timeout) => export(&target, &config, &mut batch),
+            Err(RecvTimeoutError::Disconnected) => {
+                export(&target, &config, &mut batch);
+                return;
+            }
+        }
+    }
+}
+
+fn export(target: &Target, config: &OtlpConfig, batch: &mut Vec<AuditEvent>) {
+    if batch.is_empty() {
+        return;
+    }
+    let body = encode_logs(&config.service_name, batch).to_string();
+    if let Err(e) = target.post(body.as_bytes(), config.timeout) {
+        eprintln!("[audit] otlp export of {} events failed: {}", batch.len(), e);
+    }
+    batch.clear();
+}
+
+/// Build an OTLP `ExportLogsServiceRequest` (JSON encoding) for `events`.
+pub fn encode_logs(service_name: &str, events: &[AuditEvent]) -> Value {
+    let records: Vec<Value> = events.iter().map(log_record).collect();
+    json!({
+        "resourceLogs": [{
+            "resource": { "attributes": [string_attr("service.name", service_name)] },
+            "scopeLogs": [{
+                "scope": { "name": "citadel-keystore.audit" },
+                "logRecords": records,
+            }],
+        }],
+    })
+}
+
+fn log_record(event: &AuditEvent) -> Value {
+    let nanos = event
+        .timestamp
+        .timestamp_nanos_opt()
+        .unwrap_or_default()
+        .max(0);
+    let (severity_number, severity_text) = if event.success { (9, "INFO") } else { (13, "WARN") };
+
+    let mut attributes = vec![
+        string_attr("citadel.action", &action_name(event)),
+        string_attr("citadel.actor", &event.actor),
+        json!({ "key": "citadel.success", "value": { "boolValue": event.success } }),
+    ];
+    if let Some(id) = &event.key_id {
+        attributes.push(string_attr("citadel.key_id", id.as_str()));
+    }
+    if let Some(seq) = event.sequence {
+        // OTLP/JSON encodes 64-bit integers as strings.
+        attributes.push(json!({ "key": "citadel.sequence", "value": { "intValue": seq.to_string() } }));
+    }
+
+    json!({
+        "timeUnixNano": nanos.to_string(),
+        "severityNumber": severity_number,
+        "severityText": severity_text,
+        "body": { "stringValue": serde_json::to_string(event).unwrap_or_default() },
+        "attributes": attributes,
+    })
+}
+
+fn string_attr(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// Variant name of the event's action (`"KeyRotated"`, not its fields).
+fn action_name(event: &AuditEvent) -> String {
+    match serde_json::to_value(&event.action) {
+        Ok(Value::String(name)) => name,
+        Ok(Value::Object(map)) => map.keys().next().cloned().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Minimal HTTP/1.1 client
+// ---------------------------------------------------------------------------
+
+struct Target {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Target {
+    fn parse(endpoint: &str) -> Result<Self, KeystoreError> {
+        let invalid = |why: &str| KeystoreError::InvalidInput(format!("otlp endpoint {:?}: {}", endpoint, why));
+        let rest = endpoint
+            .strip_prefix("http://")
+            .ok_or_else(|| invalid("only http:// endpoints are supported"))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) if i + 1 < rest.len() => (&rest[..i], rest[i..].to_string()),
+            Some(i) => (&rest[..i], "/v1/logs".to_string()),
+            None => (rest, "/v1/logs".to_string()),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((h, p)) => (h, p.parse().map_err(|_| invalid("bad port"))?),
+            None => (authority, 4318),
+        };
+        if host.is_empty() {
+            return Err(invalid("missing host"));
+        }
+        Ok(Self { host: host.to_string(), port, path })
+    }
+
+    fn post(&self, body: &[u8], timeout: Duration) -> std::io::Result<()> {
+        let addr = (self.host.as_str(), self.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no address"))?;
+        let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path,
+            self.host,
+            self.port,
+            body.len()
+        )?;
+        stream.write_all(body)?;
+        stream.flush()?;
+
+        let mut head = [0u8; 12];
+        stream.read_exact(&mut head)?;
+        let status = std::str::from_utf8(&head[9..12]).unwrap_or("");
+        if status.starts_with('2') {
+            Ok(())
+        } else {
+            Err(std::io::Error::other(format!("collector returned HTTP {}", status)))
+        }
+    }
+}
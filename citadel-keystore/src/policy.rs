@@ -1,7 +1,8 @@
 //! Policy engine: defines when and how keys rotate, expire, and age out.
 
+use crate::error::KeystoreError;
 use crate::types::{KeyMetadata, KeyState, KeyType, PolicyId};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -26,6 +27,13 @@ pub enum RotationTrigger {
 // Key policy
 // ---------------------------------------------------------------------------
 
+/// Default [`KeyPolicy::warn_fraction`]: warn at 90% of a limit.
+pub const DEFAULT_WARN_FRACTION: f64 = 0.9;
+
+fn default_warn_fraction() -> f64 {
+    DEFAULT_WARN_FRACTION
+}
+
 /// Policy governing key lifecycle.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct KeyPolicy {
@@ -41,12 +49,22 @@ pub struct KeyPolicy {
     pub rotation_grace_period: Duration,
     /// Maximum total lifetime for an ACTIVE key (None = unlimited).
     pub max_lifetime: Option<Duration>,
-    /// Maximum encryption operations (None = unlimited).
+    /// Maximum encryption operations per key version (None = unlimited).
+    /// Rotating starts a fresh count on the successor.
     pub max_usage_count: Option<u64>,
     /// Whether to auto-rotate when triggers fire.
     pub auto_rotate: bool,
-    /// Minimum number of old versions to retain before destruction.
+    /// Minimum number of retired versions each key lineage (a key and the
+    /// keys it was rotated from or into) keeps before destruction.
     pub min_versions_retained: u32,
+    /// How long a retired (EXPIRED, REVOKED or COMPROMISED) key is kept
+    /// before `Keystore::sweep_destroyable` destroys it (None = forever).
+    #[serde(default)]
+    pub destroy_after: Option<Duration>,
+    /// Fraction of a limit (rotation age, usage count, grace period, max
+    /// lifetime) at which warnings start, in (0, 1).
+    #[serde(default = "default_warn_fraction")]
+    pub warn_fraction: f64,
 }
 
 impl KeyPolicy {
@@ -62,6 +80,8 @@ impl KeyPolicy {
             max_usage_count: None,
             auto_rotate: false,
             min_versions_retained: 3,
+            destroy_after: None,
+            warn_fraction: DEFAULT_WARN_FRACTION,
         }
     }
 
@@ -77,10 +97,55 @@ impl KeyPolicy {
             max_usage_count: None,
             auto_rotate: false,
             min_versions_retained: 5,
+            destroy_after: None,
+            warn_fraction: DEFAULT_WARN_FRACTION,
         }
     }
 }
 
+impl KeyPolicy {
+    /// Reject policies that cannot be enforced sensibly.
+    ///
+    /// A zero grace period would expire ROTATED keys immediately, making
+    /// data encrypted under the previous version undecryptable.
+    pub fn validate(&self) -> Result<(), KeystoreError> {
+        if self.id.as_str().is_empty() {
+            return Err(KeystoreError::PolicyViolation("policy id must not be empty".into()));
+        }
+        if self.rotation_grace_period.is_zero() {
+            return Err(KeystoreError::PolicyViolation(format!(
+                "policy {}: rotation_grace_period must be non-zero",
+                self.id
+            )));
+        }
+        if self.max_lifetime.is_some_and(|d| d.is_zero()) {
+            return Err(KeystoreError::PolicyViolation(format!(
+                "policy {}: max_lifetime must be non-zero",
+                self.id
+            )));
+        }
+        if self.destroy_after.is_some_and(|d| d.is_zero()) {
+            return Err(KeystoreError::PolicyViolation(format!(
+                "policy {}: destroy_after must be non-zero",
+                self.id
+            )));
+        }
+        if self.max_usage_count == Some(0) {
+            return Err(KeystoreError::PolicyViolation(format!(
+                "policy {}: max_usage_count must be non-zero",
+                self.id
+            )));
+        }
+        if !(self.warn_fraction > 0.0 && self.warn_fraction < 1.0) {
+            return Err(KeystoreError::PolicyViolation(format!(
+                "policy {}: warn_fraction must be in (0, 1), got {}",
+                self.id, self.warn_fraction
+            )));
+        }
+        Ok(())
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Policy evaluator
 // ---------------------------------------------------------------------------
@@ -106,28 +171,33 @@ impl PolicyVerdict {
 
 /// Evaluate a policy against a key's current metadata.
 pub fn evaluate(policy: &KeyPolicy, key: &KeyMetadata) -> PolicyVerdict {
+    evaluate_at(policy, key, Utc::now())
+}
+
+/// Like [`evaluate`], but measures key age against `now` instead of the wall clock.
+pub fn evaluate_at(policy: &KeyPolicy, key: &KeyMetadata, now: DateTime<Utc>) -> PolicyVerdict {
     // Only evaluate active keys for rotation
     if key.state != KeyState::Active {
         return PolicyVerdict::Compliant;
     }
 
-    // Check usage count limit
+    // Check usage count limit (per version)
     if let Some(max_count) = policy.max_usage_count {
-        if key.usage_count >= max_count {
+        let usage = key.current_usage();
+        if usage >= max_count {
             return PolicyVerdict::UsageLimitExceeded {
-                count: key.usage_count,
+                count: usage,
                 limit: max_count,
             };
         }
-        // Warn at 90%
-        let threshold = (max_count as f64 * 0.9) as u64;
-        if key.usage_count >= threshold {
+        let threshold = (max_count as f64 * policy.warn_fraction) as u64;
+        if usage >= threshold {
             return PolicyVerdict::Warning {
                 reason: format!(
                     "usage {}/{} ({}%)",
-                    key.usage_count,
+                    usage,
                     max_count,
-                    key.usage_count * 100 / max_count
+                    usage * 100 / max_count
                 ),
             };
         }
@@ -135,7 +205,7 @@ pub fn evaluate(policy: &KeyPolicy, key: &KeyMetadata) -> PolicyVerdict {
 
     // Check age-based triggers
     if let Some(activated) = key.activated_at {
-        let age = Utc::now() - activated;
+        let age = now - activated;
         for trigger in &policy.rotation_triggers {
             if let RotationTrigger::Age(max_age) = trigger {
                 let max_age_chrono = chrono::Duration::from_std(*max_age).unwrap_or(chrono::Duration::MAX);
@@ -144,9 +214,8 @@ pub fn evaluate(policy: &KeyPolicy, key: &KeyMetadata) -> PolicyVerdict {
                         reason: format!("age {} exceeds max {}", format_duration(age), format_std_duration(*max_age)),
                     };
                 }
-                // Warn at 90%
                 let warn_threshold = chrono::Duration::from_std(Duration::from_secs(
-                    (max_age.as_secs() as f64 * 0.9) as u64
+                    (max_age.as_secs() as f64 * policy.warn_fraction) as u64
                 )).unwrap_or(chrono::Duration::MAX);
                 if age >= warn_threshold {
                     return PolicyVerdict::Warning {
@@ -1,7 +1,8 @@
 //! Storage backends: where key metadata and material live.
 
 use crate::error::KeystoreError;
-use crate::types::{KeyId, KeyMetadata, KeyState};
+use crate::keystore::EncryptedBlob;
+use crate::types::{BlobId, KeyId, KeyMetadata, KeyState};
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -18,6 +19,9 @@ use std::sync::RwLock;
 /// - FileBackend (development)
 /// - Your database (production)
 /// - HSM wrapper (compliance)
+///
+/// Backends that persist serialized metadata should call
+/// [`KeyMetadata::migrate`] on every record they read back.
 pub trait StorageBackend: Send + Sync {
     fn get(&self, id: &KeyId) -> Result<Option<KeyMetadata>, KeystoreError>;
     fn put(&self, meta: &KeyMetadata) -> Result<(), KeystoreError>;
@@ -25,6 +29,18 @@ pub trait StorageBackend: Send + Sync {
     fn list(&self) -> Result<Vec<KeyMetadata>, KeystoreError>;
     fn list_by_state(&self, state: KeyState) -> Result<Vec<KeyMetadata>, KeystoreError>;
     fn list_by_parent(&self, parent_id: &KeyId) -> Result<Vec<KeyMetadata>, KeystoreError>;
+
+    /// Number of keys in each state; states with no keys are absent.
+    ///
+    /// The default walks `list()`. Backends that can aggregate in place
+    /// (a SQL `GROUP BY state`) should override it.
+    fn count_by_state(&self) -> Result<HashMap<KeyState, usize>, KeystoreError> {
+        let mut counts = HashMap::new();
+        for meta in self.list()? {
+            *counts.entry(meta.state).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -82,10 +98,20 @@ impl StorageBackend for InMemoryBackend {
         let keys = self.keys.read().unwrap();
         Ok(keys
             .values()
-            .filter(|k| k.parent_id.as_ref() == Some(parent_id))
+            .filter(|k| KeyId::ct_matches(k.parent_id.as_ref(), parent_id))
             .cloned()
             .collect())
     }
+
+    /// One pass under the read lock, without cloning any metadata.
+    fn count_by_state(&self) -> Result<HashMap<KeyState, usize>, KeystoreError> {
+        let keys = self.keys.read().unwrap();
+        let mut counts = HashMap::new();
+        for meta in keys.values() {
+            *counts.entry(meta.state).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -97,12 +123,23 @@ impl StorageBackend for InMemoryBackend {
 /// Directory layout:
 /// ```text
 /// keys/
+///   .lock            advisory write lock
 ///   {key_id}.json
 /// ```
+///
+/// `put` and `delete` hold an exclusive advisory lock on `.lock`, so any
+/// number of processes or `FileBackend` instances on one host can write
+/// the same directory without interleaving files. Reads take no lock;
+/// writes land by atomic rename. The lock does not make a caller's
+/// read-modify-write atomic: two keystores updating the same key still
+/// resolve last-writer-wins. On NFS, locking relies on the server's
+/// lock manager (`lockd` / NFSv4).
 pub struct FileBackend {
     dir: PathBuf,
 }
 
+const LOCK_FILE: &str = ".lock";
+
 impl FileBackend {
     pub fn new(dir: impl Into<PathBuf>) -> Result<Self, KeystoreError> {
         let dir = dir.into();
@@ -111,32 +148,86 @@ impl FileBackend {
         Ok(Self { dir })
     }
 
-    fn key_path(&self, id: &KeyId) -> PathBuf {
-        self.dir.join(format!("{}.json", id.as_str()))
+    /// Exclusive lock on the directory's `.lock` file, released on drop.
+    fn write_lock(&self) -> Result<std::fs::File, KeystoreError> {
+        use fs2::FileExt;
+
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join(LOCK_FILE))
+            .map_err(|e| KeystoreError::StorageError(format!("open lock: {}", e)))?;
+        file.lock_exclusive()
+            .map_err(|e| KeystoreError::StorageError(format!("lock: {}", e)))?;
+        Ok(file)
+    }
+
+    /// Refuses IDs that `KeyId::parse` would, so none can escape `dir`.
+    fn key_path(&self, id: &KeyId) -> Result<PathBuf, KeystoreError> {
+        let id = KeyId::parse(id.as_str())?;
+        Ok(self.dir.join(format!("{}.json", id.as_str())))
     }
 
     fn read_key_file(&self, path: &Path) -> Result<KeyMetadata, KeystoreError> {
         let data = std::fs::read_to_string(path)
             .map_err(|e| KeystoreError::StorageError(format!("read: {}", e)))?;
-        serde_json::from_str(&data)
-            .map_err(|e| KeystoreError::StorageError(format!("parse: {}", e)))
+        let mut meta: KeyMetadata = serde_json::from_str(&data)
+            .map_err(|e| KeystoreError::StorageError(format!("parse: {}", e)))?;
+        meta.migrate()?;
+        Ok(meta)
+    }
+
+    /// Like `list`, but fails on the first key file that cannot be read or
+    /// parsed.
+    pub fn list_strict(&self) -> Result<Vec<KeyMetadata>, KeystoreError> {
+        self.list_files(true)
+    }
+
+    fn list_files(&self, strict: bool) -> Result<Vec<KeyMetadata>, KeystoreError> {
+        let mut keys = Vec::new();
+        let entries = std::fs::read_dir(&self.dir)
+            .map_err(|e| KeystoreError::StorageError(format!("readdir: {}", e)))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| KeystoreError::StorageError(format!("entry: {}", e)))?;
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match self.read_key_file(&path) {
+                Ok(meta) => keys.push(meta),
+                Err(e) if strict => {
+                    return Err(KeystoreError::StorageError(format!("{}: {}", path.display(), e)));
+                }
+                Err(e) => tracing::warn!(path = %path.display(), error = %e, "skipping unreadable key file"),
+            }
+        }
+        Ok(keys)
     }
 }
 
 impl StorageBackend for FileBackend {
     fn get(&self, id: &KeyId) -> Result<Option<KeyMetadata>, KeystoreError> {
-        let path = self.key_path(id);
+        let path = self.key_path(id)?;
         if !path.exists() {
             return Ok(None);
         }
-        self.read_key_file(&path).map(Some)
+        let meta = self.read_key_file(&path)?;
+        if !meta.id.ct_eq(id) {
+            return Err(KeystoreError::StorageError(format!(
+                "key file {} holds a different key ID", path.display()
+            )));
+        }
+        Ok(Some(meta))
     }
 
     fn put(&self, meta: &KeyMetadata) -> Result<(), KeystoreError> {
-        let path = self.key_path(&meta.id);
+        let path = self.key_path(&meta.id)?;
         let json = serde_json::to_string_pretty(meta)
             .map_err(|e| KeystoreError::StorageError(format!("serialize: {}", e)))?;
-        // Atomic write: write to temp, then rename
+        // Atomic write: write to temp, then rename. The lock keeps other
+        // writers off the shared temp file.
+        let _lock = self.write_lock()?;
         let tmp = path.with_extension("tmp");
         std::fs::write(&tmp, &json)
             .map_err(|e| KeystoreError::StorageError(format!("write: {}", e)))?;
@@ -146,7 +237,8 @@ impl StorageBackend for FileBackend {
     }
 
     fn delete(&self, id: &KeyId) -> Result<(), KeystoreError> {
-        let path = self.key_path(id);
+        let path = self.key_path(id)?;
+        let _lock = self.write_lock()?;
         if path.exists() {
             std::fs::remove_file(&path)
                 .map_err(|e| KeystoreError::StorageError(format!("delete: {}", e)))?;
@@ -154,18 +246,11 @@ impl StorageBackend for FileBackend {
         Ok(())
     }
 
+    /// Skips key files that cannot be read or parsed (crash mid-write,
+    /// tampering), logging a warning for each. Use
+    /// [`FileBackend::list_strict`] to fail on the first bad file instead.
     fn list(&self) -> Result<Vec<KeyMetadata>, KeystoreError> {
-        let mut keys = Vec::new();
-        let entries = std::fs::read_dir(&self.dir)
-            .map_err(|e| KeystoreError::StorageError(format!("readdir: {}", e)))?;
-        for entry in entries {
-            let entry = entry.map_err(|e| KeystoreError::StorageError(format!("entry: {}", e)))?;
-            let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) == Some("json") {
-                keys.push(self.read_key_file(&path)?);
-            }
-        }
-        Ok(keys)
+        self.list_files(false)
     }
 
     fn list_by_state(&self, state: KeyState) -> Result<Vec<KeyMetadata>, KeystoreError> {
@@ -176,7 +261,104 @@ impl StorageBackend for FileBackend {
         Ok(self
             .list()?
             .into_iter()
-            .filter(|k| k.parent_id.as_ref() == Some(parent_id))
+            .filter(|k| KeyId::ct_matches(k.parent_id.as_ref(), parent_id))
             .collect())
     }
 }
+
+// ---------------------------------------------------------------------------
+// Blob storage
+// ---------------------------------------------------------------------------
+
+/// Backend for ciphertexts kept by `Keystore::seal_and_store`.
+///
+/// Blobs are already encrypted and name their key and version, so a
+/// backend stores them as opaque records; it never sees key material.
+pub trait BlobStorageBackend: Send + Sync {
+    fn get_blob(&self, id: &BlobId) -> Result<Option<EncryptedBlob>, KeystoreError>;
+    fn put_blob(&self, id: &BlobId, blob: &EncryptedBlob) -> Result<(), KeystoreError>;
+    fn delete_blob(&self, id: &BlobId) -> Result<(), KeystoreError>;
+}
+
+/// In-memory blob storage (for testing and ephemeral use).
+#[derive(Default)]
+pub struct InMemoryBlobBackend {
+    blobs: RwLock<HashMap<BlobId, EncryptedBlob>>,
+}
+
+impl InMemoryBlobBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl BlobStorageBackend for InMemoryBlobBackend {
+    fn get_blob(&self, id: &BlobId) -> Result<Option<EncryptedBlob>, KeystoreError> {
+        Ok(self.blobs.read().unwrap().get(id).cloned())
+    }
+
+    fn put_blob(&self, id: &BlobId, blob: &EncryptedBlob) -> Result<(), KeystoreError> {
+        self.blobs.write().unwrap().insert(id.clone(), blob.clone());
+        Ok(())
+    }
+
+    fn delete_blob(&self, id: &BlobId) -> Result<(), KeystoreError> {
+        self.blobs.write().unwrap().remove(id);
+        Ok(())
+    }
+}
+
+/// File-based blob storage: `{blob_id}.json` per blob, written by atomic
+/// rename. Every blob has its own file, so no lock is taken.
+pub struct FileBlobBackend {
+    dir: PathBuf,
+}
+
+impl FileBlobBackend {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, KeystoreError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| KeystoreError::StorageError(format!("create dir: {}", e)))?;
+        Ok(Self { dir })
+    }
+
+    /// Refuses IDs that `BlobId::parse` would, so none can escape `dir`.
+    fn blob_path(&self, id: &BlobId) -> Result<PathBuf, KeystoreError> {
+        let id = BlobId::parse(id.as_str())?;
+        Ok(self.dir.join(format!("{}.json", id.as_str())))
+    }
+}
+
+impl BlobStorageBackend for FileBlobBackend {
+    fn get_blob(&self, id: &BlobId) -> Result<Option<EncryptedBlob>, KeystoreError> {
+        let path = self.blob_path(id)?;
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(KeystoreError::StorageError(format!("read: {}", e))),
+        };
+        serde_json::from_str(&data)
+            .map(Some)
+            .map_err(|e| KeystoreError::StorageError(format!("parse: {}", e)))
+    }
+
+    fn put_blob(&self, id: &BlobId, blob: &EncryptedBlob) -> Result<(), KeystoreError> {
+        let path = self.blob_path(id)?;
+        let json = serde_json::to_string(blob)
+            .map_err(|e| KeystoreError::StorageError(format!("serialize: {}", e)))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .map_err(|e| KeystoreError::StorageError(format!("write: {}", e)))?;
+        std::fs::rename(&tmp, &path)
+            .map_err(|e| KeystoreError::StorageError(format!("rename: {}", e)))
+    }
+
+    fn delete_blob(&self, id: &BlobId) -> Result<(), KeystoreError> {
+        match std::fs::remove_file(self.blob_path(id)?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(KeystoreError::StorageError(format!("delete: {}", e)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -17,6 +17,8 @@
 //! No existing KMS does this. AWS KMS and HashiCorp Vault use static policies.
 
 use crate::audit::{AuditAction, AuditEvent, AuditSinkSync};
+use crate::clock::{Clock, SystemClock};
+use crate::error::KeystoreError;
 use crate::policy::KeyPolicy;
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use serde::{Deserialize, Serialize};
@@ -137,11 +139,16 @@ pub enum ThreatEventKind {
     AuthFailure,
     /// Suspicious key enumeration or metadata probing.
     KeyEnumeration,
+    /// An operator declared a key compromised (`Keystore::compromise`).
+    KeyCompromise,
+    /// Encryption blocked by key policy (rotation overdue, usage limit).
+    PolicyViolation,
     /// Manual escalation by operator.
     ManualEscalation,
     /// Manual de-escalation by operator.
     ManualDeescalation,
-    /// Periodic heartbeat (resets decay timer, zero severity).
+    /// Periodic heartbeat (zero severity, not retained). Forces a recompute so
+    /// decay can lower the level on a quiet system.
     Heartbeat,
 }
 
@@ -179,7 +186,7 @@ pub struct SecurityMetrics {
 // ---------------------------------------------------------------------------
 
 /// Configuration for the threat assessor.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ThreatConfig {
     /// How far back to look when computing the threat score.
     pub window: Duration,
@@ -193,6 +200,25 @@ pub struct ThreatConfig {
     /// Score must drop below threshold × (1.0 - hysteresis) to de-escalate.
     /// Default 0.2 means score must drop 20% below the escalation threshold.
     pub hysteresis: f64,
+    /// Drop an ingested event whose kind and detail match one recorded this
+    /// recently, so one misbehaving client repeating the same failure counts
+    /// once per window. Events without a detail, manual
+    /// escalation/de-escalation, and events the keystore observed itself
+    /// (`record_observed`) are never deduplicated. Zero disables.
+    pub dedup_window: Duration,
+    /// Token-bucket limit on external ingestion (`record_event` /
+    /// `record_events`): up to `ingest_burst` events at once, refilled at
+    /// `ingest_rate` events per second. Events over the limit are dropped
+    /// and counted; manual escalation/de-escalation and `record_observed`
+    /// always get through. A zero burst disables the limit.
+    pub ingest_burst: u32,
+    /// Bucket refill rate, in events per second.
+    pub ingest_rate: f64,
+    /// Events recorded within this long of the last recompute are folded
+    /// into the next one instead of each rescoring the whole window. An
+    /// event that would escalate the level always recomputes at once.
+    /// Zero recomputes every time.
+    pub recompute_interval: Duration,
 }
 
 impl Default for ThreatConfig {
@@ -203,10 +229,148 @@ impl Default for ThreatConfig {
             thresholds: [5.0, 15.0, 30.0, 50.0],
             max_events: 10_000,
             hysteresis: 0.2,                    // 20% band for de-escalation
+            dedup_window: Duration::from_secs(5),
+            ingest_burst: 200,
+            ingest_rate: 50.0,
+            recompute_interval: Duration::from_millis(250),
         }
     }
 }
 
+impl ThreatConfig {
+    /// Check the invariants the assessor relies on: a non-empty window and
+    /// event cap, `decay_rate` in (0, 1], strictly increasing positive
+    /// thresholds, `hysteresis` in [0, 1), and a positive `ingest_rate`
+    /// when the ingestion limit is on.
+    pub fn validate(&self) -> Result<(), KeystoreError> {
+        let invalid = |msg: String| Err(KeystoreError::InvalidInput(format!("threat config: {}", msg)));
+
+        if self.window.is_zero() {
+            return invalid("window must be non-zero".into());
+        }
+        if self.max_events == 0 {
+            return invalid("max_events must be non-zero".into());
+        }
+        if !(self.decay_rate > 0.0 && self.decay_rate <= 1.0) {
+            return invalid(format!("decay_rate must be in (0, 1], got {}", self.decay_rate));
+        }
+        if !(self.thresholds[0] > 0.0 && self.thresholds.windows(2).all(|w| w[0] < w[1])) {
+            return invalid(format!("thresholds must be positive and strictly increasing, got {:?}", self.thresholds));
+        }
+        if !(0.0..1.0).contains(&self.hysteresis) {
+            return invalid(format!("hysteresis must be in [0, 1), got {}", self.hysteresis));
+        }
+        if self.ingest_burst > 0 && !(self.ingest_rate > 0.0 && self.ingest_rate.is_finite()) {
+            return invalid(format!("ingest_rate must be positive and finite, got {}", self.ingest_rate));
+        }
+        Ok(())
+    }
+}
+
+/// Serializable copy of the assessor's state, for persisting across restarts.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ThreatSnapshot {
+    /// Events still inside the rolling window.
+    pub events: Vec<ThreatEvent>,
+    /// Computed level at snapshot time.
+    pub current_level: ThreatLevel,
+    /// Manual override in effect (if any).
+    pub manual_override: Option<ThreatLevel>,
+    /// Level transition history.
+    pub level_history: Vec<(DateTime<Utc>, ThreatLevel, String)>,
+}
+
+/// Callback for threat level changes: `(old, new)`.
+pub type LevelChangeHook = Box<dyn Fn(ThreatLevel, ThreatLevel) + Send + Sync>;
+
+/// Level changes taken from a [`ThreatAssessor`], ready to be delivered to
+/// its hook once any lock around the assessor has been released.
+#[must_use = "call fire() to run the level change hook"]
+pub struct LevelChanges {
+    hook: Option<Arc<dyn Fn(ThreatLevel, ThreatLevel) + Send + Sync>>,
+    changes: Vec<(ThreatLevel, ThreatLevel)>,
+}
+
+impl LevelChanges {
+    /// The `(old, new)` transitions, oldest first.
+    pub fn changes(&self) -> &[(ThreatLevel, ThreatLevel)] {
+        &self.changes
+    }
+
+    /// Call the hook once per transition.
+    pub fn fire(self) {
+        if let Some(hook) = self.hook {
+            for (old, new) in self.changes {
+                hook(old, new);
+            }
+        }
+    }
+}
+
+/// One line of a [`ThreatEventLog`]: the event and where it left the assessor.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ThreatLogEntry {
+    #[serde(flatten)]
+    pub event: ThreatEvent,
+    /// Decayed score just after the event was recorded.
+    pub score: f64,
+    /// Effective level just after the event was recorded.
+    pub level: ThreatLevel,
+}
+
+/// Append-only JSONL record of every threat event an assessor accepts.
+///
+/// The scoring window forgets events as they decay; this log keeps them
+/// for post-incident analysis. It is separate from the audit chain and
+/// carries no integrity protection of its own.
+pub struct ThreatEventLog {
+    path: std::path::PathBuf,
+}
+
+impl ThreatEventLog {
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Append one entry. Failures are reported on stderr, as for
+    /// `FileAuditSink`, rather than failing the event.
+    pub fn append(&self, entry: &ThreatLogEntry) {
+        use std::io::Write;
+        let json = match serde_json::to_string(entry) {
+            Ok(json) => json,
+            Err(e) => return eprintln!("[threat] serialize error: {}", e),
+        };
+        let written = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", json));
+        if let Err(e) = written {
+            eprintln!("[threat] cannot append to {:?}: {}", self.path, e);
+        }
+    }
+
+    /// Read every entry back, oldest first. A missing file yields an
+    /// empty log; a malformed line is `InvalidData` with its line number.
+    pub fn read_entries(&self) -> std::io::Result<Vec<ThreatLogEntry>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("line {}: {}", n + 1, e))
+                })
+            })
+            .collect()
+    }
+}
+
 /// The adaptive threat assessment engine.
 ///
 /// Ingests events, computes a rolling threat score with time-decay,
@@ -221,18 +385,54 @@ pub struct ThreatAssessor {
     audit: Option<Arc<dyn AuditSinkSync>>,
     /// History of level transitions.
     level_history: Vec<(DateTime<Utc>, ThreatLevel, String)>,
+    /// Time source for decay and window pruning.
+    clock: Arc<dyn Clock>,
+    /// Ingestion tokens left, and when the bucket was last refilled.
+    tokens: f64,
+    tokens_at: Option<DateTime<Utc>>,
+    /// Events refused by the ingestion limit since startup.
+    dropped_events: u64,
+    /// When the level was last recomputed, and the score then plus the
+    /// severity recorded since (an upper bound: decay only lowers it).
+    last_recompute: Option<DateTime<Utc>>,
+    pending_score: f64,
+    /// Hook for level changes, and the transitions it has yet to see.
+    level_hook: Option<Arc<dyn Fn(ThreatLevel, ThreatLevel) + Send + Sync>>,
+    pending_level_changes: Vec<(ThreatLevel, ThreatLevel)>,
+    /// Permanent record of accepted events.
+    event_log: Option<ThreatEventLog>,
 }
 
 impl ThreatAssessor {
     pub fn new(config: ThreatConfig) -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
         Self {
-            config,
             events: VecDeque::new(),
             current_level: ThreatLevel::Low,
             manual_override: None,
             audit: None,
-            level_history: vec![(Utc::now(), ThreatLevel::Low, "initialized".into())],
+            level_history: vec![(clock.now(), ThreatLevel::Low, "initialized".into())],
+            clock,
+            tokens: config.ingest_burst as f64,
+            tokens_at: None,
+            dropped_events: 0,
+            last_recompute: None,
+            pending_score: 0.0,
+            level_hook: None,
+            pending_level_changes: Vec::new(),
+            event_log: None,
+            config,
+        }
+    }
+
+    /// Use a custom time source (e.g. `MockClock` in tests). The initial
+    /// history entry is restamped so it is in order with later events.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        if let [(at, _, _)] = self.level_history.as_mut_slice() {
+            *at = clock.now();
         }
+        self.clock = clock;
+        self
     }
 
     pub fn with_audit(mut self, audit: Arc<dyn AuditSinkSync>) -> Self {
@@ -240,10 +440,155 @@ impl ThreatAssessor {
         self
     }
 
-    /// Record a threat event and recompute the threat level.
-    pub fn record_event(&mut self, event: ThreatEvent) {
+    /// Append every accepted event to `log` with the score and level it
+    /// left behind. Heartbeats, duplicates and events refused by the
+    /// ingestion limit are not logged.
+    pub fn with_event_log(mut self, log: ThreatEventLog) -> Self {
+        self.set_event_log(log);
+        self
+    }
+
+    /// Like [`with_event_log`](Self::with_event_log), on a live assessor.
+    pub fn set_event_log(&mut self, log: ThreatEventLog) {
+        self.event_log = Some(log);
+    }
+
+    /// Register a callback for level changes, replacing any previous one.
+    ///
+    /// `recompute_level` queues each `(old, new)` transition rather than
+    /// calling out while the assessor is borrowed; deliver them with
+    /// [`take_level_changes`](Self::take_level_changes)`.fire()` after
+    /// releasing whatever lock guards the assessor. `Keystore` does this
+    /// after every call that can move the level.
+    pub fn on_level_change(&mut self, hook: LevelChangeHook) {
+        self.level_hook = Some(Arc::from(hook));
+    }
+
+    /// Take the transitions queued since the last call, with the hook that
+    /// should see them.
+    pub fn take_level_changes(&mut self) -> LevelChanges {
+        LevelChanges {
+            hook: self.level_hook.clone(),
+            changes: std::mem::take(&mut self.pending_level_changes),
+        }
+    }
+
+    /// Current configuration.
+    pub fn config(&self) -> &ThreatConfig {
+        &self.config
+    }
+
+    /// Replace the configuration at runtime and re-evaluate the level under
+    /// it. Retained events are kept; a shorter window prunes them now.
+    pub fn set_config(&mut self, config: ThreatConfig) -> Result<(), KeystoreError> {
+        config.validate()?;
+        self.tokens = self.tokens.min(config.ingest_burst as f64);
+        self.config = config;
+        self.prune_old_events();
+        self.recompute_level();
+        Ok(())
+    }
+
+    /// Ingest a threat event reported from outside (`POST /api/threat/event`)
+    /// and recompute the threat level (or fold it into the next recompute,
+    /// see `ThreatConfig::recompute_interval`).
+    ///
+    /// Duplicates within `dedup_window` are dropped before the ingestion
+    /// limit is consulted, so repeats don't use up tokens. `Heartbeat`
+    /// events carry no severity and are not stored; they only prune the
+    /// window and re-apply decay. Returns `false` if the event was refused
+    /// by the ingestion limit.
+    pub fn record_event(&mut self, event: ThreatEvent) -> bool {
         // Handle manual escalation/de-escalation
         match event.kind {
+            ThreatEventKind::Heartbeat => {
+                self.tick();
+                return true;
+            }
+            ThreatEventKind::ManualEscalation | ThreatEventKind::ManualDeescalation => {}
+            _ if self.is_duplicate(&event) => return true,
+            _ if !self.take_token() => return false,
+            _ => {}
+        }
+        self.push_event(event);
+        true
+    }
+
+    /// Record an event the keystore, or the API in front of it, observed
+    /// itself: a compromise, a failed decryption, a rejected API key.
+    ///
+    /// These are the signals the assessor exists for, so unlike
+    /// `record_event` they are never deduplicated or refused by the
+    /// ingestion limit; a client flooding the ingestion endpoints can't
+    /// suppress them.
+    pub fn record_observed(&mut self, event: ThreatEvent) {
+        if event.kind == ThreatEventKind::Heartbeat {
+            self.tick();
+            return;
+        }
+        self.push_event(event);
+    }
+
+    /// Store an accepted event, recomputing if it is manual, escalates, or
+    /// a recompute is due.
+    fn push_event(&mut self, event: ThreatEvent) {
+        let manual = self.apply_manual_override(&event.kind);
+        let logged = self.event_log.as_ref().map(|_| event.clone());
+        self.pending_score += event.severity;
+        self.events.push_back(event);
+        if self.events.len() > self.config.max_events {
+            self.events.pop_front();
+        }
+
+        if manual || self.recompute_due() || self.level_for_score(self.pending_score) > self.current_level {
+            self.tick();
+        }
+        if let Some(event) = logged {
+            self.log_events(vec![event]);
+        }
+    }
+
+    /// Ingest a batch of externally reported events (`POST
+    /// /api/threat/events`) with a single recompute.
+    ///
+    /// Duplicates are dropped as in `record_event`, including repeats
+    /// within the batch itself. Manual escalation/de-escalation set or
+    /// clear the override exactly as `record_event` does, recomputing on
+    /// the spot so a later one in the batch steps from the new level.
+    /// Returns how many events the ingestion limit refused; manual events
+    /// are never limited.
+    pub fn record_events(&mut self, events: Vec<ThreatEvent>) -> usize {
+        let mut refused = 0;
+        let mut logged = Vec::new();
+        for event in events {
+            match event.kind {
+                ThreatEventKind::Heartbeat => continue,
+                ThreatEventKind::ManualEscalation | ThreatEventKind::ManualDeescalation => {}
+                _ if self.is_duplicate(&event) => continue,
+                _ if !self.take_token() => {
+                    refused += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            let manual = self.apply_manual_override(&event.kind);
+            if self.event_log.is_some() {
+                logged.push(event.clone());
+            }
+            self.events.push_back(event);
+            if manual {
+                self.tick();
+            }
+        }
+        self.tick();
+        self.log_events(logged);
+        refused
+    }
+
+    /// Set or clear `manual_override` for a manual escalation or
+    /// de-escalation. Returns whether `kind` was one of those.
+    fn apply_manual_override(&mut self, kind: &ThreatEventKind) -> bool {
+        match kind {
             ThreatEventKind::ManualEscalation => {
                 let new_level = match self.current_level {
                     ThreatLevel::Low => ThreatLevel::Guarded,
@@ -252,27 +597,35 @@ impl ThreatAssessor {
                     ThreatLevel::High | ThreatLevel::Critical => ThreatLevel::Critical,
                 };
                 self.manual_override = Some(new_level);
+                true
             }
             ThreatEventKind::ManualDeescalation => {
                 self.manual_override = None; // Remove override, let computed level take over
+                true
             }
-            _ => {}
+            _ => false,
         }
-
-        self.events.push_back(event);
-
-        // Prune old events
-        self.prune_old_events();
-
-        // Recompute
-        self.recompute_level();
     }
 
-    /// Record a batch of events.
-    pub fn record_events(&mut self, events: Vec<ThreatEvent>) {
+    /// Append `events` to the event log, all with the current score and level.
+    fn log_events(&self, events: Vec<ThreatEvent>) {
+        let Some(log) = &self.event_log else { return };
+        let (score, level) = (self.compute_score(), self.current_level());
         for event in events {
-            self.events.push_back(event);
+            log.append(&ThreatLogEntry { event, score, level });
         }
+    }
+
+    /// Events refused by the ingestion limit since startup.
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped_events
+    }
+
+    /// Re-evaluate the level against the decayed score without adding an event.
+    ///
+    /// `current_level` is cached, so call this periodically to let a quiet
+    /// system de-escalate.
+    pub fn tick(&mut self) {
         self.prune_old_events();
         self.recompute_level();
     }
@@ -302,6 +655,27 @@ impl ThreatAssessor {
         self.events.iter().rev().take(n).collect()
     }
 
+    /// Capture the assessor's state for persistence.
+    pub fn snapshot(&self) -> ThreatSnapshot {
+        ThreatSnapshot {
+            events: self.events.iter().cloned().collect(),
+            current_level: self.current_level,
+            manual_override: self.manual_override,
+            level_history: self.level_history.clone(),
+        }
+    }
+
+    /// Restore state from a snapshot, then prune and recompute against the
+    /// current clock (events that aged out while persisted are dropped).
+    pub fn restore(&mut self, snapshot: ThreatSnapshot) {
+        self.events = snapshot.events.into();
+        self.current_level = snapshot.current_level;
+        self.manual_override = snapshot.manual_override;
+        self.level_history = snapshot.level_history;
+        self.prune_old_events();
+        self.recompute_level();
+    }
+
     /// Compute comprehensive security metrics for the dashboard.
     pub fn security_metrics(&self, total_keys: usize, compliant_keys: usize) -> SecurityMetrics {
         let level = self.current_level();
@@ -337,7 +711,7 @@ impl ThreatAssessor {
             .clamp(0.0, 100.0);
 
         let time_since_last = self.events.back().map(|e| {
-            let elapsed = Utc::now() - e.timestamp;
+            let elapsed = self.clock.now() - e.timestamp;
             elapsed.to_std().unwrap_or(Duration::ZERO)
         });
 
@@ -360,7 +734,7 @@ impl ThreatAssessor {
     // -----------------------------------------------------------------------
 
     fn compute_score(&self) -> f64 {
-        let now = Utc::now();
+        let now = self.clock.now();
         let mut score = 0.0;
 
         for event in &self.events {
@@ -374,48 +748,11 @@ impl ThreatAssessor {
 
     fn recompute_level(&mut self) {
         let score = self.compute_score();
-        let new_level = if let Some(manual) = self.manual_override {
-            manual
-        } else {
-            // Compute the level from raw score (used for escalation)
-            let raw_level = if score >= self.config.thresholds[3] {
-                ThreatLevel::Critical
-            } else if score >= self.config.thresholds[2] {
-                ThreatLevel::High
-            } else if score >= self.config.thresholds[1] {
-                ThreatLevel::Elevated
-            } else if score >= self.config.thresholds[0] {
-                ThreatLevel::Guarded
-            } else {
-                ThreatLevel::Low
-            };
-
-            // Hysteresis: de-escalation requires score to drop further
-            // than the escalation threshold. This prevents oscillation
-            // when the score hovers near a boundary.
-            let h = self.config.hysteresis;
-            let de_escalation_level = if score >= self.config.thresholds[3] * (1.0 - h) {
-                ThreatLevel::Critical
-            } else if score >= self.config.thresholds[2] * (1.0 - h) {
-                ThreatLevel::High
-            } else if score >= self.config.thresholds[1] * (1.0 - h) {
-                ThreatLevel::Elevated
-            } else if score >= self.config.thresholds[0] * (1.0 - h) {
-                ThreatLevel::Guarded
-            } else {
-                ThreatLevel::Low
-            };
-
-            if raw_level > self.current_level {
-                // Escalating — use raw thresholds (respond fast)
-                raw_level
-            } else if de_escalation_level < self.current_level {
-                // De-escalating — use relaxed thresholds (respond slowly)
-                de_escalation_level
-            } else {
-                // In the hysteresis band — hold current level
-                self.current_level
-            }
+        self.last_recompute = Some(self.clock.now());
+        self.pending_score = score;
+        let new_level = match self.manual_override {
+            Some(manual) => manual,
+            None => self.level_for_score(score),
         };
 
         if new_level != self.current_level {
@@ -427,7 +764,10 @@ impl ThreatAssessor {
                 new_level.label(),
                 old.label()
             );
-            self.level_history.push((Utc::now(), new_level, reason.clone()));
+            self.level_history.push((self.clock.now(), new_level, reason.clone()));
+            if self.level_hook.is_some() {
+                self.pending_level_changes.push((old, new_level));
+            }
 
             if let Some(audit) = &self.audit {
                 audit.record(
@@ -440,10 +780,98 @@ impl ThreatAssessor {
         }
     }
 
+    /// Map a score to a level relative to the cached one: escalate on the
+    /// raw thresholds, de-escalate only past the hysteresis band.
+    fn level_for_score(&self, score: f64) -> ThreatLevel {
+        let raw_level = if score >= self.config.thresholds[3] {
+            ThreatLevel::Critical
+        } else if score >= self.config.thresholds[2] {
+            ThreatLevel::High
+        } else if score >= self.config.thresholds[1] {
+            ThreatLevel::Elevated
+        } else if score >= self.config.thresholds[0] {
+            ThreatLevel::Guarded
+        } else {
+            ThreatLevel::Low
+        };
+
+        // Hysteresis: de-escalation requires score to drop further
+        // than the escalation threshold. This prevents oscillation
+        // when the score hovers near a boundary.
+        let h = self.config.hysteresis;
+        let de_escalation_level = if score >= self.config.thresholds[3] * (1.0 - h) {
+            ThreatLevel::Critical
+        } else if score >= self.config.thresholds[2] * (1.0 - h) {
+            ThreatLevel::High
+        } else if score >= self.config.thresholds[1] * (1.0 - h) {
+            ThreatLevel::Elevated
+        } else if score >= self.config.thresholds[0] * (1.0 - h) {
+            ThreatLevel::Guarded
+        } else {
+            ThreatLevel::Low
+        };
+
+        if raw_level > self.current_level {
+            // Escalating — use raw thresholds (respond fast)
+            raw_level
+        } else if de_escalation_level < self.current_level {
+            // De-escalating — use relaxed thresholds (respond slowly)
+            de_escalation_level
+        } else {
+            // In the hysteresis band — hold current level
+            self.current_level
+        }
+    }
+
+    /// Whether a retained event with the same kind and detail falls within
+    /// `dedup_window` before `event`.
+    fn is_duplicate(&self, event: &ThreatEvent) -> bool {
+        if self.config.dedup_window.is_zero()
+            || event.detail.is_none()
+            || matches!(event.kind, ThreatEventKind::ManualEscalation | ThreatEventKind::ManualDeescalation)
+        {
+            return false;
+        }
+        let since = event.timestamp
+            - ChronoDuration::from_std(self.config.dedup_window).unwrap_or(ChronoDuration::MAX);
+        self.events
+            .iter()
+            .rev()
+            .take_while(|e| e.timestamp >= since)
+            .any(|e| e.kind == event.kind && e.detail == event.detail)
+    }
+
+    /// Whether enough time has passed since the last recompute to do one now.
+    fn recompute_due(&self) -> bool {
+        let interval = ChronoDuration::from_std(self.config.recompute_interval).unwrap_or(ChronoDuration::MAX);
+        self.config.recompute_interval.is_zero()
+            || self.last_recompute.map_or(true, |at| self.clock.now() - at >= interval)
+    }
+
+    /// Spend one ingestion token, refilling the bucket for the time elapsed.
+    fn take_token(&mut self) -> bool {
+        if self.config.ingest_burst == 0 {
+            return true;
+        }
+        let now = self.clock.now();
+        if let Some(at) = self.tokens_at {
+            let elapsed = (now - at).to_std().unwrap_or(Duration::ZERO).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * self.config.ingest_rate).min(self.config.ingest_burst as f64);
+        }
+        self.tokens_at = Some(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            self.dropped_events += 1;
+            false
+        }
+    }
+
     fn prune_old_events(&mut self) {
-        let cutoff = Utc::now()
+        let cutoff = self.clock.now()
             - ChronoDuration::from_std(self.config.window).unwrap_or(ChronoDuration::MAX);
-        while self.events.front().map_or(false, |e| e.timestamp < cutoff) {
+        while self.events.front().is_some_and(|e| e.timestamp < cutoff) {
             self.events.pop_front();
         }
         while self.events.len() > self.config.max_events {
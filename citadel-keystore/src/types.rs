@@ -1,14 +1,25 @@
-//! Core types: KeyId, KeyType, KeyState, KeyMetadata, KeyVersion.
+//! Core types: KeyId, BlobId, KeyType, KeyState, KeyMetadata, KeyVersion.
 
+use crate::error::{KeyIdError, KeystoreError};
+use crate::policy::KeyPolicy;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::Duration;
 
 // ---------------------------------------------------------------------------
 // Key identifiers
 // ---------------------------------------------------------------------------
 
-/// Unique key identifier (hex-encoded random bytes).
+/// Unique key identifier (32 hex chars: random or a public-key fingerprint).
+///
+/// `==` is ordinary string equality, and primary lookups (the in-memory
+/// map, the file backend's path) are not constant-time either: an ID names
+/// a key but grants nothing, and those lookups already reveal existence
+/// through their result. Scans that test a caller-supplied ID against
+/// every stored ID (`list_by_parent`, derived-sibling checks) and the file
+/// backend's check that a key file holds the ID it was opened for use
+/// `ct_eq`, so a fingerprint cannot be probed byte by byte through timing.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct KeyId(String);
 
@@ -20,16 +31,57 @@ impl KeyId {
         Self(hex::encode(bytes))
     }
 
+    /// Derive a stable KeyId from a public key: the first 16 bytes of its
+    /// SHA-256, hex-encoded. Importing the same key material always yields
+    /// the same ID, which makes imports idempotent.
+    pub fn from_public_key(pk: &citadel_envelope::PublicKey) -> Self {
+        Self(citadel_keystore_core::KeyId::from_public_key(pk).to_string())
+    }
+
     /// Create from a specific string (for testing/deterministic use).
+    ///
+    /// Accepts anything; use [`KeyId::parse`] for IDs from external input.
     pub fn new(id: impl Into<String>) -> Self {
         Self(id.into())
     }
 
+    /// Validate an ID from external input: 1 to [`MAX_KEY_ID_LEN`]
+    /// characters from `[a-z0-9_-]`. Every generated ID qualifies, and no
+    /// accepted ID can name a path outside the file backend's directory.
+    pub fn parse(s: &str) -> Result<Self, KeyIdError> {
+        if s.is_empty() {
+            return Err(KeyIdError::Empty);
+        }
+        if s.len() > MAX_KEY_ID_LEN {
+            return Err(KeyIdError::TooLong(s.len()));
+        }
+        if let Some(c) = s.chars().find(|c| !matches!(c, 'a'..='z' | '0'..='9' | '_' | '-')) {
+            return Err(KeyIdError::InvalidChar(c));
+        }
+        Ok(Self(s.to_string()))
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Constant-time equality. Only the length is not hidden, and every
+    /// generated ID has the same length.
+    pub fn ct_eq(&self, other: &KeyId) -> bool {
+        use subtle::ConstantTimeEq;
+        let (a, b) = (self.0.as_bytes(), other.0.as_bytes());
+        a.len() == b.len() && bool::from(a.ct_eq(b))
+    }
+
+    /// Constant-time match of an optional stored ID (e.g. `parent_id`).
+    pub(crate) fn ct_matches(stored: Option<&KeyId>, wanted: &KeyId) -> bool {
+        stored.is_some_and(|id| id.ct_eq(wanted))
+    }
 }
 
+/// Longest ID accepted by [`KeyId::parse`].
+pub const MAX_KEY_ID_LEN: usize = 64;
+
 impl fmt::Display for KeyId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -38,13 +90,23 @@ impl fmt::Display for KeyId {
 
 use rand_core::RngCore;
 
-/// Policy identifier.
+/// Identifier of a blob stored with `Keystore::seal_and_store`
+/// (32 random hex chars).
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct PolicyId(String);
+pub struct BlobId(String);
 
-impl PolicyId {
-    pub fn new(id: impl Into<String>) -> Self {
-        Self(id.into())
+impl BlobId {
+    /// Create a new random BlobId.
+    pub fn generate() -> Self {
+        Self(KeyId::generate().0)
+    }
+
+    /// Validate an ID from external input, with the same rules as
+    /// [`KeyId::parse`].
+    pub fn parse(s: &str) -> Result<Self, KeystoreError> {
+        KeyId::parse(s)
+            .map(|id| Self(id.0))
+            .map_err(|e| KeystoreError::InvalidInput(e.to_string().replacen("key id", "blob id", 1)))
     }
 
     pub fn as_str(&self) -> &str {
@@ -52,108 +114,39 @@ impl PolicyId {
     }
 }
 
-impl fmt::Display for PolicyId {
+impl fmt::Display for BlobId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
     }
 }
 
-// ---------------------------------------------------------------------------
-// Key classification
-// ---------------------------------------------------------------------------
+/// Policy identifier.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PolicyId(String);
+
+impl PolicyId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
 
-/// Position in the key hierarchy.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub enum KeyType {
-    /// Root key — offline, protects the entire hierarchy.
-    Root,
-    /// Domain key — per-tenant or per-environment.
-    Domain,
-    /// Key-encrypting key — wraps DEKs.
-    KeyEncrypting,
-    /// Data-encrypting key — directly encrypts user data.
-    DataEncrypting,
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
-impl fmt::Display for KeyType {
+impl fmt::Display for PolicyId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            KeyType::Root => write!(f, "ROOT"),
-            KeyType::Domain => write!(f, "DOMAIN"),
-            KeyType::KeyEncrypting => write!(f, "KEK"),
-            KeyType::DataEncrypting => write!(f, "DEK"),
-        }
+        write!(f, "{}", self.0)
     }
 }
 
 // ---------------------------------------------------------------------------
-// Key lifecycle state machine
+// Key classification and lifecycle state machine
 // ---------------------------------------------------------------------------
 
-/// Key lifecycle state.
-///
-/// ```text
-/// PENDING → ACTIVE ↔ ROTATED → EXPIRED → DESTROYED
-///             │
-///             └──→ REVOKED
-/// ```
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub enum KeyState {
-    /// Generated but not yet activated.
-    Pending,
-    /// Active — can encrypt and decrypt.
-    Active,
-    /// Rotated — superseded by a new version. Can still decrypt (grace period).
-    Rotated,
-    /// Expired — can no longer encrypt or decrypt.
-    Expired,
-    /// Revoked — emergency deactivation. Cannot be reactivated.
-    Revoked,
-    /// Destroyed — key material has been purged.
-    Destroyed,
-}
-
-impl KeyState {
-    /// Whether this state allows encryption.
-    pub fn can_encrypt(&self) -> bool {
-        matches!(self, KeyState::Active)
-    }
-
-    /// Whether this state allows decryption.
-    pub fn can_decrypt(&self) -> bool {
-        matches!(self, KeyState::Active | KeyState::Rotated)
-    }
-
-    /// Valid transitions from this state.
-    pub fn valid_transitions(&self) -> &[KeyState] {
-        match self {
-            KeyState::Pending => &[KeyState::Active, KeyState::Destroyed],
-            KeyState::Active => &[KeyState::Rotated, KeyState::Revoked, KeyState::Expired],
-            KeyState::Rotated => &[KeyState::Expired],
-            KeyState::Expired => &[KeyState::Destroyed],
-            KeyState::Revoked => &[KeyState::Destroyed],
-            KeyState::Destroyed => &[],
-        }
-    }
-
-    /// Check if transitioning to `target` is valid.
-    pub fn can_transition_to(&self, target: KeyState) -> bool {
-        self.valid_transitions().contains(&target)
-    }
-}
-
-impl fmt::Display for KeyState {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            KeyState::Pending => write!(f, "PENDING"),
-            KeyState::Active => write!(f, "ACTIVE"),
-            KeyState::Rotated => write!(f, "ROTATED"),
-            KeyState::Expired => write!(f, "EXPIRED"),
-            KeyState::Revoked => write!(f, "REVOKED"),
-            KeyState::Destroyed => write!(f, "DESTROYED"),
-        }
-    }
-}
+/// Shared with `citadel-keystore-core`, so the `no_std` keystore enforces
+/// the same transitions.
+pub use citadel_keystore_core::{KeyState, KeyType};
 
 // ---------------------------------------------------------------------------
 // Key version (tracks rotation history)
@@ -171,15 +164,49 @@ pub struct KeyVersion {
     /// Serialized secret key bytes (hex), encrypted by parent KEK.
     /// For Root keys, this is wrapped externally.
     pub secret_key_hex: String,
+    /// Encryptions performed with this version. `None` in metadata written
+    /// before per-version counting; read it through
+    /// [`KeyMetadata::current_usage`], which falls back to the key total.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage_count: Option<u64>,
+}
+
+/// One row of a key's version history (`Keystore::key_versions`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionInfo {
+    /// The key record holding this version; rotation moves new versions
+    /// to a successor key.
+    pub key_id: KeyId,
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    /// This is the version `encrypt` uses: the current version of an
+    /// ACTIVE key that has not been superseded.
+    pub is_current: bool,
+    /// Blobs sealed under this version can still be opened: the key's
+    /// state allows decryption and the secret has not been destroyed.
+    pub decryptable: bool,
 }
 
 // ---------------------------------------------------------------------------
 // Key metadata
 // ---------------------------------------------------------------------------
 
+/// Schema version written by this release. Bump it, and add a step to
+/// [`KeyMetadata::migrate`], whenever stored metadata changes meaning.
+///
+/// - 0: no `schema_version` field; per-version usage counts absent.
+/// - 1: current version carries its own `usage_count`.
+pub const KEY_METADATA_SCHEMA_VERSION: u32 = 1;
+
+/// Placeholder written over a version's key material by `Keystore::destroy`.
+pub(crate) const DESTROYED_KEY_MATERIAL: &str = "DESTROYED";
+
 /// Complete metadata for a managed key.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct KeyMetadata {
+    /// Layout version of this record (0 when the field is absent).
+    #[serde(default)]
+    pub schema_version: u32,
     /// Unique identifier.
     pub id: KeyId,
     /// Human-readable name.
@@ -190,8 +217,22 @@ pub struct KeyMetadata {
     pub state: KeyState,
     /// Associated policy (if any).
     pub policy_id: Option<PolicyId>,
-    /// Parent key in the hierarchy (None for root).
+    /// Parent key in the hierarchy (None for root). Rotation keeps it;
+    /// the key this one was rotated from is `supersedes`.
     pub parent_id: Option<KeyId>,
+    /// Set when the key material was derived from version N of the
+    /// parent's secret key (`Keystore::generate_derived`) rather than
+    /// generated randomly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derived_from_version: Option<u32>,
+    /// The key this one replaced in `Keystore::rotate`. `decrypt` follows
+    /// this link back when a blob names a version this record lacks.
+    /// Unset on keys rotated before the link was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<KeyId>,
+    /// The key minted to replace this one, once rotated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub superseded_by: Option<KeyId>,
     /// When this key was first created.
     pub created_at: DateTime<Utc>,
     /// When the state last changed.
@@ -202,26 +243,133 @@ pub struct KeyMetadata {
     pub rotated_at: Option<DateTime<Utc>>,
     /// When the key was revoked.
     pub revoked_at: Option<DateTime<Utc>>,
+    /// When the key was declared compromised.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compromised_at: Option<DateTime<Utc>>,
+    /// When the key expired. Unset on keys expired before it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expired_at: Option<DateTime<Utc>>,
     /// When the key was destroyed.
     pub destroyed_at: Option<DateTime<Utc>>,
     /// All versions (current + historical).
     pub versions: Vec<KeyVersion>,
     /// Current (latest) version number.
     pub current_version: u32,
-    /// Number of times this key has been used for encryption.
+    /// Number of times this key has been used for encryption, across all
+    /// versions. Policy limits use the per-version count instead (see
+    /// [`KeyMetadata::current_usage`]).
+    ///
+    /// Rotation resets usage: `rotate` mints a successor key that starts
+    /// at 0, while this key keeps its final count for auditing.
     pub usage_count: u64,
     /// Arbitrary metadata tags.
     pub tags: std::collections::HashMap<String, String>,
+    /// Encryption contexts this key may be used with (empty = unrestricted).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_contexts: Vec<Vec<u8>>,
 }
 
 impl KeyMetadata {
+    /// Upgrade a record read from storage to [`KEY_METADATA_SCHEMA_VERSION`]
+    /// in memory. Storage backends call this on every read; the upgraded
+    /// form is persisted by the next write.
+    ///
+    /// Errors if the record was written by a newer release.
+    pub fn migrate(&mut self) -> Result<(), KeystoreError> {
+        if self.schema_version > KEY_METADATA_SCHEMA_VERSION {
+            return Err(KeystoreError::StorageError(format!(
+                "key {} has schema version {} (this build supports up to {})",
+                self.id, self.schema_version, KEY_METADATA_SCHEMA_VERSION
+            )));
+        }
+        if self.schema_version < 1 {
+            // v0 only counted per key; attribute the total to the current version.
+            let total = self.usage_count;
+            let current = self.current_version;
+            if let Some(v) = self.versions.iter_mut().find(|v| v.version == current) {
+                v.usage_count.get_or_insert(total);
+            }
+            self.schema_version = 1;
+        }
+        Ok(())
+    }
+
+    /// Whether `context` passes this key's context allow-list.
+    pub fn allows_context(&self, context: &[u8]) -> bool {
+        self.allowed_contexts.is_empty() || self.allowed_contexts.iter().any(|c| c == context)
+    }
+
     /// Get the current (latest) version.
     pub fn current_key_version(&self) -> Option<&KeyVersion> {
         self.versions.iter().find(|v| v.version == self.current_version)
     }
 
+    /// Every version of this record, oldest first, with whether it is
+    /// current and still decryptable. `Keystore::key_versions` covers the
+    /// whole rotation lineage.
+    pub fn version_history(&self) -> Vec<VersionInfo> {
+        let can_decrypt = self.state.can_decrypt();
+        let encryptable = self.state == KeyState::Active && self.superseded_by.is_none();
+        self.versions
+            .iter()
+            .map(|v| VersionInfo {
+                key_id: self.id.clone(),
+                version: v.version,
+                created_at: v.created_at,
+                is_current: encryptable && v.version == self.current_version,
+                decryptable: can_decrypt && v.secret_key_hex != DESTROYED_KEY_MATERIAL,
+            })
+            .collect()
+    }
+
+    /// Encryptions performed with the current version — what
+    /// `max_usage_count` is checked against. Metadata from before
+    /// per-version counting reports the whole-key total.
+    pub fn current_usage(&self) -> u64 {
+        self.current_key_version()
+            .and_then(|v| v.usage_count)
+            .unwrap_or(self.usage_count)
+    }
+
+    /// Count one encryption against the current version and the key total.
+    pub(crate) fn record_usage(&mut self) {
+        let current = self.current_usage();
+        let version = self.current_version;
+        if let Some(v) = self.versions.iter_mut().find(|v| v.version == version) {
+            v.usage_count = Some(current + 1);
+        }
+        self.usage_count += 1;
+    }
+
     /// Duration since activation (if activated).
     pub fn age(&self) -> Option<chrono::Duration> {
         self.activated_at.map(|a| Utc::now() - a)
     }
+
+    /// Time left before this key must expire under `policy`: the rest of
+    /// the rotation grace period for a ROTATED key, or of `max_lifetime`
+    /// for an ACTIVE one. `Some(ZERO)` once it is due; `None` in any other
+    /// state or when the policy sets no lifetime. Pass the threat-adapted
+    /// policy to match what `Keystore::expire_due_keys` will do.
+    pub fn time_until_expiry(&self, policy: &KeyPolicy) -> Option<Duration> {
+        self.time_until_expiry_at(policy, Utc::now())
+    }
+
+    /// [`time_until_expiry`](Self::time_until_expiry) as of `now`.
+    pub fn time_until_expiry_at(&self, policy: &KeyPolicy, now: DateTime<Utc>) -> Option<Duration> {
+        let (since, span) = self.expiry_span(policy.rotation_grace_period, policy.max_lifetime)?;
+        let elapsed = (now - since).to_std().unwrap_or(Duration::ZERO);
+        Some(span.saturating_sub(elapsed))
+    }
+
+    /// When the expiry clock started and how long it runs: `grace` from
+    /// rotation for a ROTATED key, `max_lifetime` from activation for an
+    /// ACTIVE one.
+    pub(crate) fn expiry_span(&self, grace: Duration, max_lifetime: Option<Duration>) -> Option<(DateTime<Utc>, Duration)> {
+        match self.state {
+            KeyState::Rotated => Some((self.rotated_at?, grace)),
+            KeyState::Active => Some((self.activated_at?, max_lifetime?)),
+            _ => None,
+        }
+    }
 }